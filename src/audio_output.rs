@@ -0,0 +1,258 @@
+// Output device selection for the cpal-driven engines.
+//
+// reSID, SIDLite and the U64 audio stream all open their cpal stream
+// through `open()`, which applies the device / sample rate / latency
+// chosen in Settings → Audio. Anything that can't be honoured — device
+// unplugged, rate not supported, fixed buffer refused by the driver —
+// falls back to the system default with a log line rather than failing,
+// so a stale setting never leaves the player mute.
+//
+// Devices are matched by the name cpal reports. On Linux that is the ALSA
+// PCM name, which is also how PipeWire ("pipewire"), JACK ("jack") and
+// PulseAudio ("pulse") sinks show up.
+//
+// The engines' ring buffers follow the same choice via `RingSizes`: the
+// silence prefill is the latency target and the cap leaves a fixed
+// headroom on top, at whatever rate the device ended up running.
+
+use cpal::traits::{DeviceTrait, HostTrait};
+
+/// Sample rates offered in Settings. 0 in the config = device default.
+pub const SAMPLE_RATES: [u32; 3] = [44_100, 48_000, 96_000];
+
+/// Ring depth when no latency target is set (the historical ~150 ms).
+/// Has to outlast the player's reset → first frame gap (sleep 50 ms +
+/// setup), or the first callback underruns.
+const DEFAULT_LATENCY_MS: u32 = 150;
+
+/// Lowest latency target honoured; below this frame jitter alone
+/// drains the ring.
+const MIN_LATENCY_MS: u32 = 10;
+
+/// Ring capacity beyond the prefill before new samples are dropped.
+const HEADROOM_MS: u32 = 100;
+
+/// The user's output choice, read from the config when an engine opens.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutputSettings {
+    /// Device name as reported by cpal. `None` = system default.
+    pub device: Option<String>,
+    /// Requested rate in Hz. 0 = the device's default rate.
+    pub sample_rate: u32,
+    /// Latency target in ms. 0 = driver-chosen buffer, 150 ms ring.
+    pub latency_ms: u32,
+}
+
+impl OutputSettings {
+    pub fn load() -> Self {
+        let config = crate::config::Config::load();
+        Self {
+            device: config.audio_device,
+            sample_rate: config.audio_sample_rate,
+            latency_ms: config.audio_latency_ms,
+        }
+    }
+}
+
+/// Ring-buffer sizes in stereo pairs for an opened stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RingSizes {
+    /// Silence queued ahead of the first frame.
+    pub prefill: usize,
+    /// Most pairs the ring may hold.
+    pub capacity: usize,
+}
+
+impl RingSizes {
+    pub fn new(sample_rate: u32, latency_ms: u32) -> Self {
+        let ms = if latency_ms == 0 {
+            DEFAULT_LATENCY_MS
+        } else {
+            latency_ms.max(MIN_LATENCY_MS)
+        };
+        let pairs = |ms: u32| (sample_rate as u64 * ms as u64 / 1000) as usize;
+        Self {
+            prefill: pairs(ms),
+            capacity: pairs(ms + HEADROOM_MS),
+        }
+    }
+}
+
+/// An output device and the stereo f32 stream config to open it with.
+pub struct Output {
+    pub device: cpal::Device,
+    pub config: cpal::StreamConfig,
+    pub rings: RingSizes,
+}
+
+/// Names of the host's output devices, for the Settings list.
+pub fn list_devices() -> Vec<String> {
+    let host = cpal::default_host();
+    match host.output_devices() {
+        Ok(devices) => devices.filter_map(|d| d.name().ok()).collect(),
+        Err(e) => {
+            eprintln!("[audio] Cannot enumerate output devices: {e}");
+            Vec::new()
+        }
+    }
+}
+
+/// Resolve the configured output. `max_rate` caps the rate for engines
+/// whose resampler can't go higher (SIDLite). `tag` prefixes log lines.
+pub fn open(settings: &OutputSettings, max_rate: Option<u32>, tag: &str) -> Result<Output, String> {
+    let host = cpal::default_host();
+    let device = match settings.device.as_deref() {
+        Some(wanted) => match find_device(&host, wanted) {
+            Some(d) => Some(d),
+            None => {
+                eprintln!("[{tag}] Audio device '{wanted}' not found — using the default");
+                None
+            }
+        },
+        None => None,
+    };
+    let device = match device {
+        Some(d) => d,
+        None => host
+            .default_output_device()
+            .ok_or_else(|| "No audio output device found".to_string())?,
+    };
+    let name = device.name().unwrap_or_else(|_| "unknown".into());
+
+    let default_config = device
+        .default_output_config()
+        .map_err(|e| format!("No default output config: {e}"))?;
+    let native_rate = default_config.sample_rate().0;
+
+    let ranges: Vec<cpal::SupportedStreamConfigRange> = device
+        .supported_output_configs()
+        .map(|r| r.filter(|c| c.channels() == 2).collect())
+        .unwrap_or_default();
+
+    let mut wanted = if settings.sample_rate == 0 {
+        native_rate
+    } else {
+        settings.sample_rate
+    };
+    if let Some(max) = max_rate {
+        wanted = wanted.min(max);
+    }
+    let sample_rate = if wanted == native_rate || supports_rate(&ranges, wanted) {
+        wanted
+    } else {
+        eprintln!("[{tag}] '{name}' can't run at {wanted}Hz — using {native_rate}Hz");
+        native_rate
+    };
+
+    let buffer_size = match settings.latency_ms {
+        0 => cpal::BufferSize::Default,
+        ms => {
+            // Half the target per callback, so the ring holds the rest.
+            let frames = (sample_rate as u64 * ms.max(MIN_LATENCY_MS) as u64 / 2000) as u32;
+            cpal::BufferSize::Fixed(clamp_buffer(&ranges, sample_rate, frames))
+        }
+    };
+
+    eprintln!(
+        "[{tag}] Audio device: '{}', native rate: {}Hz, using {}Hz, buffer: {}",
+        name,
+        native_rate,
+        sample_rate,
+        match buffer_size {
+            cpal::BufferSize::Fixed(n) => format!("{n} frames"),
+            cpal::BufferSize::Default => "default".into(),
+        },
+    );
+
+    Ok(Output {
+        device,
+        config: cpal::StreamConfig {
+            channels: 2,
+            sample_rate: cpal::SampleRate(sample_rate),
+            buffer_size,
+        },
+        rings: RingSizes::new(sample_rate, settings.latency_ms),
+    })
+}
+
+impl Output {
+    /// Build the f32 output stream. A fixed buffer size the driver
+    /// refuses is retried once with the driver default.
+    pub fn build_stream<D>(
+        &mut self,
+        data_callback: D,
+        tag: &'static str,
+    ) -> Result<cpal::Stream, String>
+    where
+        D: FnMut(&mut [f32], &cpal::OutputCallbackInfo) + Send + Clone + 'static,
+    {
+        let error_callback = move |err| eprintln!("[{tag}] Audio error: {err}");
+        match self.device.build_output_stream(
+            &self.config,
+            data_callback.clone(),
+            error_callback,
+            None,
+        ) {
+            Ok(stream) => Ok(stream),
+            Err(e) if matches!(self.config.buffer_size, cpal::BufferSize::Fixed(_)) => {
+                eprintln!("[{tag}] Fixed buffer refused ({e}) — using the driver default");
+                self.config.buffer_size = cpal::BufferSize::Default;
+                self.device
+                    .build_output_stream(&self.config, data_callback, error_callback, None)
+                    .map_err(|e| format!("build_output_stream failed: {e}"))
+            }
+            Err(e) => Err(format!("build_output_stream failed: {e}")),
+        }
+    }
+}
+
+fn find_device(host: &cpal::Host, wanted: &str) -> Option<cpal::Device> {
+    host.output_devices()
+        .ok()?
+        .find(|d| d.name().map(|n| n == wanted).unwrap_or(false))
+}
+
+fn supports_rate(ranges: &[cpal::SupportedStreamConfigRange], rate: u32) -> bool {
+    ranges
+        .iter()
+        .any(|r| r.min_sample_rate().0 <= rate && rate <= r.max_sample_rate().0)
+}
+
+/// Clamp `frames` into the buffer range the device reports for `rate`.
+/// Devices that don't report one get the request unchanged.
+fn clamp_buffer(ranges: &[cpal::SupportedStreamConfigRange], rate: u32, frames: u32) -> u32 {
+    ranges
+        .iter()
+        .filter(|r| r.min_sample_rate().0 <= rate && rate <= r.max_sample_rate().0)
+        .find_map(|r| match *r.buffer_size() {
+            cpal::SupportedBufferSize::Range { min, max } => Some(frames.clamp(min, max)),
+            cpal::SupportedBufferSize::Unknown => None,
+        })
+        .unwrap_or(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_rings_are_150ms_prefill_plus_100ms_headroom() {
+        // The engines used to cap at a fixed 12_288 pairs (~256 ms at
+        // 48 kHz) whatever the rate; the cap now scales with it.
+        let r = RingSizes::new(48_000, 0);
+        assert_eq!(r.prefill, 7_200); // 150 ms
+        assert_eq!(r.capacity, 12_000); // 250 ms
+    }
+
+    #[test]
+    fn rings_follow_the_latency_target_and_rate() {
+        let r = RingSizes::new(96_000, 20);
+        assert_eq!(r.prefill, 1_920);
+        assert_eq!(r.capacity, 11_520);
+        // Targets below the floor are raised to it.
+        assert_eq!(
+            RingSizes::new(44_100, 1),
+            RingSizes::new(44_100, MIN_LATENCY_MS)
+        );
+    }
+}
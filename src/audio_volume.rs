@@ -7,6 +7,14 @@
 //
 // Does NOT affect the USB hardware engine (analog out from USBSID-Pico is
 // outside Phosphor's reach) — that engine simply ignores the value.
+//
+// A second factor, the fade gain, is driven by the player thread during
+// end-of-song and sleep-timer fade-outs and multiplies into `scale()`.
+// Hardware engines fade by ramping $D418 instead (see player::send_sid_writes).
+//
+// A third factor, the track gain, is the loudness-normalisation gain for
+// the playing tune (see `loudness`). Unlike the other two it may exceed 1.0,
+// so the callbacks pass each scaled sample through `limit`.
 
 use std::sync::atomic::{AtomicU32, Ordering};
 
@@ -19,8 +27,76 @@ pub fn set(volume: f32) {
     VOLUME_BITS.store(v.to_bits(), Ordering::Relaxed);
 }
 
-/// Current master volume in [0.0, 1.0]. Read once per audio callback.
+/// Live fade-out gain in [0.0, 1.0]. 1.0 whenever no fade is running.
+static FADE_BITS: AtomicU32 = AtomicU32::new(0x3F80_0000); // 1.0_f32.to_bits()
+
+/// Per-track loudness gain. 1.0 when normalisation is off or the tune
+/// hasn't been measured yet.
+static TRACK_GAIN_BITS: AtomicU32 = AtomicU32::new(0x3F80_0000); // 1.0_f32.to_bits()
+
+/// Most the track gain may boost (+12 dB).
+const MAX_TRACK_GAIN: f32 = 4.0;
+
+/// Level above which `limit` starts bending samples toward full scale.
+const LIMIT_KNEE: f32 = 0.9;
+
+/// Current master volume × fade gain × track gain. Read once per audio
+/// callback.
 #[inline]
 pub fn scale() -> f32 {
-    f32::from_bits(VOLUME_BITS.load(Ordering::Relaxed))
+    f32::from_bits(VOLUME_BITS.load(Ordering::Relaxed)) * fade() * track_gain()
+}
+
+/// Soft-limit a sample after `scale()`: untouched up to `LIMIT_KNEE`,
+/// then eased toward ±1.0 so a boosted track saturates smoothly instead
+/// of hard-clipping in the output device.
+#[inline]
+pub fn limit(x: f32) -> f32 {
+    let a = x.abs();
+    if a <= LIMIT_KNEE {
+        return x;
+    }
+    let over = (a - LIMIT_KNEE) / (1.0 - LIMIT_KNEE);
+    (LIMIT_KNEE + (1.0 - LIMIT_KNEE) * over / (1.0 + over)).copysign(x)
+}
+
+/// Update the fade gain. Called by the player thread once per frame.
+pub fn set_fade(gain: f32) {
+    let g = gain.clamp(0.0, 1.0);
+    FADE_BITS.store(g.to_bits(), Ordering::Relaxed);
+}
+
+/// Current fade gain in [0.0, 1.0].
+#[inline]
+pub fn fade() -> f32 {
+    f32::from_bits(FADE_BITS.load(Ordering::Relaxed))
+}
+
+/// Update the per-track loudness gain. Clamped to [0, `MAX_TRACK_GAIN`].
+pub fn set_track_gain(gain: f32) {
+    let g = gain.clamp(0.0, MAX_TRACK_GAIN);
+    TRACK_GAIN_BITS.store(g.to_bits(), Ordering::Relaxed);
+}
+
+/// Current per-track loudness gain.
+#[inline]
+pub fn track_gain() -> f32 {
+    f32::from_bits(TRACK_GAIN_BITS.load(Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_passes_normal_levels_and_stays_below_full_scale() {
+        assert_eq!(limit(0.5), 0.5);
+        assert_eq!(limit(-LIMIT_KNEE), -LIMIT_KNEE);
+        for x in [0.95, 1.0, 2.0, MAX_TRACK_GAIN] {
+            let y = limit(x);
+            assert!(y > LIMIT_KNEE && y < 1.0, "{x} → {y}");
+            assert_eq!(limit(-x), -y);
+        }
+        assert!(limit(1.0) < limit(2.0));
+    }
 }
@@ -9,6 +9,14 @@ use std::path::PathBuf;
 
 pub const DEFAULT_HVSC_RSYNC_URL: &str = "https://hvsc.brona.dk/HVSC/C64Music/";
 
+/// Default end-of-song / sleep-timer fade length, and the cap applied
+/// when reading it back from config.json.
+pub const DEFAULT_FADE_OUT_SECS: u32 = 3;
+pub const MAX_FADE_OUT_SECS: u32 = 30;
+
+/// Default silence-skip threshold in seconds.
+pub const DEFAULT_SILENCE_SKIP_SECS: u32 = 5;
+
 /// Default window dimensions — used on first launch.
 const DEFAULT_WINDOW_WIDTH: f32 = 900.0;
 const DEFAULT_WINDOW_HEIGHT: f32 = 600.0;
@@ -20,6 +28,12 @@ pub struct Config {
     /// Default song length in seconds when Songlength DB has no entry.
     /// 0 = disabled (no auto-advance for unknown lengths).
     pub default_song_length_secs: u32,
+    /// Fade-out length in seconds before a song-length auto-advance or a
+    /// sleep-timer stop. 0 = hard cut (the pre-fade behaviour).
+    pub fade_out_secs: u32,
+    /// Skip to the next track after this many seconds of silence (all
+    /// voices quiet, registers static). 0 = never skip on silence.
+    pub silence_skip_secs: u32,
     /// Audio output engine name ("auto", "usb", "emulated", "u64").
     pub output_engine: String,
     /// Ultimate 64 IP address or hostname (for "u64" engine).
@@ -73,15 +87,48 @@ pub struct Config {
     pub u64_audio_enabled: bool,
     /// UDP port to receive U64 audio stream on (default 11001).
     pub u64_audio_port: u16,
+    /// Start tunes on the U64 as a standalone PRG (`prg_export`) through
+    /// its run-PRG path instead of the firmware SID player.
+    pub u64_run_as_prg: bool,
     /// Force stereo mirroring for 2SID tunes (duplicate SID1 writes to SID2).
     /// When enabled, 2SID tunes play in mono-stereo mode instead of true dual-SID.
     pub force_stereo_2sid: bool,
+    /// Per-chip model override for the software engines, SID1–SID4:
+    /// 0 = follow the tune header, 1 = force 6581, 2 = force 8580.
+    pub sid_models: [u8; 4],
+    /// Software engines: add DC to 8580 chips so $D418 digis are audible.
+    /// Overridable per tune (see `emu_tuning`).
+    pub digi_boost_8580: bool,
+    /// Software engines: 6581 filter cutoff preset, an
+    /// `emu_tuning::FilterPreset` name. Overridable per tune.
+    pub filter_6581: String,
+    /// Software engines: where each chip of a multi-SID tune sits in the
+    /// stereo mix, an `emu_tuning::StereoLayout` name. Overridable per tune.
+    pub stereo_layout: String,
+    /// Pan per chip, SID1–SID4, for the "custom" layout: -100 = hard
+    /// left, 100 = hard right.
+    pub stereo_pan: [i8; 4],
+    /// Software engines: headphone crossfeed in percent. 0 = off.
+    pub crossfeed_pct: u32,
+    /// Tempo control: software engines change pitch with the speed
+    /// instead of keeping it (see `tempo`).
+    pub tempo_pitch_follows: bool,
+    /// Run PSID tunes on the cycle-exact c64_emu core (VIC badlines,
+    /// real CIA timing) instead of libsidplayfp / the simple 6502 core.
+    pub psid_cycle_exact: bool,
     /// Restart the USB device when loading a new SID file (macOS only).
     pub restart_usb_on_load: bool,
     /// macOS USB transport mode: "bridge" (default — talk to the root-owned
     /// usbsid-bridge LaunchDaemon over a Unix socket) or "direct"
     /// on Linux/Windows, which always use the direct path.
     pub macos_usb_mode: String,
+    /// SID write log played by the "replay" engine (see `sid_log`).
+    pub replay_file: Option<String>,
+    /// Engine the "replay" engine feeds: "usb", "emulated" or "sidlite".
+    pub replay_engine: String,
+    /// Second engine fed the same writes for A/B listening ("none", "usb",
+    /// "emulated" or "sidlite"; see `sid_mirror`).
+    pub mirror_engine: String,
     /// Enable the built-in HTTP server for remote control from a web browser.
     pub http_remote_enabled: bool,
     /// Port for the HTTP remote control server (default 8364).
@@ -106,6 +153,20 @@ pub struct Config {
     /// effect on the USB hardware engine (analog output, not reachable
     /// from host). Default 1.0 = unity gain (no change vs prior versions).
     pub master_volume: f32,
+    /// Loudness normalisation target in LUFS (e.g. -18). 0 = off. Each
+    /// tune gets a gain towards it on the reSID, SIDLite and U64-stream
+    /// outputs (see `loudness`).
+    pub loudness_target_lufs: i32,
+    /// Output device for the cpal engines, by the name cpal reports.
+    /// `None` = system default; a device that has gone away also falls
+    /// back to the default (see `audio_output`).
+    pub audio_device: Option<String>,
+    /// Output sample rate in Hz (44100 / 48000 / 96000). 0 = the
+    /// device's default rate. SIDLite tops out at 48 kHz.
+    pub audio_sample_rate: u32,
+    /// Output latency target in ms — sets the device buffer and the
+    /// engines' ring-buffer depth. 0 = driver default with a 150 ms ring.
+    pub audio_latency_ms: u32,
     /// Where the 🎲 Surprise Me button (mini + big player) picks from.
     /// `"hvsc"` = random tune from the HVSC library (default),
     /// `"playlist"` = random entry from the currently-loaded playlist.
@@ -119,6 +180,8 @@ impl Default for Config {
         Self {
             skip_rsid: false,
             default_song_length_secs: 0,
+            fade_out_secs: DEFAULT_FADE_OUT_SECS,
+            silence_skip_secs: DEFAULT_SILENCE_SKIP_SECS,
             output_engine: "auto".to_string(),
             u64_address: String::new(),
             u64_password: String::new(),
@@ -138,9 +201,21 @@ impl Default for Config {
             hvsc_known_version: None,
             u64_audio_enabled: false,
             u64_audio_port: 11001,
+            u64_run_as_prg: false,
             force_stereo_2sid: false,
+            sid_models: [0; 4],
+            digi_boost_8580: false,
+            filter_6581: "average".to_string(),
+            stereo_layout: "classic".to_string(),
+            stereo_pan: [-100, 100, -50, 50],
+            crossfeed_pct: 0,
+            tempo_pitch_follows: false,
+            psid_cycle_exact: false,
             restart_usb_on_load: false,
             macos_usb_mode: "bridge".to_string(),
+            replay_file: None,
+            replay_engine: "emulated".to_string(),
+            mirror_engine: "none".to_string(),
             http_remote_enabled: false,
             http_remote_port: 8364,
             http_stream_enabled: false,
@@ -150,6 +225,10 @@ impl Default for Config {
             window_height_saved: DEFAULT_WINDOW_HEIGHT,
             base_font_size: 12.0,
             master_volume: 1.0,
+            loudness_target_lufs: 0,
+            audio_device: None,
+            audio_sample_rate: 0,
+            audio_latency_ms: 0,
             surprise_source: "hvsc".to_string(),
         }
     }
@@ -216,6 +295,16 @@ impl Config {
                 if let Ok(n) = val.parse::<u32>() {
                     config.default_song_length_secs = n;
                 }
+            } else if let Some(rest) = line.strip_prefix("\"fade_out_secs\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                if let Ok(n) = val.parse::<u32>() {
+                    config.fade_out_secs = n.min(MAX_FADE_OUT_SECS);
+                }
+            } else if let Some(rest) = line.strip_prefix("\"silence_skip_secs\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                if let Ok(n) = val.parse::<u32>() {
+                    config.silence_skip_secs = n;
+                }
             } else if let Some(rest) = line.strip_prefix("\"output_engine\"") {
                 let val = rest.trim().trim_start_matches(':').trim();
                 if let Some(s) = strip_json_string(val) {
@@ -303,6 +392,9 @@ impl Config {
             } else if let Some(rest) = line.strip_prefix("\"has_seen_welcome\"") {
                 let val = rest.trim().trim_start_matches(':').trim();
                 config.has_seen_welcome = val == "true";
+            } else if let Some(rest) = line.strip_prefix("\"u64_run_as_prg\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                config.u64_run_as_prg = val == "true";
             } else if let Some(rest) = line.strip_prefix("\"u64_audio_enabled\"") {
                 let val = rest.trim().trim_start_matches(':').trim();
                 config.u64_audio_enabled = val == "true";
@@ -314,6 +406,50 @@ impl Config {
             } else if let Some(rest) = line.strip_prefix("\"force_stereo_2sid\"") {
                 let val = rest.trim().trim_start_matches(':').trim();
                 config.force_stereo_2sid = val == "true";
+            } else if let Some(rest) = line.strip_prefix("\"sid_models\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                let items = val.trim_start_matches('[').trim_end_matches(']');
+                for (slot, item) in config.sid_models.iter_mut().zip(items.split(',')) {
+                    if let Ok(m @ 0..=2) = item.trim().parse::<u8>() {
+                        *slot = m;
+                    }
+                }
+            } else if let Some(rest) = line.strip_prefix("\"digi_boost_8580\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                config.digi_boost_8580 = val == "true";
+            } else if let Some(rest) = line.strip_prefix("\"filter_6581\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                if let Some(s) = strip_json_string(val) {
+                    if crate::emu_tuning::FilterPreset::from_name(&s).is_some() {
+                        config.filter_6581 = s;
+                    }
+                }
+            } else if let Some(rest) = line.strip_prefix("\"stereo_layout\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                if let Some(s) = strip_json_string(val) {
+                    if crate::emu_tuning::StereoLayout::from_name(&s).is_some() {
+                        config.stereo_layout = s;
+                    }
+                }
+            } else if let Some(rest) = line.strip_prefix("\"stereo_pan\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                let items = val.trim_start_matches('[').trim_end_matches(']');
+                for (slot, item) in config.stereo_pan.iter_mut().zip(items.split(',')) {
+                    if let Ok(p @ -100..=100) = item.trim().parse::<i8>() {
+                        *slot = p;
+                    }
+                }
+            } else if let Some(rest) = line.strip_prefix("\"crossfeed_pct\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                if let Ok(n) = val.parse::<u32>() {
+                    config.crossfeed_pct = n.min(100);
+                }
+            } else if let Some(rest) = line.strip_prefix("\"tempo_pitch_follows\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                config.tempo_pitch_follows = val == "true";
+            } else if let Some(rest) = line.strip_prefix("\"psid_cycle_exact\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                config.psid_cycle_exact = val == "true";
             } else if let Some(rest) = line.strip_prefix("\"restart_usb_on_load\"") {
                 let val = rest.trim().trim_start_matches(':').trim();
                 config.restart_usb_on_load = val == "true";
@@ -325,6 +461,25 @@ impl Config {
                         config.macos_usb_mode = s;
                     }
                 }
+            } else if let Some(rest) = line.strip_prefix("\"replay_file\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                if val != "null" {
+                    config.replay_file = strip_json_string(val);
+                }
+            } else if let Some(rest) = line.strip_prefix("\"replay_engine\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                if let Some(s) = strip_json_string(val) {
+                    if s == "usb" || s == "emulated" || s == "sidlite" {
+                        config.replay_engine = s;
+                    }
+                }
+            } else if let Some(rest) = line.strip_prefix("\"mirror_engine\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                if let Some(s) = strip_json_string(val) {
+                    if matches!(s.as_str(), "none" | "usb" | "emulated" | "sidlite") {
+                        config.mirror_engine = s;
+                    }
+                }
             } else if let Some(rest) = line.strip_prefix("\"http_remote_enabled\"") {
                 let val = rest.trim().trim_start_matches(':').trim();
                 config.http_remote_enabled = val == "true";
@@ -371,6 +526,28 @@ impl Config {
                 if let Ok(n) = val.parse::<f32>() {
                     config.master_volume = n.clamp(0.0, 1.0);
                 }
+            } else if let Some(rest) = line.strip_prefix("\"loudness_target_lufs\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                if let Ok(n) = val.parse::<i32>() {
+                    config.loudness_target_lufs = n.clamp(-40, 0);
+                }
+            } else if let Some(rest) = line.strip_prefix("\"audio_device\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                if val != "null" {
+                    config.audio_device = strip_json_string(val);
+                }
+            } else if let Some(rest) = line.strip_prefix("\"audio_sample_rate\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                if let Ok(n) = val.parse::<u32>() {
+                    if n == 0 || crate::audio_output::SAMPLE_RATES.contains(&n) {
+                        config.audio_sample_rate = n;
+                    }
+                }
+            } else if let Some(rest) = line.strip_prefix("\"audio_latency_ms\"") {
+                let val = rest.trim().trim_start_matches(':').trim();
+                if let Ok(n) = val.parse::<u32>() {
+                    config.audio_latency_ms = n.min(1_000);
+                }
             } else if let Some(rest) = line.strip_prefix("\"surprise_source\"") {
                 let val = rest.trim().trim_start_matches(':').trim();
                 if let Some(s) = strip_json_string(val) {
@@ -409,6 +586,8 @@ impl Config {
                 "{{\n",
                 "  \"skip_rsid\": {},\n",
                 "  \"default_song_length_secs\": {},\n",
+                "  \"fade_out_secs\": {},\n",
+                "  \"silence_skip_secs\": {},\n",
                 "  \"output_engine\": \"{}\",\n",
                 "  \"u64_address\": \"{}\",\n",
                 "  \"u64_password\": \"{}\",\n",
@@ -428,9 +607,21 @@ impl Config {
                 "  \"has_seen_welcome\": {},\n",
                 "  \"u64_audio_enabled\": {},\n",
                 "  \"u64_audio_port\": {},\n",
+                "  \"u64_run_as_prg\": {},\n",
                 "  \"force_stereo_2sid\": {},\n",
+                "  \"sid_models\": [{}],\n",
+                "  \"digi_boost_8580\": {},\n",
+                "  \"filter_6581\": \"{}\",\n",
+                "  \"stereo_layout\": \"{}\",\n",
+                "  \"stereo_pan\": [{}],\n",
+                "  \"crossfeed_pct\": {},\n",
+                "  \"tempo_pitch_follows\": {},\n",
+                "  \"psid_cycle_exact\": {},\n",
                 "  \"restart_usb_on_load\": {},\n",
                 "  \"macos_usb_mode\": \"{}\",\n",
+                "  \"replay_file\": {},\n",
+                "  \"replay_engine\": \"{}\",\n",
+                "  \"mirror_engine\": \"{}\",\n",
                 "  \"http_remote_enabled\": {},\n",
                 "  \"http_remote_port\": {},\n",
                 "  \"http_stream_enabled\": {},\n",
@@ -440,11 +631,17 @@ impl Config {
                 "  \"window_height_saved\": {},\n",
                 "  \"base_font_size\": {},\n",
                 "  \"master_volume\": {},\n",
+                "  \"loudness_target_lufs\": {},\n",
+                "  \"audio_device\": {},\n",
+                "  \"audio_sample_rate\": {},\n",
+                "  \"audio_latency_ms\": {},\n",
                 "  \"surprise_source\": \"{}\"\n",
                 "}}\n",
             ),
             self.skip_rsid,
             self.default_song_length_secs,
+            self.fade_out_secs,
+            self.silence_skip_secs,
             self.output_engine,
             self.u64_address.replace('\\', "\\\\").replace('"', "\\\""),
             self.u64_password.replace('\\', "\\\\").replace('"', "\\\""),
@@ -464,9 +661,21 @@ impl Config {
             self.has_seen_welcome,
             self.u64_audio_enabled,
             self.u64_audio_port,
+            self.u64_run_as_prg,
             self.force_stereo_2sid,
+            self.sid_models.map(|m| m.to_string()).join(", "),
+            self.digi_boost_8580,
+            self.filter_6581,
+            self.stereo_layout,
+            self.stereo_pan.map(|p| p.to_string()).join(", "),
+            self.crossfeed_pct,
+            self.tempo_pitch_follows,
+            self.psid_cycle_exact,
             self.restart_usb_on_load,
             self.macos_usb_mode,
+            fmt_opt_str(&self.replay_file),
+            self.replay_engine,
+            self.mirror_engine,
             self.http_remote_enabled,
             self.http_remote_port,
             self.http_stream_enabled,
@@ -476,6 +685,10 @@ impl Config {
             self.window_height_saved,
             self.base_font_size,
             self.master_volume,
+            self.loudness_target_lufs,
+            fmt_opt_str(&self.audio_device),
+            self.audio_sample_rate,
+            self.audio_latency_ms,
             self.surprise_source,
         )
     }
@@ -536,6 +749,12 @@ pub fn songlength_db_path() -> Option<PathBuf> {
     config_dir().map(|d| d.join("Songlengths.md5"))
 }
 
+/// Local song-length overlay — lengths Phosphor estimated for tunes
+/// Songlengths.md5 doesn't cover.
+pub fn songlength_overlay_path() -> Option<PathBuf> {
+    config_dir().map(|d| d.join("Songlengths.local.md5"))
+}
+
 /// Refresh Songlengths.md5 from the configured HVSC base URL.
 /// Pure-Rust path via `hvsc_sync::fetch_hvsc_document` — no subprocess.
 pub async fn download_songlength(hvsc_base: String) -> Result<PathBuf, String> {
@@ -0,0 +1,373 @@
+// Tuning knobs for the software engines (reSID, SIDLite).
+//
+//   * 8580 digi boost — a real 8580 has almost no DC offset in its mixer,
+//     so $D418 volume-register samples are near-silent on it. Boost adds
+//     an external DC input (reSID) or a volume-scaled DC step (SIDLite)
+//     on 8580 chips so those digis come through, as the modded "digi
+//     boost" boards and libsidplayfp's option do.
+//   * 6581 filter preset — 6581 filter cutoff curves vary widely between
+//     chips, mostly as a sideways shift of the same curve. A preset
+//     shifts the 11-bit cutoff written to 6581 chips to mimic a darker
+//     or brighter chip.
+//   * Stereo layout — where each chip of a multi-SID tune sits in the
+//     stereo mix, plus an optional headphone crossfeed (see `stereo_mix`).
+//
+// Global defaults live in `Config`; per-tune overrides keyed by HVSC MD5
+// are stored one per line in `<config_dir>/tune_emu.txt`:
+//
+//   <md5> digi_boost=on|off filter_6581=<preset> stereo=<layout>
+//
+// with every key optional.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::config::Config;
+
+/// 6581 filter cutoff preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilterPreset {
+    VeryDark,
+    Dark,
+    #[default]
+    Average,
+    Bright,
+    VeryBright,
+}
+
+impl FilterPreset {
+    pub const ALL: [Self; 5] = [
+        Self::VeryDark,
+        Self::Dark,
+        Self::Average,
+        Self::Bright,
+        Self::VeryBright,
+    ];
+
+    /// Name used in `config.json` and `tune_emu.txt`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::VeryDark => "very_dark",
+            Self::Dark => "dark",
+            Self::Average => "average",
+            Self::Bright => "bright",
+            Self::VeryBright => "very_bright",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::VeryDark => "Very dark",
+            Self::Dark => "Dark",
+            Self::Average => "Average",
+            Self::Bright => "Bright",
+            Self::VeryBright => "Very bright",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.name() == name)
+    }
+
+    /// Shift applied to the 11-bit cutoff value (0–2047) on 6581 chips.
+    pub fn cutoff_offset(self) -> i16 {
+        match self {
+            Self::VeryDark => -384,
+            Self::Dark => -192,
+            Self::Average => 0,
+            Self::Bright => 192,
+            Self::VeryBright => 384,
+        }
+    }
+}
+
+/// Stereo placement of the chips in the software engines' mix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StereoLayout {
+    /// SID1 left, SID2 right, SID3/SID4 centred — the original fixed mix.
+    #[default]
+    Classic,
+    /// Every chip in the middle.
+    Centre,
+    /// Chips spread evenly from left to right (3SID = L/C/R).
+    Spread,
+    /// `Spread` at half width — easier on headphones.
+    Narrow,
+    /// Per-chip pan from `Config::stereo_pan`.
+    Custom,
+}
+
+impl StereoLayout {
+    pub const ALL: [Self; 5] = [
+        Self::Classic,
+        Self::Centre,
+        Self::Spread,
+        Self::Narrow,
+        Self::Custom,
+    ];
+
+    /// Name used in `config.json` and `tune_emu.txt`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Classic => "classic",
+            Self::Centre => "centre",
+            Self::Spread => "spread",
+            Self::Narrow => "narrow",
+            Self::Custom => "custom",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Classic => "Classic",
+            Self::Centre => "Centred",
+            Self::Spread => "Spread",
+            Self::Narrow => "Narrow",
+            Self::Custom => "Custom",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.name() == name)
+    }
+
+    /// Pan per chip slot, -100 (left) … 100 (right), for a tune using
+    /// `chips` SIDs. `custom` is only read by `Custom`.
+    pub fn pans(self, chips: usize, custom: [i8; 4]) -> [i8; 4] {
+        let spread = |width: i32| {
+            let mut pans = [0i8; 4];
+            if chips >= 2 {
+                let last = chips.min(4) as i32 - 1;
+                for (i, pan) in pans.iter_mut().take(chips).enumerate() {
+                    let centred = (i as i32 * 200 + last / 2) / last - 100;
+                    *pan = (centred * width / 100) as i8;
+                }
+            }
+            pans
+        };
+        match self {
+            Self::Classic => [-100, 100, 0, 0],
+            Self::Centre => [0; 4],
+            Self::Spread => spread(100),
+            Self::Narrow => spread(50),
+            Self::Custom => custom.map(|p| p.clamp(-100, 100)),
+        }
+    }
+}
+
+/// Settings handed to `SidDevice::set_emu_tuning` for one tune.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EmuTuning {
+    pub digi_boost_8580: bool,
+    pub filter_6581: FilterPreset,
+    pub stereo: StereoLayout,
+    /// Per-chip pan for `StereoLayout::Custom`.
+    pub custom_pan: [i8; 4],
+    /// Headphone crossfeed in percent (0 = off).
+    pub crossfeed_pct: u8,
+    /// SIDs the tune itself uses, for the stereo layout. A mono tune
+    /// mirrored onto SID2 counts 1. 0 = every chip the engine runs.
+    pub tune_sids: u8,
+}
+
+/// Shift the cutoff held in `$D415` (low 3 bits) / `$D416` (high 8 bits)
+/// by `offset`, clamped to the register range. Returns the new pair.
+pub fn adjust_cutoff(lo: u8, hi: u8, offset: i16) -> (u8, u8) {
+    let fc = ((hi as i16) << 3) | (lo & 0x07) as i16;
+    let fc = (fc + offset).clamp(0, 0x7FF) as u16;
+    ((lo & !0x07) | (fc & 0x07) as u8, (fc >> 3) as u8)
+}
+
+/// One tune's overrides; `None` falls back to the global setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TuneOverride {
+    pub digi_boost_8580: Option<bool>,
+    pub filter_6581: Option<FilterPreset>,
+    pub stereo: Option<StereoLayout>,
+}
+
+impl TuneOverride {
+    fn is_empty(&self) -> bool {
+        self.digi_boost_8580.is_none() && self.filter_6581.is_none() && self.stereo.is_none()
+    }
+}
+
+/// Per-tune overrides, keyed by lowercase HVSC MD5.
+#[derive(Debug, Clone, Default)]
+pub struct TuneOverrides {
+    entries: HashMap<String, TuneOverride>,
+}
+
+impl TuneOverrides {
+    /// Load from the config directory, or return an empty set.
+    pub fn load() -> Self {
+        db_path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .map(|text| Self::parse(&text))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let Some(p) = db_path() else {
+            return;
+        };
+        if let Some(parent) = p.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Err(e) = std::fs::write(&p, self.to_text()) {
+            eprintln!("[phosphor] Cannot save {}: {e}", p.display());
+        }
+    }
+
+    pub fn get(&self, md5: &str) -> TuneOverride {
+        self.entries
+            .get(&md5.trim().to_lowercase())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Store `ov` for `md5`; an empty override removes the entry.
+    pub fn set(&mut self, md5: &str, ov: TuneOverride) {
+        let key = md5.trim().to_lowercase();
+        if ov.is_empty() {
+            self.entries.remove(&key);
+        } else {
+            self.entries.insert(key, ov);
+        }
+    }
+
+    fn parse(text: &str) -> Self {
+        let mut entries = HashMap::new();
+        for line in text.lines() {
+            let mut fields = line.split_whitespace();
+            let Some(md5) = fields.next() else {
+                continue;
+            };
+            if md5.len() != 32 || md5.starts_with('#') {
+                continue;
+            }
+            let mut ov = TuneOverride::default();
+            for field in fields {
+                match field.split_once('=') {
+                    Some(("digi_boost", "on")) => ov.digi_boost_8580 = Some(true),
+                    Some(("digi_boost", "off")) => ov.digi_boost_8580 = Some(false),
+                    Some(("filter_6581", name)) => ov.filter_6581 = FilterPreset::from_name(name),
+                    Some(("stereo", name)) => ov.stereo = StereoLayout::from_name(name),
+                    _ => {}
+                }
+            }
+            if !ov.is_empty() {
+                entries.insert(md5.to_lowercase(), ov);
+            }
+        }
+        Self { entries }
+    }
+
+    fn to_text(&self) -> String {
+        let mut keys: Vec<&String> = self.entries.keys().collect();
+        keys.sort_unstable(); // deterministic output, friendly for diff
+        let mut out =
+            String::from("# md5 digi_boost=on|off filter_6581=<preset> stereo=<layout>\n");
+        for key in keys {
+            let ov = &self.entries[key];
+            out.push_str(key);
+            if let Some(on) = ov.digi_boost_8580 {
+                out.push_str(if on {
+                    " digi_boost=on"
+                } else {
+                    " digi_boost=off"
+                });
+            }
+            if let Some(preset) = ov.filter_6581 {
+                out.push_str(" filter_6581=");
+                out.push_str(preset.name());
+            }
+            if let Some(layout) = ov.stereo {
+                out.push_str(" stereo=");
+                out.push_str(layout.name());
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Effective tuning for the tune with HVSC MD5 `md5`.
+pub fn resolve(config: &Config, md5: &str, tune_sids: usize) -> EmuTuning {
+    let ov = TuneOverrides::load().get(md5);
+    EmuTuning {
+        digi_boost_8580: ov.digi_boost_8580.unwrap_or(config.digi_boost_8580),
+        filter_6581: ov
+            .filter_6581
+            .unwrap_or(FilterPreset::from_name(&config.filter_6581).unwrap_or_default()),
+        stereo: ov
+            .stereo
+            .unwrap_or(StereoLayout::from_name(&config.stereo_layout).unwrap_or_default()),
+        custom_pan: config.stereo_pan,
+        crossfeed_pct: config.crossfeed_pct.min(100) as u8,
+        tune_sids: tune_sids.min(4) as u8,
+    }
+}
+
+fn db_path() -> Option<PathBuf> {
+    crate::config::config_dir().map(|d| d.join("tune_emu.txt"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cutoff_shift_keeps_unused_bits_and_clamps() {
+        // FC = $0405 → +192 = $04C5; bits 3–7 of $D415 are preserved.
+        assert_eq!(adjust_cutoff(0xF8 | 0x05, 0x80, 192), (0xF8 | 0x05, 0x98));
+        assert_eq!(adjust_cutoff(0x07, 0xFF, 384), (0x07, 0xFF));
+        assert_eq!(adjust_cutoff(0x01, 0x00, -384), (0x00, 0x00));
+        assert_eq!(adjust_cutoff(0x03, 0x40, 0), (0x03, 0x40));
+    }
+
+    #[test]
+    fn overrides_roundtrip_through_text() {
+        let md5_a = "0123456789abcdef0123456789abcdef";
+        let md5_b = "FEDCBA9876543210FEDCBA9876543210";
+        let mut db = TuneOverrides::default();
+        db.set(
+            md5_a,
+            TuneOverride {
+                digi_boost_8580: Some(true),
+                filter_6581: None,
+                stereo: None,
+            },
+        );
+        db.set(
+            md5_b,
+            TuneOverride {
+                digi_boost_8580: Some(false),
+                filter_6581: Some(FilterPreset::VeryDark),
+                stereo: Some(StereoLayout::Spread),
+            },
+        );
+        let back = TuneOverrides::parse(&db.to_text());
+        assert_eq!(back.get(md5_a), db.get(md5_a));
+        assert_eq!(back.get(&md5_b.to_lowercase()), db.get(md5_b));
+        assert_eq!(back.get(md5_b).filter_6581, Some(FilterPreset::VeryDark));
+        assert_eq!(back.get(md5_b).stereo, Some(StereoLayout::Spread));
+
+        db.set(md5_a, TuneOverride::default());
+        assert!(!db.to_text().contains(md5_a));
+    }
+
+    #[test]
+    fn spread_layouts_fan_out_over_the_active_chips() {
+        let custom = [0; 4];
+        assert_eq!(StereoLayout::Spread.pans(1, custom), [0, 0, 0, 0]);
+        assert_eq!(StereoLayout::Spread.pans(2, custom), [-100, 100, 0, 0]);
+        assert_eq!(StereoLayout::Spread.pans(3, custom), [-100, 0, 100, 0]);
+        assert_eq!(StereoLayout::Spread.pans(4, custom), [-100, -33, 33, 100]);
+        assert_eq!(StereoLayout::Narrow.pans(3, custom), [-50, 0, 50, 0]);
+        assert_eq!(
+            StereoLayout::Custom.pans(2, [-128, 40, 0, 127]),
+            [-100, 40, 0, 100]
+        );
+    }
+}
@@ -453,10 +453,12 @@ mod tests {
             songs: 1,
             selected_song: 1,
             is_pal: true,
+            force_pal: None,
             num_sids: 1,
             is_rsid: false,
             md5: Some(md5.to_string()),
             duration_secs: None,
+            duration_is_default: false,
             has_wds: false,
         }
     }
@@ -0,0 +1,352 @@
+// Loudness normalisation across tracks.
+//
+// `LoudnessMeter` measures integrated loudness the ITU-R BS.1770 way:
+// K-weighting (high shelf + high pass), 400 ms blocks with 75 % overlap,
+// an absolute gate at -70 LUFS and a relative gate 10 LU below the
+// ungated mean. `player::loudness_scan` feeds it a short headless render
+// of each tune; the result is kept per HVSC MD5 in `loudness.txt` next
+// to `heard.txt`, and turned into a per-track gain with `track_gain`.
+// The gain goes through `audio_volume::set_track_gain`, so it reaches the
+// reSID, SIDLite and U64-stream outputs but not USB hardware.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Most a quiet tune is boosted, in dB.
+const MAX_BOOST_DB: f32 = 12.0;
+/// Most a loud tune is cut, in dB.
+const MAX_CUT_DB: f32 = 24.0;
+/// Boosts stop where the measured sample peak would reach this level.
+const PEAK_CEILING: f32 = 0.98;
+/// Blocks quieter than this never count (BS.1770 absolute gate).
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+/// Blocks this far below the ungated mean don't count either.
+const RELATIVE_GATE_LU: f64 = 10.0;
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Meter
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Integrated loudness and sample peak of one analysed tune.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub lufs: f32,
+    /// Largest absolute sample, 1.0 = full scale.
+    pub peak: f32,
+}
+
+/// Direct-form-I biquad, coefficients normalised to a0 = 1.
+#[derive(Debug, Clone, Copy, Default)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[0] * self.y[0]
+            - self.a[1] * self.y[1];
+        self.x = [x, self.x[0]];
+        self.y = [y, self.y[0]];
+        y
+    }
+}
+
+/// BS.1770 stage 1: high shelf, about +4 dB above 1.5 kHz. Bilinear
+/// transform of the analog prototype, so any sample rate matches the
+/// 48 kHz coefficients in the spec.
+fn shelf(sample_rate: u32) -> Biquad {
+    let (gain_db, q, fc) = (
+        3.999_843_853_973_347,
+        0.707_175_236_955_419_6,
+        1_681.974_450_955_533,
+    );
+    let k = (std::f64::consts::PI * fc / sample_rate as f64).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.499_666_774_154_541_6);
+    let a0 = 1.0 + k / q + k * k;
+    Biquad {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        ..Default::default()
+    }
+}
+
+/// BS.1770 stage 2: high pass around 38 Hz.
+fn high_pass(sample_rate: u32) -> Biquad {
+    let (q, fc) = (0.500_327_037_323_877_3, 38.135_470_876_024_44);
+    let k = (std::f64::consts::PI * fc / sample_rate as f64).tan();
+    let a0 = 1.0 + k / q + k * k;
+    Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        ..Default::default()
+    }
+}
+
+/// Streaming stereo loudness meter.
+pub struct LoudnessMeter {
+    /// K-weighting filters per channel: shelf, then high pass.
+    filters: [[Biquad; 2]; 2],
+    /// Samples per 100 ms step; four steps make one gating block.
+    step_len: usize,
+    step_sum: f64,
+    step_fill: usize,
+    /// Mean square (summed over channels) of every finished step.
+    steps: Vec<f64>,
+    peak: f32,
+}
+
+impl LoudnessMeter {
+    pub fn new(sample_rate: u32) -> Self {
+        let chain = [shelf(sample_rate), high_pass(sample_rate)];
+        Self {
+            filters: [chain, chain],
+            step_len: (sample_rate as usize / 10).max(1),
+            step_sum: 0.0,
+            step_fill: 0,
+            steps: Vec::new(),
+            peak: 0.0,
+        }
+    }
+
+    /// Feed one stereo sample pair, each in [-1, 1].
+    pub fn push(&mut self, left: f32, right: f32) {
+        self.peak = self.peak.max(left.abs()).max(right.abs());
+        for (ch, x) in [left, right].into_iter().enumerate() {
+            let [shelf, hp] = &mut self.filters[ch];
+            let z = hp.process(shelf.process(x as f64));
+            self.step_sum += z * z;
+        }
+        self.step_fill += 1;
+        if self.step_fill == self.step_len {
+            self.steps.push(self.step_sum / self.step_len as f64);
+            self.step_sum = 0.0;
+            self.step_fill = 0;
+        }
+    }
+
+    /// Feed interleaved 16-bit pairs, as the offline engines render them.
+    pub fn push_pairs(&mut self, pairs: &[(i16, i16)]) {
+        for &(l, r) in pairs {
+            self.push(l as f32 / 32768.0, r as f32 / 32768.0);
+        }
+    }
+
+    /// Gated integrated loudness. `None` for less than one block of audio
+    /// or when everything sits below the absolute gate.
+    pub fn finish(&self) -> Option<Measurement> {
+        let blocks: Vec<f64> = self
+            .steps
+            .windows(4)
+            .map(|w| w.iter().sum::<f64>() / 4.0)
+            .collect();
+        let above = |gate: f64| -> Vec<f64> {
+            blocks
+                .iter()
+                .copied()
+                .filter(|&p| block_lufs(p) > gate)
+                .collect()
+        };
+        let mean = |v: &[f64]| v.iter().sum::<f64>() / v.len() as f64;
+
+        let loud = above(ABSOLUTE_GATE_LUFS);
+        if loud.is_empty() {
+            return None;
+        }
+        let relative = block_lufs(mean(&loud)) - RELATIVE_GATE_LU;
+        let gated = above(relative.max(ABSOLUTE_GATE_LUFS));
+        if gated.is_empty() {
+            return None;
+        }
+        Some(Measurement {
+            lufs: block_lufs(mean(&gated)) as f32,
+            peak: self.peak,
+        })
+    }
+}
+
+fn block_lufs(power: f64) -> f64 {
+    -0.691 + 10.0 * power.max(1e-20).log10()
+}
+
+/// Linear gain that brings `m` to `target_lufs`, limited to
+/// `MAX_BOOST_DB` / `MAX_CUT_DB` and so that boosts don't push the
+/// sample peak past `PEAK_CEILING`.
+pub fn track_gain(m: Measurement, target_lufs: f32) -> f32 {
+    let db = (target_lufs - m.lufs).clamp(-MAX_CUT_DB, MAX_BOOST_DB);
+    let gain = 10f32.powf(db / 20.0);
+    if gain > 1.0 && m.peak > 0.0 {
+        gain.min((PEAK_CEILING / m.peak).max(1.0))
+    } else {
+        gain
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  LoudnessDb
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Measured loudness per HVSC MD5, one `md5 lufs peak` line per tune.
+#[derive(Debug, Clone, Default)]
+pub struct LoudnessDb {
+    entries: HashMap<String, Measurement>,
+    /// Set when an entry was added since the last save.
+    dirty: bool,
+}
+
+impl LoudnessDb {
+    /// Load from the config directory, or return an empty DB.
+    pub fn load() -> Self {
+        let text = db_path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .unwrap_or_default();
+        let db = Self::parse(&text);
+        if !db.entries.is_empty() {
+            eprintln!(
+                "[phosphor] LoudnessDb: loaded {} measured tracks",
+                db.entries.len()
+            );
+        }
+        db
+    }
+
+    fn parse(text: &str) -> Self {
+        let entries = text
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let md5 = parts.next()?.to_lowercase();
+                let lufs = parts.next()?.parse().ok()?;
+                let peak = parts.next()?.parse().ok()?;
+                (md5.len() == 32).then_some((md5, Measurement { lufs, peak }))
+            })
+            .collect();
+        Self {
+            entries,
+            dirty: false,
+        }
+    }
+
+    fn to_text(&self) -> String {
+        let mut lines: Vec<String> = self
+            .entries
+            .iter()
+            .map(|(md5, m)| format!("{md5} {:.2} {:.4}", m.lufs, m.peak))
+            .collect();
+        lines.sort_unstable(); // deterministic output, friendly for diff
+        lines.join("\n")
+    }
+
+    /// Persist to disk only if an entry was added since the last save.
+    pub fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        if let Some(p) = db_path() {
+            if let Some(parent) = p.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            if std::fs::write(&p, self.to_text()).is_ok() {
+                self.dirty = false;
+            }
+        }
+    }
+
+    pub fn get(&self, md5: &str) -> Option<Measurement> {
+        self.entries.get(&md5.trim().to_lowercase()).copied()
+    }
+
+    pub fn insert(&mut self, md5: &str, m: Measurement) {
+        self.entries.insert(md5.trim().to_lowercase(), m);
+        self.dirty = true;
+    }
+}
+
+fn db_path() -> Option<PathBuf> {
+    crate::config::config_dir().map(|d| d.join("loudness.txt"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(amplitude: f32, secs: f32) -> Option<Measurement> {
+        let rate = 48_000;
+        let mut meter = LoudnessMeter::new(rate);
+        for n in 0..(rate as f32 * secs) as usize {
+            let t = n as f32 / rate as f32;
+            let s = amplitude * (2.0 * std::f32::consts::PI * 997.0 * t).sin();
+            meter.push(s, s);
+        }
+        meter.finish()
+    }
+
+    #[test]
+    fn full_scale_stereo_sine_reads_zero_lufs() {
+        // BS.1770: a 997 Hz 0 dBFS sine reads -3.01 LUFS per channel.
+        let m = sine(1.0, 5.0).unwrap();
+        assert!(m.lufs.abs() < 0.1, "{}", m.lufs);
+        assert!((m.peak - 1.0).abs() < 1e-3);
+        let quiet = sine(0.1, 5.0).unwrap();
+        assert!((quiet.lufs + 20.0).abs() < 0.1, "{}", quiet.lufs);
+    }
+
+    #[test]
+    fn silence_and_short_input_have_no_reading() {
+        assert_eq!(sine(0.0, 5.0), None);
+        assert_eq!(sine(1.0, 0.2), None);
+    }
+
+    #[test]
+    fn gain_respects_limits_and_peak() {
+        let loud = Measurement {
+            lufs: -8.0,
+            peak: 1.0,
+        };
+        assert!((track_gain(loud, -18.0) - 10f32.powf(-0.5)).abs() < 1e-4);
+        // Quiet but peaky: the boost stops at the ceiling.
+        let peaky = Measurement {
+            lufs: -30.0,
+            peak: 0.49,
+        };
+        assert!((track_gain(peaky, -18.0) - 2.0).abs() < 1e-4);
+        // Quiet and clean: capped at MAX_BOOST_DB.
+        let quiet = Measurement {
+            lufs: -40.0,
+            peak: 0.01,
+        };
+        assert!((track_gain(quiet, -18.0) - 10f32.powf(0.6)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn db_round_trips_and_skips_junk() {
+        let text = "0123456789abcdef0123456789ABCDEF -17.50 0.8000\nnot-an-md5 -3 1\n\n";
+        let mut db = LoudnessDb::parse(text);
+        let m = db.get("0123456789abcdef0123456789abcdef").unwrap();
+        assert_eq!(
+            m,
+            Measurement {
+                lufs: -17.5,
+                peak: 0.8
+            }
+        );
+        db.insert(
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            Measurement {
+                lufs: -9.0,
+                peak: 1.0,
+            },
+        );
+        let again = LoudnessDb::parse(&db.to_text());
+        assert_eq!(again.entries.len(), 2);
+        assert!(again.get("ffffffffffffffffffffffffffffffff").is_some());
+    }
+}
@@ -1,5 +1,6 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod audio_output;
 #[allow(dead_code)]
 mod audio_stream;
 #[allow(dead_code)]
@@ -8,16 +9,28 @@ mod c64_emu;
 mod config;
 mod debug_log;
 mod device_config;
+mod emu_tuning;
 mod favorites;
 mod heard_db;
+mod loudness;
 mod petscii;
 mod player;
 mod playlist;
+mod prg_export;
 mod recently_played;
 mod sid_device;
+mod sid_log;
+mod sid_mirror;
+mod siddump;
+mod silence;
+mod stereo_mix;
 mod stil;
+mod tempo;
+mod tui;
 mod ui;
 mod version_check;
+mod voice_mask;
+mod voice_scope;
 
 #[cfg(all(feature = "usb", target_os = "macos"))]
 mod usb_bridge;
@@ -31,6 +44,10 @@ mod daemon_installer;
 #[cfg(feature = "usb")]
 mod sid_direct;
 
+// MPRIS media-player interface (media keys, panel widgets, playerctl).
+#[cfg(target_os = "linux")]
+mod mpris;
+
 mod assembly64;
 mod assembly64_browser;
 mod hvsc_browser;
@@ -79,6 +96,7 @@ mod windows_timer {
     }
 }
 
+use std::collections::{HashSet, VecDeque};
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
@@ -216,6 +234,9 @@ impl Default for SessionMode {
 //  Application state
 // ─────────────────────────────────────────────────────────────────────────────
 
+/// How long an auto-advance notice stays in the status bar.
+const ADVANCE_NOTICE_TTL: Duration = Duration::from_secs(5);
+
 struct App {
     /// Channel to send commands to the player thread.
     cmd_tx: Sender<PlayerCmd>,
@@ -298,6 +319,10 @@ struct App {
     /// Configured duration for the current sleep timer, so the UI can
     /// render "Sleep in 15 min" even after the deadline drifts.
     sleep_selected_mins: Option<u32>,
+    /// True once a `PlayerCmd::FadeOut` went out for the current song
+    /// (song-length end or sleep timer). Cleared by `clear_advance_status`
+    /// and on seek so the next song / position can fade again.
+    fade_sent: bool,
     /// Status message shown below the Songlength download button.
     download_status: String,
     /// Combined status for auto-downloads shown in the search bar.
@@ -328,6 +353,10 @@ struct App {
     /// Recently played history (last 100 unique tracks).
     recently_played: RecentlyPlayed,
     heard_db: HeardDb,
+    /// Measured loudness per MD5, for normalisation.
+    loudness_db: loudness::LoudnessDb,
+    /// Per-tune digi boost / 6581 filter overrides (Audio settings).
+    tune_overrides: emu_tuning::TuneOverrides,
     /// Pre-formatted HVSC completion string for the status bar.
     heard_text: String,
     /// Remote HVSC version reported by the boot-time `check_hvsc_update`
@@ -382,6 +411,30 @@ struct App {
     /// Consecutive frames with zero SID writes — used to detect end-of-song
     /// silence for MUS files that don't have songlength DB entries.
     silence_frames: u32,
+    /// Level/register-based silence detector for tunes that keep writing
+    /// the SID after the music has ended.
+    silence: silence::SilenceDetector,
+    /// Short-lived note shown in the status bar after an automatic skip
+    /// (e.g. "Silence — skipping"), with the moment it was raised.
+    advance_notice: Option<(String, Instant)>,
+    /// Tunes waiting for a background song-length estimate, as
+    /// `(md5, path)`. Drained one at a time from the tick.
+    length_queue: VecDeque<(String, PathBuf)>,
+    /// MD5s queued or being analysed, so a tune is only queued once.
+    length_pending: HashSet<String>,
+    /// True while an estimate is running on a worker thread.
+    length_running: bool,
+    /// Tunes waiting for a background loudness measurement, as
+    /// `(md5, path)`. Drained one at a time from the tick.
+    loudness_queue: VecDeque<(String, PathBuf)>,
+    /// MD5s queued, being measured, or that failed to measure this
+    /// session, so a tune is only tried once.
+    loudness_pending: HashSet<String>,
+    /// True while a measurement is running on a worker thread.
+    loudness_running: bool,
+    /// Output devices from the last scan, for Settings → Audio. `None`
+    /// until that tab is first opened.
+    audio_devices: Option<Vec<String>>,
     /// When the last auto-advance (subtune or track) was issued. Used to
     /// debounce a single auto-advance per 500 ms regardless of any stale-
     /// status race we haven't located. Reset to `None` on every user-
@@ -410,6 +463,9 @@ struct App {
     tracker_history: TrackerHistory,
     /// Tracker canvas cache — owns the iced Cache for the tracker Canvas.
     tracker_view: TrackerView,
+    /// Per-voice mute/solo state. Published to the player through
+    /// `voice_mask::set` on every change; survives track changes.
+    voice_mask: voice_mask::VoiceMask,
 
     /// HVSC STIL database (loaded on demand from STIL.txt).
     stil_db: Option<stil::StilDb>,
@@ -462,6 +518,12 @@ struct App {
     http_server: Option<Arc<tiny_http::Server>>,
     /// Editable text for the HTTP port field in settings.
     http_port_text: String,
+    /// Shared state for the MPRIS D-Bus service.
+    #[cfg(target_os = "linux")]
+    mpris_state: Arc<Mutex<mpris::MprisState>>,
+    /// Commands from the MPRIS D-Bus service.
+    #[cfg(target_os = "linux")]
+    mpris_cmd_rx: Receiver<mpris::MprisCmd>,
 }
 
 impl App {
@@ -485,6 +547,7 @@ impl App {
         // boot reads; main() also seeds before the window opens.
         crate::ui::font::set_base(config.base_font_size);
         crate::audio_volume::set(config.master_volume);
+        crate::tempo::set_pitch_follows(config.tempo_pitch_follows);
         eprintln!(
             "[phosphor] Config: skip_rsid={}, default_length={}s, engine={}",
             config.skip_rsid, config.default_song_length_secs, config.output_engine,
@@ -527,6 +590,11 @@ impl App {
             .and_then(|p| {
                 eprintln!("[phosphor] Loading Songlengths.md5 at {}", p.display());
                 SonglengthDb::load(&p).ok()
+            })
+            .or_else(|| {
+                // No HVSC DB yet — still use lengths estimated earlier.
+                let db = SonglengthDb::overlay_only();
+                (!db.overlay.is_empty()).then_some(db)
             });
 
         // Keep `last_songlength_file` in sync so Settings UI shows the same path.
@@ -584,6 +652,16 @@ impl App {
             http_remote_running = http_server.is_some();
         }
 
+        // MPRIS D-Bus service. Always on: it costs one idle thread, and
+        // without a session bus it logs once and exits.
+        #[cfg(target_os = "linux")]
+        let (mpris_state, mpris_cmd_rx) = {
+            let state = Arc::new(Mutex::new(mpris::MprisState::default()));
+            let (tx, rx) = crossbeam_channel::bounded(32);
+            mpris::start(Arc::clone(&state), tx);
+            (state, rx)
+        };
+
         // Snapshot fields needed for auto-download before config moves into app.
         // Both Songlengths.md5 and STIL.txt are fetched as DOCUMENTS/*.* relative
         // to the single hvsc_rsync_url — one source of truth.
@@ -620,6 +698,7 @@ impl App {
             show_welcome: initial_show_welcome,
             sleep_deadline: None,
             sleep_selected_mins: None,
+            fade_sent: false,
             download_status: String::new(),
             auto_download_status: String::new(),
             pending_auto_downloads: 0,
@@ -633,6 +712,8 @@ impl App {
             window_height,
             recently_played,
             heard_db,
+            loudness_db: loudness::LoudnessDb::load(),
+            tune_overrides: emu_tuning::TuneOverrides::load(),
             heard_text: String::new(),
             hvsc_remote_version: None,
             hvsc_status_text: String::new(),
@@ -665,6 +746,15 @@ impl App {
             pixel_ratio: 1.0,
             context_menu: None,
             silence_frames: 0,
+            silence: silence::SilenceDetector::new(),
+            advance_notice: None,
+            length_queue: VecDeque::new(),
+            length_pending: HashSet::new(),
+            length_running: false,
+            loudness_queue: VecDeque::new(),
+            loudness_pending: HashSet::new(),
+            loudness_running: false,
+            audio_devices: None,
             last_advance_at: None,
             advance_suppress_logged: false,
             vis_expanded: false,
@@ -675,6 +765,7 @@ impl App {
             vis_expanded_info: None,
             tracker_history: TrackerHistory::new(),
             tracker_view: TrackerView::new(),
+            voice_mask: voice_mask::VoiceMask::default(),
             stil_db,
             stil_entry: None,
             show_stil_overlay: false,
@@ -696,6 +787,10 @@ impl App {
             http_remote_running,
             http_server,
             http_port_text,
+            #[cfg(target_os = "linux")]
+            mpris_state,
+            #[cfg(target_os = "linux")]
+            mpris_cmd_rx,
         };
 
         let current_version = env!("CARGO_PKG_VERSION").to_string();
@@ -953,6 +1048,92 @@ impl App {
                 }
             }
 
+            Message::ContextMenuCycleClock => {
+                if let Some(cm) = self.context_menu.take() {
+                    if let Some(entry) = self.playlist.entries.get_mut(cm.track_idx) {
+                        entry.force_pal = match entry.force_pal {
+                            None => Some(true),
+                            Some(true) => Some(false),
+                            Some(false) => None,
+                        };
+                        eprintln!(
+                            "[phosphor] Clock for \"{}\": {}",
+                            entry.title,
+                            match entry.force_pal {
+                                None => "from tune",
+                                Some(true) => "PAL",
+                                Some(false) => "NTSC",
+                            }
+                        );
+                        // Lets maybe_save_session write the new directive.
+                        self.playlist_reorder_epoch = self.playlist_reorder_epoch.wrapping_add(1);
+                        // The clock is fixed at load time — restart the
+                        // tune if it's the one playing.
+                        if self.playlist.current == Some(cm.track_idx)
+                            && self.status.state != PlayState::Stopped
+                        {
+                            self.play_track(cm.track_idx);
+                        }
+                    }
+                }
+            }
+
+            Message::ContextMenuExportDump => {
+                if let Some(cm) = self.context_menu.take() {
+                    if let Some(entry) = self.playlist.entries.get(cm.track_idx) {
+                        let secs = entry
+                            .duration_secs
+                            .filter(|&d| d > 0)
+                            .unwrap_or(SIDDUMP_DEFAULT_SECS);
+                        let fps = if entry.is_pal { 50 } else { 60 };
+                        let source = SidDumpSource::Headless {
+                            path: entry.path.clone(),
+                            song: entry.selected_song,
+                            frames: secs * fps,
+                        };
+                        let name = siddump_file_name(&entry.path, entry.selected_song);
+                        return Task::perform(
+                            save_siddump_dialog(source, name),
+                            Message::SidDumpSaved,
+                        );
+                    }
+                }
+            }
+
+            Message::ContextMenuExportLiveDump => {
+                if let Some(cm) = self.context_menu.take() {
+                    if let Some(entry) = self.playlist.entries.get(cm.track_idx) {
+                        let source = SidDumpSource::Text(self.tracker_history.siddump());
+                        let name = siddump_file_name(&entry.path, entry.selected_song);
+                        return Task::perform(
+                            save_siddump_dialog(source, name),
+                            Message::SidDumpSaved,
+                        );
+                    }
+                }
+            }
+
+            Message::SidDumpSaved(Ok(path)) => {
+                eprintln!("[phosphor] SIDdump saved to {}", path.display());
+            }
+            Message::SidDumpSaved(Err(e)) => eprintln!("[phosphor] SIDdump export failed: {e}"),
+
+            Message::ContextMenuExportPrg => {
+                if let Some(cm) = self.context_menu.take() {
+                    if let Some(entry) = self.playlist.entries.get(cm.track_idx) {
+                        return Task::perform(
+                            save_prg_dialog(entry.path.clone(), entry.selected_song),
+                            Message::PrgSaved,
+                        );
+                    }
+                }
+            }
+
+            Message::PrgSaved(Ok(path)) => {
+                eprintln!("[phosphor] PRG saved to {}", path.display());
+            }
+            Message::PrgSaved(Err(e)) => eprintln!("[phosphor] PRG export failed: {e}"),
+
             // ── Transport ────────────────────────────────────────────────
             Message::PlayPause => {
                 self.context_menu = None;
@@ -1020,6 +1201,19 @@ impl App {
                 }
             }
 
+            Message::SeekTo(secs) => {
+                if self.status.state != PlayState::Stopped {
+                    self.send_cmd(PlayerCmd::Seek(Duration::from_secs(secs as u64)));
+                    // The jump can land right on the song-length boundary;
+                    // let auto-advance judge the new position afresh.
+                    self.last_advance_at = None;
+                    self.silence_frames = 0;
+                    self.silence.reset();
+                    // The player cancels any running fade on seek.
+                    self.fade_sent = false;
+                }
+            }
+
             // ── Playlist interaction ─────────────────────────────────────
             Message::PlaylistSelect(idx) => {
                 self.context_menu = None;
@@ -1481,6 +1675,9 @@ impl App {
 
             Message::SettingsTabChanged(t) => {
                 self.settings_tab = t;
+                if t == ui::SettingsTab::Audio && self.audio_devices.is_none() {
+                    return self.update(Message::ScanAudioDevices);
+                }
             }
 
             Message::ToggleDeviceConfig => {
@@ -1588,6 +1785,10 @@ impl App {
                 self.config.force_stereo_2sid = !self.config.force_stereo_2sid;
                 self.config.save();
             }
+            Message::TogglePsidCycleExact => {
+                self.config.psid_cycle_exact = !self.config.psid_cycle_exact;
+                self.config.save();
+            }
 
             #[cfg(target_os = "macos")]
             Message::SetMacosUsbMode(mode) => {
@@ -1610,6 +1811,61 @@ impl App {
             #[cfg(not(target_os = "macos"))]
             Message::SetMacosUsbMode(_) => {}
 
+            Message::SetSilenceSkipSecs(secs) => {
+                self.config.silence_skip_secs = secs;
+                self.config.save();
+            }
+
+            Message::SetLoudnessTarget(lufs) => {
+                self.config.loudness_target_lufs = lufs;
+                self.config.save();
+                self.apply_loudness_gain();
+                if let Some(idx) = self.playlist.current {
+                    self.queue_loudness_scan(idx);
+                }
+            }
+
+            Message::ScanAudioDevices => {
+                return Task::perform(
+                    async {
+                        tokio::task::spawn_blocking(audio_output::list_devices)
+                            .await
+                            .unwrap_or_default()
+                    },
+                    Message::AudioDevicesScanned,
+                );
+            }
+            Message::AudioDevicesScanned(devices) => {
+                self.audio_devices = Some(devices);
+            }
+
+            Message::SetAudioDevice(device) => {
+                if device != self.config.audio_device {
+                    self.config.audio_device = device;
+                    self.config.save();
+                    self.reopen_engine();
+                }
+            }
+            Message::SetAudioSampleRate(rate) => {
+                if rate != self.config.audio_sample_rate {
+                    self.config.audio_sample_rate = rate;
+                    self.config.save();
+                    self.reopen_engine();
+                }
+            }
+            Message::SetAudioLatency(ms) => {
+                if ms != self.config.audio_latency_ms {
+                    self.config.audio_latency_ms = ms;
+                    self.config.save();
+                    self.reopen_engine();
+                }
+            }
+
+            Message::SetFadeOutSecs(secs) => {
+                self.config.fade_out_secs = secs.min(config::MAX_FADE_OUT_SECS);
+                self.config.save();
+            }
+
             Message::DefaultSongLengthChanged(val) => {
                 self.default_length_text = val.clone();
                 let new_val = val.trim().parse::<u32>().unwrap_or(0);
@@ -1643,6 +1899,22 @@ impl App {
                 }
             }
 
+            Message::TempoNudge(steps) => {
+                let target =
+                    crate::tempo::current() as i32 + steps as i32 * crate::tempo::STEP_PCT as i32;
+                crate::tempo::set(target.clamp(0, u16::MAX as i32) as u16);
+            }
+
+            Message::TempoReset => {
+                crate::tempo::set(100);
+            }
+
+            Message::TogglePitchFollows => {
+                self.config.tempo_pitch_follows = !self.config.tempo_pitch_follows;
+                crate::tempo::set_pitch_follows(self.config.tempo_pitch_follows);
+                self.config.save();
+            }
+
             Message::BaseFontSizeChanged(val) => {
                 // Echo the keystroke back into the input buffer so the
                 // field remains editable mid-type. On a successful parse
@@ -1694,24 +1966,115 @@ impl App {
 
             Message::SetOutputEngine(engine) => {
                 if engine != self.config.output_engine {
-                    self.config.output_engine = engine.clone();
+                    self.config.output_engine = engine;
                     self.config.save();
-                    // Remember if something was playing so we can resume.
-                    let was_playing = self.status.state == PlayState::Playing;
-                    let cur_idx = self.playlist.current;
-                    let _ = self.cmd_tx.try_send(PlayerCmd::SetEngine(
-                        engine,
-                        self.config.u64_address.clone(),
-                        self.config.u64_password.clone(),
-                    ));
-                    // Auto-resume on the new engine.
-                    if was_playing {
-                        if let Some(idx) = cur_idx {
+                    self.reopen_engine();
+                }
+            }
+
+            Message::ToggleWriteLog => {
+                if let Some((path, frames)) = sid_log::stop() {
+                    eprintln!(
+                        "[phosphor] SID write log stopped: {frames} frames in {}",
+                        path.display()
+                    );
+                } else {
+                    let name = self
+                        .playlist
+                        .current
+                        .and_then(|i| self.playlist.entries.get(i))
+                        .and_then(|e| e.path.file_stem())
+                        .map(|s| format!("{}.{}", s.to_string_lossy(), sid_log::EXTENSION))
+                        .unwrap_or_else(|| format!("phosphor.{}", sid_log::EXTENSION));
+                    return Task::perform(pick_write_log_dialog(name), Message::WriteLogFileChosen);
+                }
+            }
+
+            Message::WriteLogFileChosen(Some(path)) => match sid_log::arm(&path) {
+                Ok(()) => {
+                    eprintln!("[phosphor] SID write log armed: {}", path.display());
+                    // Recording starts at the next device reset — restart
+                    // the current tune so the log covers it from the top.
+                    if self.status.state != PlayState::Stopped {
+                        if let Some(idx) = self.playlist.current {
                             self.play_track(idx);
                         }
                     }
                 }
+                Err(e) => eprintln!("[phosphor] SID write log: {e}"),
+            },
+            Message::WriteLogFileChosen(None) => {}
+
+            Message::PickReplayFile => {
+                return Task::perform(pick_replay_file_dialog(), Message::ReplayFileChosen);
+            }
+
+            Message::ReplayFileChosen(Some(path)) => {
+                self.config.replay_file = Some(path.to_string_lossy().into_owned());
+                self.config.save();
+                if self.config.output_engine == "replay" {
+                    self.reopen_engine();
+                }
+            }
+            Message::ReplayFileChosen(None) => {}
+
+            Message::SetReplayEngine(engine) => {
+                if engine != self.config.replay_engine {
+                    self.config.replay_engine = engine;
+                    self.config.save();
+                    if self.config.output_engine == "replay" {
+                        self.reopen_engine();
+                    }
+                }
+            }
+
+            Message::SetSidModelOverride(chip, model) => {
+                if let Some(slot) = self.config.sid_models.get_mut(chip) {
+                    *slot = model;
+                    self.config.save();
+                }
+            }
+
+            Message::ToggleDigiBoost8580 => {
+                self.config.digi_boost_8580 = !self.config.digi_boost_8580;
+                self.config.save();
+            }
+            Message::SetFilter6581(preset) => {
+                self.config.filter_6581 = preset.name().to_string();
+                self.config.save();
+            }
+            Message::SetStereoLayout(layout) => {
+                self.config.stereo_layout = layout.name().to_string();
+                self.config.save();
+            }
+            Message::SetStereoPan(chip, pan) => {
+                if let Some(slot) = self.config.stereo_pan.get_mut(chip) {
+                    *slot = pan.clamp(-100, 100);
+                    self.config.save();
+                }
+            }
+            Message::SetCrossfeed(pct) => {
+                self.config.crossfeed_pct = pct.min(100);
+                self.config.save();
+            }
+            Message::SetTuneDigiBoost(value) => {
+                self.update_tune_override(|ov| ov.digi_boost_8580 = value);
+            }
+            Message::SetTuneFilter6581(value) => {
+                self.update_tune_override(|ov| ov.filter_6581 = value);
+            }
+            Message::SetTuneStereo(value) => {
+                self.update_tune_override(|ov| ov.stereo = value);
+            }
+
+            Message::SetMirrorEngine(engine) => {
+                if engine != self.config.mirror_engine {
+                    self.config.mirror_engine = engine;
+                    self.config.save();
+                    self.reopen_engine();
+                }
             }
+            Message::SwapMirror => sid_mirror::toggle_audible(),
 
             Message::SetU64Address(addr) => {
                 self.config.u64_address = addr;
@@ -1978,9 +2341,21 @@ impl App {
                 self.tick = self.tick.wrapping_add(1);
                 self.poll_status();
 
-                // Sleep-timer expiry: stop playback once we cross the deadline,
-                // then clear the timer so it doesn't fire repeatedly.
+                // Sleep-timer expiry: fade out over the last few seconds,
+                // stop playback once we cross the deadline, then clear the
+                // timer so it doesn't fire repeatedly.
                 if let Some(deadline) = self.sleep_deadline {
+                    let fade = Duration::from_secs(self.config.fade_out_secs as u64);
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if !self.fade_sent
+                        && !fade.is_zero()
+                        && !remaining.is_zero()
+                        && remaining <= fade
+                        && self.status.state == PlayState::Playing
+                    {
+                        self.fade_sent = true;
+                        self.send_cmd(PlayerCmd::FadeOut(remaining));
+                    }
                     if Instant::now() >= deadline {
                         eprintln!("[sleep] timer expired — stopping playback");
                         self.sleep_deadline = None;
@@ -2064,6 +2439,15 @@ impl App {
                 // mode so the user's own default is never clobbered.
                 self.maybe_save_session();
 
+                let tasks = Task::batch([self.start_length_estimate(), self.start_loudness_scan()]);
+
+                // ── MPRIS ───────────────────────────────────────────────
+                #[cfg(target_os = "linux")]
+                let tasks = {
+                    self.update_mpris_state();
+                    Task::batch([tasks, self.poll_mpris_commands()])
+                };
+
                 // ── Remote control ──────────────────────────────────────
                 if self.http_remote_running {
                     self.update_remote_state();
@@ -2071,7 +2455,32 @@ impl App {
                     // A returned task means the remote enqueued an op
                     // that needs to re-enter update() (Surprise / load /
                     // restore). Fire it before we return.
-                    return t;
+                    return Task::batch([tasks, t]);
+                }
+                return tasks;
+            }
+
+            Message::LoudnessMeasured(md5, result) => {
+                self.loudness_running = false;
+                match result {
+                    Ok(m) => {
+                        self.loudness_pending.remove(&md5);
+                        self.loudness_db.insert(&md5, m);
+                        self.loudness_db.save();
+                        self.apply_loudness_gain();
+                    }
+                    // Stays in `loudness_pending`, so it isn't retried.
+                    Err(e) => eprintln!("[phosphor] Loudness measurement failed: {e}"),
+                }
+            }
+
+            Message::LengthEstimated(md5, result) => {
+                self.length_running = false;
+                match result {
+                    Ok(est) => self.apply_length_estimate(&md5, est.lengths()),
+                    // Not saved, so a later run tries again; staying in
+                    // `length_pending` keeps this one from retrying.
+                    Err(e) => eprintln!("[phosphor] Length estimate failed: {e}"),
                 }
             }
 
@@ -2116,6 +2525,26 @@ impl App {
                 self.visualizer.toggle_mode();
             }
 
+            Message::ToggleVoiceMute(sid, voice) => {
+                self.voice_mask.toggle_mute(sid, voice);
+                self.publish_voice_mask();
+            }
+
+            Message::ToggleVoiceSolo(sid, voice) => {
+                self.voice_mask.toggle_solo(sid, voice);
+                self.publish_voice_mask();
+            }
+
+            Message::ToggleSidMute(sid) => {
+                self.voice_mask.toggle_sid_mute(sid);
+                self.publish_voice_mask();
+            }
+
+            Message::ToggleSidSolo(sid) => {
+                self.voice_mask.toggle_sid_solo(sid);
+                self.publish_voice_mask();
+            }
+
             Message::ToggleVisFull => {
                 self.vis_expanded = !self.vis_expanded;
             }
@@ -2406,13 +2835,13 @@ impl App {
                         song,
                         force_stereo: self.config.force_stereo_2sid
                             || std::env::args().any(|a| a == "--stereo"),
-                        sid4_addr: parse_sid4_from_args(),
                         audio_port: if self.config.u64_audio_enabled {
                             Some(self.config.u64_audio_port)
                         } else {
                             None
                         },
                         restart_usb_on_load: self.config.restart_usb_on_load,
+                        force_pal: None,
                     });
                     self.show_hvsc_browser = false;
                 }
@@ -2457,13 +2886,13 @@ impl App {
                         song,
                         force_stereo: self.config.force_stereo_2sid
                             || std::env::args().any(|a| a == "--stereo"),
-                        sid4_addr: parse_sid4_from_args(),
                         audio_port: if self.config.u64_audio_enabled {
                             Some(self.config.u64_audio_port)
                         } else {
                             None
                         },
                         restart_usb_on_load: self.config.restart_usb_on_load,
+                        force_pal: None,
                     });
                     self.show_hvsc_browser = false;
                 }
@@ -2533,13 +2962,13 @@ impl App {
                     song,
                     force_stereo: self.config.force_stereo_2sid
                         || std::env::args().any(|a| a == "--stereo"),
-                    sid4_addr: parse_sid4_from_args(),
                     audio_port: if self.config.u64_audio_enabled {
                         Some(self.config.u64_audio_port)
                     } else {
                         None
                     },
                     restart_usb_on_load: self.config.restart_usb_on_load,
+                    force_pal: None,
                 });
                 crate::dlog!("SurprisePicked: PlayerCmd::Play sent, closing browser");
                 self.show_hvsc_browser = false;
@@ -2745,13 +3174,13 @@ impl App {
                                 song: resolved_song,
                                 force_stereo: self.config.force_stereo_2sid
                                     || std::env::args().any(|a| a == "--stereo"),
-                                sid4_addr: parse_sid4_from_args(),
                                 audio_port: if self.config.u64_audio_enabled {
                                     Some(self.config.u64_audio_port)
                                 } else {
                                     None
                                 },
                                 restart_usb_on_load: self.config.restart_usb_on_load,
+                                force_pal: None,
                             });
                             self.show_hvsc_browser = false;
                         }
@@ -3259,6 +3688,14 @@ impl App {
                 self.config.save();
             }
 
+            Message::ToggleU64RunAsPrg => {
+                self.config.u64_run_as_prg = !self.config.u64_run_as_prg;
+                self.config.save();
+                if self.config.output_engine == "u64" {
+                    self.reopen_engine();
+                }
+            }
+
             Message::U64AudioPortChanged(val) => {
                 if let Ok(port) = val.trim().parse::<u16>() {
                     if port >= 1024 {
@@ -3416,7 +3853,10 @@ impl App {
         // Engine label suffix: when we're on USB and the device has been
         // probed at least once, show what chips are actually on the board
         // (e.g. "2× MOS8580" or "MOS6581 + MOS8580"). Other engines: none.
-        let engine_suffix = if self.config.output_engine == "usb" {
+        // An open A/B mirror shows which side is heard instead.
+        let engine_suffix = if let Some(label) = sid_mirror::status_label() {
+            Some(label)
+        } else if self.config.output_engine == "usb" {
             self.device_cfg.as_ref().map(format_usb_chip_summary)
         } else {
             None
@@ -3495,6 +3935,11 @@ impl App {
                 self.hvsc_sync_progress,
                 self.sleep_selected_mins,
                 self.settings_tab,
+                self.audio_devices.as_deref(),
+                self.playlist.current_entry().and_then(|e| {
+                    let md5 = e.md5.as_deref()?;
+                    Some((e.title.as_str(), self.tune_overrides.get(md5)))
+                }),
             );
             column![
                 info_bar,
@@ -3537,6 +3982,7 @@ impl App {
                 &self.status.sid_regs,
                 num_sids,
                 is_pal,
+                self.voice_mask,
                 tracker_height,
             );
             column![
@@ -3622,6 +4068,11 @@ impl App {
                 playlist_widget,
                 rule::horizontal(1),
                 ui::status_bar(
+                    self.advance_notice
+                        .as_ref()
+                        .filter(|(_, at)| at.elapsed() < ADVANCE_NOTICE_TTL)
+                        .map(|(msg, _)| msg.as_str())
+                        .unwrap_or(""),
                     &self.heard_text,
                     &self.hvsc_status_text,
                     self.hvsc_update_available,
@@ -3776,6 +4227,13 @@ impl App {
                     {
                         Some(Message::VolumeNudge(0.05))
                     }
+                    // [ / ] — tempo down / up one step
+                    Key::Character(ref c) if c.as_str() == "[" && status != Status::Captured => {
+                        Some(Message::TempoNudge(-1))
+                    }
+                    Key::Character(ref c) if c.as_str() == "]" && status != Status::Captured => {
+                        Some(Message::TempoNudge(1))
+                    }
                     // K — toggle karaoke mode (MUS files with WDS lyrics)
                     Key::Character(ref c) if c.as_str() == "k" && status != Status::Captured => {
                         Some(Message::ToggleKaraoke)
@@ -3788,6 +4246,10 @@ impl App {
                     Key::Character(ref c) if c.as_str() == "l" && status != Status::Captured => {
                         Some(Message::ToggleHvscBrowser)
                     }
+                    // A — switch the audible side of the A/B mirror
+                    Key::Character(ref c) if c.as_str() == "a" && status != Status::Captured => {
+                        Some(Message::SwapMirror)
+                    }
                     // ? — show/hide help overlay
                     Key::Character(ref c) if c.as_str() == "?" => Some(Message::ShowHelp),
                     // Arrow keys — context-sensitive, resolved in update()
@@ -3836,6 +4298,28 @@ impl App {
         self.status.u64_screen_read_at = None;
         self.status.u64_screen_total_secs = None;
         self.silence_frames = 0;
+        self.silence.reset();
+        self.fade_sent = false;
+    }
+
+    /// Seconds into the current subtune, as the auto-advance check sees it.
+    ///
+    /// Prefers the U64's on-screen elapsed seconds over host wall-clock
+    /// so playback advances based on actual hardware position, not on
+    /// host time that started counting before the C64 produced audio.
+    /// Interpolates sub-second time using the wall-clock delta since
+    /// the last successful read — without this, elapsed lags up to
+    /// ~1.5 s behind reality (1 s screen-render granularity + 0.5 s
+    /// poll period). Falls back to wall-clock for non-U64 engines or
+    /// when the U64's player UI couldn't be parsed.
+    fn playback_elapsed_secs(&self) -> u64 {
+        match (
+            self.status.u64_screen_elapsed_secs,
+            self.status.u64_screen_read_at,
+        ) {
+            (Some(secs), Some(read_at)) => secs as u64 + read_at.elapsed().as_secs(),
+            _ => self.status.elapsed.as_secs(),
+        }
     }
 
     /// Queue a command to the player thread **without ever blocking the UI
@@ -3851,6 +4335,43 @@ impl App {
         }
     }
 
+    /// Hand the current mute/solo mask to the player thread and redraw the
+    /// bars / tracker columns that show it.
+    fn publish_voice_mask(&mut self) {
+        voice_mask::set(self.voice_mask);
+        self.visualizer.set_voice_mask(self.voice_mask);
+        self.tracker_view.invalidate();
+    }
+
+    /// Edit the playing tune's emulation-tuning override and persist it.
+    fn update_tune_override(&mut self, edit: impl FnOnce(&mut emu_tuning::TuneOverride)) {
+        let Some(md5) = self.playlist.current_entry().and_then(|e| e.md5.clone()) else {
+            return;
+        };
+        let mut ov = self.tune_overrides.get(&md5);
+        edit(&mut ov);
+        self.tune_overrides.set(&md5, ov);
+        self.tune_overrides.save();
+    }
+
+    /// Drop the player's device so the next play reopens
+    /// `config.output_engine`, and resume the current tune on it if
+    /// something was playing.
+    fn reopen_engine(&mut self) {
+        let was_playing = self.status.state == PlayState::Playing;
+        let cur_idx = self.playlist.current;
+        let _ = self.cmd_tx.try_send(PlayerCmd::SetEngine(
+            self.config.output_engine.clone(),
+            self.config.u64_address.clone(),
+            self.config.u64_password.clone(),
+        ));
+        if was_playing {
+            if let Some(idx) = cur_idx {
+                self.play_track(idx);
+            }
+        }
+    }
+
     fn play_track(&mut self, idx: usize) {
         if let Some(entry) = self.playlist.entries.get(idx) {
             if self.config.skip_rsid && entry.is_rsid {
@@ -3890,6 +4411,7 @@ impl App {
             }
 
             self.silence_frames = 0;
+            self.silence.reset();
             self.karaoke_groups.clear();
             self.karaoke_line = 0;
             self.last_flag_count = 0;
@@ -3899,7 +4421,6 @@ impl App {
 
             let force_stereo =
                 self.config.force_stereo_2sid || std::env::args().any(|a| a == "--stereo");
-            let sid4_addr = parse_sid4_from_args();
             let play_path = entry.path.clone();
             let play_song = entry.selected_song;
 
@@ -3916,14 +4437,17 @@ impl App {
                 path: play_path,
                 song: play_song,
                 force_stereo,
-                sid4_addr,
                 audio_port,
                 restart_usb_on_load: self.config.restart_usb_on_load,
+                force_pal: entry.force_pal,
             });
             self.clear_advance_status();
             // Fresh track — drop the debounce so the auto-advance for THIS
             // track's first subtune isn't gated by the previous track's fire.
             self.last_advance_at = None;
+            self.queue_length_estimate(idx);
+            self.apply_loudness_gain();
+            self.queue_loudness_scan(idx);
             // First USB playback this session → ask the bridge for the
             // device's actual SID chip layout once. The result goes into
             // self.device_cfg via DeviceConfigResult, and from there into
@@ -4093,15 +4617,9 @@ impl App {
                         if !self.karaoke_has_flags {
                             // Stereo voices 4-6 are in a separate .str file;
                             // FLAGs may live there instead.
-                            for ext in &["str", "STR"] {
-                                let str_path = mus_path.with_extension(ext);
-                                if let Ok(str_data) = std::fs::read(&str_path) {
-                                    if petscii::mus_has_flags(&str_data) {
-                                        self.karaoke_has_flags = true;
-                                        break;
-                                    }
-                                }
-                            }
+                            self.karaoke_has_flags = player::sid_file::mus_companion_str(&mus_path)
+                                .and_then(|p| std::fs::read(p).ok())
+                                .is_some_and(|d| petscii::mus_has_flags(&d));
                         }
 
                         // Load WDS lyrics as logical groups.
@@ -4322,6 +4840,38 @@ impl App {
             } else {
                 self.silence_frames = 0;
             }
+            // Tunes that keep writing the SID after the music ends never
+            // trip the zero-writes counter; watch levels + registers too.
+            let num_sids = self
+                .status
+                .track_info
+                .as_ref()
+                .map(|i| i.num_sids)
+                .unwrap_or(1);
+            self.silence.update(
+                self.status.elapsed,
+                &self.status.voice_levels,
+                &self.status.sid_regs,
+                num_sids,
+            );
+
+            // Start the fade-out early enough that it ends right on the
+            // song-length boundary, where the advance below takes over.
+            if !self.fade_sent && self.config.fade_out_secs > 0 {
+                let dur = self
+                    .playlist
+                    .current_entry()
+                    .and_then(|e| e.duration_secs)
+                    .or(self.status.u64_screen_total_secs);
+                if let Some(dur) = dur {
+                    let elapsed = self.playback_elapsed_secs();
+                    let left = (dur as u64).saturating_sub(elapsed);
+                    if left > 0 && left <= self.config.fade_out_secs as u64 {
+                        self.fade_sent = true;
+                        self.send_cmd(PlayerCmd::FadeOut(Duration::from_secs(left)));
+                    }
+                }
+            }
 
             if let Some(cur_idx) = self.playlist.current {
                 let advance_info = self.playlist.entries.get(cur_idx).and_then(|entry| {
@@ -4330,26 +4880,20 @@ impl App {
                     let dur = entry
                         .duration_secs
                         .or_else(|| self.status.u64_screen_total_secs.map(|s| s as u32));
-                    // Prefer the U64's on-screen elapsed seconds over host wall-clock
-                    // so playback advances based on actual hardware position, not on
-                    // host time that started counting before the C64 produced audio.
-                    // Interpolate sub-second time using the wall-clock delta since
-                    // the last successful read — without this, elapsed lags up to
-                    // ~1.5 s behind reality (1 s screen-render granularity + 0.5 s
-                    // poll period). Falls back to wall-clock for non-U64 engines or
-                    // when the U64's player UI couldn't be parsed.
-                    let elapsed = match (
-                        self.status.u64_screen_elapsed_secs,
-                        self.status.u64_screen_read_at,
-                    ) {
-                        (Some(secs), Some(read_at)) => secs as u64 + read_at.elapsed().as_secs(),
-                        _ => self.status.elapsed.as_secs(),
-                    };
+                    let elapsed = self.playback_elapsed_secs();
                     // Advance if duration exceeded OR prolonged silence detected.
                     // Silence detection: ~90 frames ≈ 3 seconds at 30fps tick.
                     // Only trigger after at least 5 seconds of playback to avoid
                     // false positives during song intro.
-                    let silence_ended = self.silence_frames > 90 && elapsed > 5;
+                    // Tunes without a length also advance once levels and
+                    // registers have been still for the configured time.
+                    let silence_limit = self.config.silence_skip_secs;
+                    let silence_ended = elapsed > 5
+                        && (self.silence_frames > 90
+                            || (dur.is_none()
+                                && silence_limit > 0
+                                && self.silence.silent_for()
+                                    >= Duration::from_secs(silence_limit as u64)));
                     let duration_ended = dur.map_or(false, |d| elapsed >= d as u64);
 
                     if duration_ended || silence_ended {
@@ -4409,47 +4953,32 @@ impl App {
                         );
                         self.last_advance_at = Some(now);
                         self.advance_suppress_logged = false;
+                        if trigger == "silence" {
+                            self.advance_notice = Some(("Silence — skipping".to_string(), now));
+                        }
                         if cur_song < total_songs {
                             let next_song = cur_song + 1;
                             let subtune_idx = (next_song - 1) as usize;
-                            let next_dur = md5
-                                .as_ref()
-                                .and_then(|m| {
-                                    self.songlength_db
-                                        .as_ref()
-                                        .and_then(|db| db.lookup(m, subtune_idx))
-                                })
-                                .or_else(|| {
-                                    let d = self.config.default_song_length_secs;
-                                    if d > 0 {
-                                        Some(d)
-                                    } else {
-                                        None
-                                    }
-                                });
+                            let next_dur = md5.as_ref().and_then(|m| {
+                                self.songlength_db
+                                    .as_ref()
+                                    .and_then(|db| db.lookup(m, subtune_idx))
+                            });
+                            let default = self.config.default_song_length_secs;
                             self.send_cmd(PlayerCmd::SetSubtune(next_song));
                             self.clear_advance_status();
                             if let Some(e) = self.playlist.entries.get_mut(cur_idx) {
                                 e.selected_song = next_song;
-                                e.duration_secs = next_dur;
+                                e.set_duration(next_dur, default);
                             }
                         } else {
-                            let first_dur = md5
-                                .as_ref()
-                                .and_then(|m| {
-                                    self.songlength_db.as_ref().and_then(|db| db.lookup(m, 0))
-                                })
-                                .or_else(|| {
-                                    let d = self.config.default_song_length_secs;
-                                    if d > 0 {
-                                        Some(d)
-                                    } else {
-                                        None
-                                    }
-                                });
+                            let first_dur = md5.as_ref().and_then(|m| {
+                                self.songlength_db.as_ref().and_then(|db| db.lookup(m, 0))
+                            });
+                            let default = self.config.default_song_length_secs;
                             if let Some(e) = self.playlist.entries.get_mut(cur_idx) {
                                 e.selected_song = 1;
-                                e.duration_secs = first_dur;
+                                e.set_duration(first_dur, default);
                             }
                             if let Some(idx) = self.playlist.next() {
                                 self.play_track(idx);
@@ -4472,24 +5001,15 @@ impl App {
                 .entries
                 .get(cur_idx)
                 .and_then(|e| e.md5.clone());
-            let new_dur = md5
-                .as_deref()
-                .and_then(|m| {
-                    self.songlength_db
-                        .as_ref()
-                        .and_then(|db| db.lookup(m, (song - 1) as usize))
-                })
-                .or_else(|| {
-                    let d = self.config.default_song_length_secs;
-                    if d > 0 {
-                        Some(d)
-                    } else {
-                        None
-                    }
-                });
+            let new_dur = md5.as_deref().and_then(|m| {
+                self.songlength_db
+                    .as_ref()
+                    .and_then(|db| db.lookup(m, (song - 1) as usize))
+            });
+            let default = self.config.default_song_length_secs;
             if let Some(e) = self.playlist.entries.get_mut(cur_idx) {
                 e.selected_song = song;
-                e.duration_secs = new_dur;
+                e.set_duration(new_dur, default);
             }
         }
     }
@@ -4579,6 +5099,8 @@ impl App {
                 engine: self.config.output_engine.clone(),
                 is_favorite: is_favorite_current,
                 master_volume: self.config.master_volume,
+                tempo_pct: crate::tempo::current(),
+                tempo_pitch_follows: self.config.tempo_pitch_follows,
                 shuffle: self.playlist.shuffle,
                 repeat: match self.playlist.repeat {
                     playlist::RepeatMode::Off => "off",
@@ -4595,6 +5117,8 @@ impl App {
                 skip_rsid: self.config.skip_rsid,
                 force_stereo_2sid: self.config.force_stereo_2sid,
                 surprise_source: self.config.surprise_source.clone(),
+                voice_muted: self.voice_mask.muted,
+                voice_soloed: self.voice_mask.soloed,
             };
 
             // Snapshot hvsc_root + published manifest for the library
@@ -4707,6 +5231,21 @@ impl App {
                     self.send_cmd(PlayerCmd::SetSubtune(n));
                     self.clear_advance_status();
                 }
+                remote::RemoteCmd::Seek(secs) => {
+                    tasks.push(Task::done(Message::SeekTo(secs)));
+                }
+                remote::RemoteCmd::ToggleVoiceMute(sid, voice) => {
+                    tasks.push(Task::done(Message::ToggleVoiceMute(sid, voice)));
+                }
+                remote::RemoteCmd::ToggleVoiceSolo(sid, voice) => {
+                    tasks.push(Task::done(Message::ToggleVoiceSolo(sid, voice)));
+                }
+                remote::RemoteCmd::ToggleSidMute(sid) => {
+                    tasks.push(Task::done(Message::ToggleSidMute(sid)));
+                }
+                remote::RemoteCmd::ToggleSidSolo(sid) => {
+                    tasks.push(Task::done(Message::ToggleSidSolo(sid)));
+                }
 
                 // ── Playback QOL (in-line, no Task needed) ───────────
                 remote::RemoteCmd::ToggleFavorite(idx) => {
@@ -4748,6 +5287,14 @@ impl App {
                     crate::audio_volume::set(clamped);
                     self.config.save();
                 }
+                remote::RemoteCmd::SetTempo(pct) => {
+                    crate::tempo::set(pct);
+                }
+                remote::RemoteCmd::SetTempoPitchFollows(on) => {
+                    self.config.tempo_pitch_follows = on;
+                    crate::tempo::set_pitch_follows(on);
+                    self.config.save();
+                }
 
                 // ── Ops that reuse full Message handlers via Task::done
                 remote::RemoteCmd::Surprise => {
@@ -4903,6 +5450,94 @@ impl App {
         }
     }
 
+    /// Push the now-playing snapshot to the MPRIS D-Bus thread.
+    #[cfg(target_os = "linux")]
+    fn update_mpris_state(&self) {
+        let Ok(mut ms) = self.mpris_state.try_lock() else {
+            return;
+        };
+        let hvsc_root = self.config.hvsc_root.as_deref().map(std::path::Path::new);
+        ms.status = match self.status.state {
+            PlayState::Playing => "Playing",
+            PlayState::Paused => "Paused",
+            PlayState::Stopped => "Stopped",
+        };
+        ms.track = self
+            .status
+            .track_info
+            .as_ref()
+            .map(|info| mpris::MprisTrack {
+                index: self.playlist.current.unwrap_or(0),
+                title: info.name.clone(),
+                author: info.author.clone(),
+                released: info.released.clone(),
+                current_song: info.current_song,
+                songs: info.songs,
+                hvsc_path: hvsc_root
+                    .and_then(|root| info.path.strip_prefix(root).ok())
+                    .map(|rel| format!("/{}", rel.to_string_lossy().replace('\\', "/"))),
+                url: url::Url::from_file_path(&info.path).ok().map(String::from),
+                duration_secs: self.playlist.current_entry().and_then(|e| e.duration_secs),
+            });
+        ms.elapsed = self.status.elapsed;
+        ms.volume = self.config.master_volume;
+        let wraps = self.playlist.shuffle || self.playlist.repeat == playlist::RepeatMode::All;
+        ms.can_go_next = self
+            .playlist
+            .current
+            .is_some_and(|c| wraps || c + 1 < self.playlist.len());
+        ms.can_go_previous = self.playlist.current.is_some();
+    }
+
+    /// Process commands from the MPRIS D-Bus service. Each maps onto
+    /// the `Message` the matching desktop control sends.
+    #[cfg(target_os = "linux")]
+    fn poll_mpris_commands(&mut self) -> Task<Message> {
+        let mut tasks: Vec<Task<Message>> = Vec::new();
+        while let Ok(cmd) = self.mpris_cmd_rx.try_recv() {
+            let playing = self.status.state == PlayState::Playing;
+            let msg = match cmd {
+                mpris::MprisCmd::Play if playing => continue,
+                mpris::MprisCmd::Pause if !playing => continue,
+                mpris::MprisCmd::Play | mpris::MprisCmd::Pause | mpris::MprisCmd::PlayPause => {
+                    Message::PlayPause
+                }
+                mpris::MprisCmd::Stop => Message::Stop,
+                mpris::MprisCmd::Next => Message::NextTrack,
+                mpris::MprisCmd::Previous => Message::PrevTrack,
+                mpris::MprisCmd::Seek(offset_us) => {
+                    let target = self.status.elapsed.as_micros() as i64 + offset_us;
+                    let length = self.playlist.current_entry().and_then(|e| e.duration_secs);
+                    // MPRIS: seeking past the end acts like Next.
+                    if length.is_some_and(|l| target >= l as i64 * 1_000_000) {
+                        Message::NextTrack
+                    } else {
+                        Message::SeekTo((target.max(0) / 1_000_000) as u32)
+                    }
+                }
+                mpris::MprisCmd::SetPosition(track_id, pos_us) => {
+                    let current = self
+                        .mpris_state
+                        .lock()
+                        .ok()
+                        .and_then(|ms| ms.track.as_ref().map(|t| t.track_id()));
+                    // Stale requests for a previous track are ignored.
+                    if pos_us < 0 || current.as_deref() != Some(track_id.as_str()) {
+                        continue;
+                    }
+                    Message::SeekTo((pos_us / 1_000_000) as u32)
+                }
+                mpris::MprisCmd::SetVolume(v) => Message::VolumeChanged(v),
+            };
+            tasks.push(Task::done(msg));
+        }
+        if tasks.is_empty() {
+            Task::none()
+        } else {
+            Task::batch(tasks)
+        }
+    }
+
     /// Realise a single SID at an absolute path (typically inside the
     /// HVSC tree, but any path works), add it to the playlist, apply
     /// songlengths, and optionally start playback. Shared by the two
@@ -4938,18 +5573,143 @@ impl App {
                     song,
                     force_stereo: self.config.force_stereo_2sid
                         || std::env::args().any(|a| a == "--stereo"),
-                    sid4_addr: parse_sid4_from_args(),
                     audio_port: if self.config.u64_audio_enabled {
                         Some(self.config.u64_audio_port)
                     } else {
                         None
                     },
                     restart_usb_on_load: self.config.restart_usb_on_load,
+                    force_pal: None,
                 });
             }
         }
     }
 
+    /// Queue a tune for background length estimation when neither the
+    /// HVSC DB nor the local overlay knows it.
+    fn queue_length_estimate(&mut self, idx: usize) {
+        let Some(entry) = self.playlist.entries.get(idx) else {
+            return;
+        };
+        let Some(md5) = entry.md5.clone() else {
+            return;
+        };
+        let known = self.songlength_db.as_ref().is_some_and(|db| {
+            db.has_overlay(&md5)
+                || db
+                    .lookup_with_path_fallback(
+                        Some(&md5),
+                        &entry.path,
+                        self.config.hvsc_root.as_deref().map(std::path::Path::new),
+                        0,
+                    )
+                    .is_some()
+        });
+        if known || !self.length_pending.insert(md5.clone()) {
+            return;
+        }
+        self.length_queue.push_back((md5, entry.path.clone()));
+    }
+
+    /// Save a finished estimate to the overlay and fill in entries that
+    /// had no length, or only the configured default standing in for one.
+    fn apply_length_estimate(&mut self, md5: &str, lengths: Vec<(u32, u32)>) {
+        self.length_pending.remove(md5);
+        let db = self
+            .songlength_db
+            .get_or_insert_with(SonglengthDb::overlay_only);
+        db.set_overlay(md5, lengths.clone());
+
+        for e in &mut self.playlist.entries {
+            if e.md5.as_deref() != Some(md5) {
+                continue;
+            }
+            let sub = e.selected_song.saturating_sub(1) as usize;
+            let Some(&(secs, _)) = lengths.get(sub).filter(|&&(d, _)| d > 0) else {
+                continue;
+            };
+            if e.duration_secs.is_none() || e.duration_is_default {
+                e.set_duration(Some(secs), 0);
+            }
+        }
+    }
+
+    /// Start the next queued length estimate if none is running.
+    fn start_length_estimate(&mut self) -> Task<Message> {
+        if self.length_running {
+            return Task::none();
+        }
+        let Some((md5, path)) = self.length_queue.pop_front() else {
+            return Task::none();
+        };
+        self.length_running = true;
+        eprintln!("[phosphor] Estimating song length for {}", path.display());
+        Task::perform(
+            async move {
+                let result =
+                    tokio::task::spawn_blocking(move || player::length_estimate::estimate(&path))
+                        .await
+                        .unwrap_or_else(|e| Err(format!("Estimate task failed: {e}")));
+                (md5, result)
+            },
+            |(md5, result)| Message::LengthEstimated(md5, result),
+        )
+    }
+
+    /// Set the loudness-normalisation gain for the playing entry. Unity
+    /// when normalisation is off or the tune hasn't been measured yet.
+    fn apply_loudness_gain(&self) {
+        let target = self.config.loudness_target_lufs;
+        let gain = self
+            .playlist
+            .current_entry()
+            .and_then(|e| e.md5.as_deref())
+            .and_then(|md5| self.loudness_db.get(md5))
+            .filter(|_| target != 0)
+            .map(|m| loudness::track_gain(m, target as f32))
+            .unwrap_or(1.0);
+        crate::audio_volume::set_track_gain(gain);
+    }
+
+    /// Queue a tune for background loudness measurement when
+    /// normalisation is on and the tune hasn't been measured.
+    fn queue_loudness_scan(&mut self, idx: usize) {
+        if self.config.loudness_target_lufs == 0 {
+            return;
+        }
+        let Some(entry) = self.playlist.entries.get(idx) else {
+            return;
+        };
+        let Some(md5) = entry.md5.clone() else {
+            return;
+        };
+        if self.loudness_db.get(&md5).is_some() || !self.loudness_pending.insert(md5.clone()) {
+            return;
+        }
+        self.loudness_queue.push_back((md5, entry.path.clone()));
+    }
+
+    /// Start the next queued loudness measurement if none is running.
+    fn start_loudness_scan(&mut self) -> Task<Message> {
+        if self.loudness_running {
+            return Task::none();
+        }
+        let Some((md5, path)) = self.loudness_queue.pop_front() else {
+            return Task::none();
+        };
+        self.loudness_running = true;
+        Task::perform(
+            async move {
+                let result =
+                    tokio::task::spawn_blocking(move || player::loudness_scan::measure(&path))
+                        .await
+                        .unwrap_or_else(|e| Err(format!("Loudness task failed: {e}")));
+                (md5, result)
+            },
+            |(md5, result)| Message::LoudnessMeasured(md5, result),
+        )
+    }
+
     fn apply_songlengths(&mut self) {
         if let Some(ref db) = self.songlength_db {
             db.apply_to_playlist(
@@ -5199,7 +5959,7 @@ fn apply_default_length(playlist: &mut Playlist, default_secs: u32) {
             if is_mus {
                 continue;
             }
-            entry.duration_secs = Some(default_secs);
+            entry.set_duration(None, default_secs);
             count += 1;
         }
     }
@@ -5418,6 +6178,97 @@ async fn save_playlist_dialog(
     }
 }
 
+/// Length of a headless SIDdump export when the tune has no known
+/// duration.
+const SIDDUMP_DEFAULT_SECS: u32 = 180;
+
+/// Where a SIDdump export's rows come from.
+enum SidDumpSource {
+    /// Headless run of `frames` play calls.
+    Headless {
+        path: PathBuf,
+        song: u16,
+        frames: u32,
+    },
+    /// Already rendered (live tracker history).
+    Text(String),
+}
+
+fn siddump_file_name(path: &std::path::Path, song: u16) -> String {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "tune".into());
+    format!("{stem}-{song}.txt")
+}
+
+/// Build the PRG for `path` / `song`, ask where to save it, and write it.
+async fn save_prg_dialog(path: PathBuf, song: u16) -> Result<PathBuf, String> {
+    let prg = build_prg_file(&path, song)?;
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "tune".into());
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title("Export as PRG")
+        .add_filter("C64 program", &["prg"])
+        .set_file_name(format!("{stem}-{song}.prg"))
+        .save_file()
+        .await
+        .ok_or_else(|| "Cancelled".to_string())?;
+    let out = handle.path().to_path_buf();
+    std::fs::write(&out, prg).map_err(|e| format!("Cannot write {}: {e}", out.display()))?;
+    Ok(out)
+}
+
+fn build_prg_file(path: &std::path::Path, song: u16) -> Result<Vec<u8>, String> {
+    let data = std::fs::read(path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+    let sid = player::sid_file::load_sid(&data)?;
+    prg_export::build(&sid, song)
+}
+
+async fn pick_write_log_dialog(file_name: String) -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Record SID write log")
+        .add_filter("SID write log", &[sid_log::EXTENSION])
+        .set_file_name(file_name)
+        .save_file()
+        .await
+        .map(|h| h.path().to_path_buf())
+}
+
+async fn pick_replay_file_dialog() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Replay SID write log")
+        .add_filter("SID write log", &[sid_log::EXTENSION])
+        .add_filter("All files", &["*"])
+        .pick_file()
+        .await
+        .map(|h| h.path().to_path_buf())
+}
+
+async fn save_siddump_dialog(source: SidDumpSource, file_name: String) -> Result<PathBuf, String> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title("Export SIDdump")
+        .add_filter("Text", &["txt"])
+        .set_file_name(file_name)
+        .save_file()
+        .await
+        .ok_or_else(|| "Cancelled".to_string())?;
+    let path = handle.path().to_path_buf();
+    let text = match source {
+        SidDumpSource::Text(text) => text,
+        SidDumpSource::Headless { path, song, frames } => {
+            tokio::task::spawn_blocking(move || player::regdump::dump(&path, Some(song), frames))
+                .await
+                .map_err(|e| format!("Dump task failed: {e}"))??
+                .0
+        }
+    };
+    std::fs::write(&path, text).map_err(|e| format!("Cannot write {}: {e}", path.display()))?;
+    Ok(path)
+}
+
 fn write_m3u(
     path: &std::path::Path,
     entries: &[(PathBuf, String, String, Option<u32>)],
@@ -5583,22 +6434,385 @@ fn check_numsids(root: &std::path::Path) {
     );
 }
 
-fn parse_sid4_from_args() -> u16 {
-    let args: Vec<String> = std::env::args().collect();
-    args.windows(2)
-        .find(|w| w[0] == "--sid4")
-        .and_then(|w| parse_hex_addr(&w[1]))
-        .unwrap_or(0)
+/// Argument cursor handed to a headless subcommand's option handler.
+struct CliArgs<'a> {
+    it: std::slice::Iter<'a, String>,
+}
+
+impl CliArgs<'_> {
+    /// The value following option `name`.
+    fn value(&mut self, name: &str) -> Result<String, String> {
+        self.it
+            .next()
+            .cloned()
+            .ok_or_else(|| format!("{name} needs a value"))
+    }
+
+    /// The value following option `name`, parsed.
+    fn parse<T: std::str::FromStr>(&mut self, name: &str) -> Result<T, String> {
+        let v = self.value(name)?;
+        v.parse().map_err(|_| format!("Bad {name} '{v}'"))
+    }
+
+    /// The value following option `name`, parsed and kept within `range`.
+    fn parse_in<T: std::str::FromStr + PartialOrd>(
+        &mut self,
+        name: &str,
+        range: std::ops::RangeInclusive<T>,
+    ) -> Result<T, String> {
+        let v = self.value(name)?;
+        v.parse()
+            .ok()
+            .filter(|n| range.contains(n))
+            .ok_or_else(|| format!("Bad {name} '{v}'"))
+    }
+}
+
+/// Parse a headless subcommand's arguments: one input path plus the
+/// options `on_opt` knows. `on_opt` returns `Ok(false)` for an option it
+/// doesn't take. On a bad command line the error and `usage` are printed
+/// and the exit code comes back as the error.
+fn parse_cli_args(
+    args: &[String],
+    usage: &str,
+    mut on_opt: impl FnMut(&str, &mut CliArgs) -> Result<bool, String>,
+) -> Result<PathBuf, i32> {
+    let mut input: Option<PathBuf> = None;
+    let mut cli = CliArgs { it: args.iter() };
+    while let Some(arg) = cli.it.next() {
+        let parsed = match arg.as_str() {
+            other if other.starts_with('-') => match on_opt(other, &mut cli) {
+                Ok(true) => Ok(()),
+                Ok(false) => Err(format!("Unknown option '{other}'")),
+                Err(e) => Err(e),
+            },
+            other if input.is_none() => {
+                input = Some(PathBuf::from(other));
+                Ok(())
+            }
+            other => Err(format!("Unexpected argument '{other}'")),
+        };
+        if let Err(e) = parsed {
+            eprintln!("{e}\n{usage}");
+            return Err(2);
+        }
+    }
+    input.ok_or_else(|| {
+        eprintln!("{usage}");
+        2
+    })
+}
+
+/// `phosphor --check-numsids <file-or-directory>`.
+fn check_numsids_cli(args: &[String]) -> i32 {
+    const USAGE: &str = "Usage: phosphor --check-numsids <file-or-directory>";
+
+    match parse_cli_args(args, USAGE, |_, _| Ok(false)) {
+        Ok(root) => {
+            check_numsids(&root);
+            0
+        }
+        Err(code) => code,
+    }
+}
+
+/// `phosphor render <file.sid> [--song N] [--seconds S]
+/// [--engine resid|sidlite] [--rate HZ] -o <out.wav|out.flac>`.
+/// Returns the process exit code.
+fn render_cli(args: &[String]) -> i32 {
+    const USAGE: &str = "Usage: phosphor render <file.sid> [--song N] [--seconds S] \
+                         [--engine resid|sidlite] [--rate HZ] -o <out.wav|out.flac>";
+
+    let mut opts = player::render::RenderOptions {
+        input: PathBuf::new(),
+        output: PathBuf::new(),
+        song: None,
+        seconds: None,
+        engine: "resid".to_string(),
+        sample_rate: 44_100,
+    };
+    let mut output: Option<PathBuf> = None;
+
+    let input = match parse_cli_args(args, USAGE, |arg, cli| {
+        match arg {
+            "--song" => opts.song = Some(cli.parse(arg)?),
+            "--seconds" => opts.seconds = Some(cli.parse(arg)?),
+            "--engine" => opts.engine = cli.value(arg)?,
+            "--rate" => opts.sample_rate = cli.parse_in(arg, 8_000..=192_000)?,
+            "-o" | "--output" => output = Some(PathBuf::from(cli.value(arg)?)),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }) {
+        Ok(input) => input,
+        Err(code) => return code,
+    };
+    let Some(output) = output else {
+        eprintln!("{USAGE}");
+        return 2;
+    };
+    opts.input = input;
+    opts.output = output;
+
+    match player::render::render(&opts) {
+        Ok(summary) => {
+            println!(
+                "{}: song {}, {}s, {} samples @ {}Hz",
+                opts.output.display(),
+                summary.song,
+                summary.seconds,
+                summary.samples,
+                summary.sample_rate,
+            );
+            0
+        }
+        Err(e) => {
+            eprintln!("[render] {e}");
+            1
+        }
+    }
 }
 
-fn parse_hex_addr(s: &str) -> Option<u16> {
-    let s = s.trim();
-    let hex = s
-        .strip_prefix('$')
-        .or_else(|| s.strip_prefix("0x"))
-        .or_else(|| s.strip_prefix("0X"))
-        .unwrap_or(s);
-    u16::from_str_radix(hex, 16).ok()
+/// `phosphor --siddump <file.sid> ...` — headless SIDdump-style register
+/// dump to stdout or `-o`. Returns the process exit code.
+fn siddump_cli(args: &[String]) -> i32 {
+    const USAGE: &str =
+        "Usage: phosphor --siddump <file.sid> [--song N] [--frames N] [-o <out.txt>]";
+    /// 60 s of PAL frames, same as siddump's default run.
+    const DEFAULT_FRAMES: u32 = 3000;
+
+    let mut output: Option<PathBuf> = None;
+    let mut song: Option<u16> = None;
+    let mut frames = DEFAULT_FRAMES;
+
+    let input = match parse_cli_args(args, USAGE, |arg, cli| {
+        match arg {
+            "--song" => song = Some(cli.parse(arg)?),
+            "--frames" => frames = cli.parse_in(arg, 1..=u32::MAX)?,
+            "-o" | "--output" => output = Some(PathBuf::from(cli.value(arg)?)),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }) {
+        Ok(input) => input,
+        Err(code) => return code,
+    };
+
+    let text = match player::regdump::dump(&input, song, frames) {
+        Ok((text, song)) => {
+            eprintln!(
+                "[siddump] {}: song {song}, {frames} frames",
+                input.display()
+            );
+            text
+        }
+        Err(e) => {
+            eprintln!("[siddump] {e}");
+            return 1;
+        }
+    };
+    match output {
+        Some(path) => match std::fs::write(&path, text) {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("[siddump] Cannot write {}: {e}", path.display());
+                1
+            }
+        },
+        None => {
+            print!("{text}");
+            0
+        }
+    }
+}
+
+/// `phosphor --prg <file.sid> ...` — write a standalone C64 PRG that
+/// plays the tune. Defaults to `<file>.prg` next to the input.
+fn prg_cli(args: &[String]) -> i32 {
+    const USAGE: &str = "Usage: phosphor --prg <file.sid> [--song N] [-o <out.prg>]";
+
+    let mut output: Option<PathBuf> = None;
+    let mut song: Option<u16> = None;
+
+    let input = match parse_cli_args(args, USAGE, |arg, cli| {
+        match arg {
+            "--song" => song = Some(cli.parse(arg)?),
+            "-o" | "--output" => output = Some(PathBuf::from(cli.value(arg)?)),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }) {
+        Ok(input) => input,
+        Err(code) => return code,
+    };
+    let output = output.unwrap_or_else(|| input.with_extension("prg"));
+
+    let song = match song {
+        Some(n) => n,
+        None => match std::fs::read(&input)
+            .map_err(|e| e.to_string())
+            .and_then(|d| player::sid_file::parse_header(&d))
+        {
+            Ok(h) => h.start_song,
+            Err(e) => {
+                eprintln!("[prg] {}: {e}", input.display());
+                return 1;
+            }
+        },
+    };
+    let prg = match build_prg_file(&input, song) {
+        Ok(prg) => prg,
+        Err(e) => {
+            eprintln!("[prg] {}: {e}", input.display());
+            return 1;
+        }
+    };
+    match std::fs::write(&output, &prg) {
+        Ok(()) => {
+            eprintln!(
+                "[prg] {}: song {song}, {} bytes → {}",
+                input.display(),
+                prg.len(),
+                output.display()
+            );
+            0
+        }
+        Err(e) => {
+            eprintln!("[prg] Cannot write {}: {e}", output.display());
+            1
+        }
+    }
+}
+
+/// `phosphor --compare <file-or-dir> ...` — run each tune on libsidplayfp
+/// and on our c64_emu core and report the first frame where their SID
+/// writes differ. One line per tune; the full report is printed for a
+/// single file or with `-v`. Exits 1 if any tune diverged or failed, so
+/// it can gate a regression run over a whole HVSC subtree.
+fn compare_cli(args: &[String]) -> i32 {
+    use player::refcompare::{compare, Outcome};
+    use walkdir::WalkDir;
+
+    const USAGE: &str =
+        "Usage: phosphor --compare <file-or-directory> [--song N] [--frames N] [-v]";
+    /// 30 s of PAL frames.
+    const DEFAULT_FRAMES: u32 = 1500;
+
+    let mut song: Option<u16> = None;
+    let mut frames = DEFAULT_FRAMES;
+    let mut verbose = false;
+
+    let root = match parse_cli_args(args, USAGE, |arg, cli| {
+        match arg {
+            "--song" => song = Some(cli.parse(arg)?),
+            "--frames" => frames = cli.parse_in(arg, 1..=u32::MAX)?,
+            "-v" | "--verbose" => verbose = true,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }) {
+        Ok(root) => root,
+        Err(code) => return code,
+    };
+    let verbose = verbose || root.is_file();
+
+    let (mut matched, mut diverged, mut errors) = (0usize, 0usize, 0usize);
+    for dirent in WalkDir::new(&root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        let p = dirent.path();
+        let is_sid = p
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("sid"));
+        if !p.is_file() || !is_sid {
+            continue;
+        }
+        match compare(p, song, frames) {
+            Ok((Outcome::Match { frames }, song)) => {
+                matched += 1;
+                println!("[match] {}: song {song}, {frames} frames", p.display());
+            }
+            Ok((Outcome::Diverged(d), song)) => {
+                diverged += 1;
+                println!(
+                    "[DIFF ] {}: song {song}, frame {} write #{} (libsidplayfp frame {} write #{})",
+                    p.display(),
+                    d.our_frame,
+                    d.write,
+                    d.ref_frame,
+                    d.ref_write
+                );
+                if verbose {
+                    println!("{}", d.report());
+                }
+            }
+            Err(e) => {
+                errors += 1;
+                println!("[ERR  ] {}: {e}", p.display());
+            }
+        }
+    }
+
+    eprintln!(
+        "\n--- compare summary ---\n\
+         match       : {matched}\n\
+         diverged    : {diverged}\n\
+         errors      : {errors}"
+    );
+    if diverged + errors > 0 {
+        1
+    } else {
+        0
+    }
+}
+
+/// Run the headless subcommand named on the command line, if any, and
+/// return its exit code. `None` means launch the GUI.
+///
+/// - `phosphor --check-numsids <path>` — diagnostic walk over a directory
+///   of .sid files through the regular `PlaylistEntry::from_path`,
+///   printing num_sids and the relevant header bytes per file. Output is
+///   identical to what Phosphor records at load time, so any "library
+///   says 2SID, files-picker says 1" mismatch is reproducible here too.
+/// - `phosphor render <file.sid> --song N --seconds S --engine resid -o out.wav`
+///   — offline render through reSID / SIDLite to WAV or FLAC.
+/// - `phosphor --siddump <file.sid> --song N --frames 3000 -o dump.txt`
+///   — SIDdump-style register dump for transcription.
+/// - `phosphor --prg <file.sid> --song N -o tune.prg` — standalone C64
+///   PRG for real hardware / SD cards.
+/// - `phosphor --compare <file-or-dir> --frames 1500 -v` — accuracy check
+///   of our own C64 core against libsidplayfp.
+/// - `phosphor --tui [files, folders or playlists...]` — terminal
+///   front-end for headless machines.
+fn run_subcommand(args: &[String]) -> Option<i32> {
+    const FLAGS: [(&str, fn(&[String]) -> i32); 4] = [
+        ("--check-numsids", check_numsids_cli),
+        ("--siddump", siddump_cli),
+        ("--prg", prg_cli),
+        ("--compare", compare_cli),
+    ];
+
+    if args.get(1).map(String::as_str) == Some("render") {
+        return Some(render_cli(&args[2..]));
+    }
+    for (flag, run) in FLAGS {
+        if let Some(i) = args.iter().position(|a| a == flag) {
+            return Some(run(&args[i + 1..]));
+        }
+    }
+    if args.iter().any(|a| a == "--tui") {
+        let paths = args
+            .iter()
+            .skip(1)
+            .filter(|a| !a.starts_with("--"))
+            .map(PathBuf::from)
+            .collect();
+        return Some(tui::run(paths));
+    }
+    None
 }
 
 async fn flush_frame() {
@@ -5657,29 +6871,11 @@ fn main() -> iced::Result {
     env_logger::init();
     debug_log::init();
 
-    // Diagnostic subcommand: walk a directory of .sid files, call the
-    // regular `PlaylistEntry::from_path` on each (the same function
-    // every load path in Phosphor uses), and print num_sids + relevant
-    // header bytes per file. Exits without launching the GUI.
-    //
-    //   phosphor --check-numsids <path>
-    //
-    // The output is identical to what Phosphor would record at load
-    // time, so any "library says 2SID, files-picker says 1" mismatch
-    // is reproducible here too — or proven NOT to be in `from_path`.
+    // Headless subcommands run instead of the GUI and exit.
     {
         let args: Vec<String> = std::env::args().collect();
-        if let Some(i) = args.iter().position(|a| a == "--check-numsids") {
-            match args.get(i + 1).cloned() {
-                Some(p) => {
-                    check_numsids(std::path::Path::new(&p));
-                    return Ok(());
-                }
-                None => {
-                    eprintln!("Usage: phosphor --check-numsids <file-or-directory>");
-                    std::process::exit(2);
-                }
-            }
+        if let Some(code) = run_subcommand(&args) {
+            std::process::exit(code);
         }
     }
 
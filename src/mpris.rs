@@ -0,0 +1,432 @@
+// MPRIS D-Bus interface (Linux only).
+//
+// Publishes `org.mpris.MediaPlayer2.phosphor` on the session bus so media
+// keys, GNOME/KDE panel widgets and `playerctl` can drive Phosphor. Same
+// shape as the HTTP remote: the App pushes a `MprisState` snapshot on
+// every Tick, and D-Bus method calls come back as `MprisCmd`s polled on
+// Tick. A background thread owns the connection and emits
+// PropertiesChanged / Seeked when the snapshot moves.
+//
+// Try it headless against a private bus:
+//
+//   dbus-run-session -- sh -c 'phosphor & sleep 2; playerctl -p phosphor metadata'
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::{ObjectPath, OwnedValue, Value};
+use zbus::{fdo, interface};
+
+const BUS_NAME: &str = "org.mpris.MediaPlayer2.phosphor";
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+/// `mpris:trackid` when nothing is loaded.
+const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+/// How often the bus thread compares snapshots.
+const POLL: Duration = Duration::from_millis(250);
+/// A position jump this far from the expected one counts as a seek.
+const SEEK_SLACK: Duration = Duration::from_millis(1500);
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Commands sent from D-Bus → App (polled on Tick)
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub enum MprisCmd {
+    Play,
+    Pause,
+    PlayPause,
+    Stop,
+    Next,
+    Previous,
+    /// Relative seek in microseconds, may be negative.
+    Seek(i64),
+    /// Absolute position in microseconds within the given track id.
+    SetPosition(String, i64),
+    SetVolume(f32),
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Shared state: App → D-Bus thread (updated on every Tick)
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Default, PartialEq)]
+pub struct MprisTrack {
+    /// Playlist index; becomes the `mpris:trackid` object path.
+    pub index: usize,
+    pub title: String,
+    pub author: String,
+    pub released: String,
+    pub current_song: u16,
+    pub songs: u16,
+    /// HVSC-relative path (`/MUSICIANS/H/Hubbard_Rob/Commando.sid`) when
+    /// the file lives under the configured HVSC root.
+    pub hvsc_path: Option<String>,
+    /// `file://` URL of the tune.
+    pub url: Option<String>,
+    /// Subtune length from the Songlength DB.
+    pub duration_secs: Option<u32>,
+}
+
+#[derive(Clone, Default)]
+pub struct MprisState {
+    /// "Playing" | "Paused" | "Stopped", as MPRIS spells them.
+    pub status: &'static str,
+    pub track: Option<MprisTrack>,
+    pub elapsed: Duration,
+    pub volume: f32,
+    pub can_go_next: bool,
+    pub can_go_previous: bool,
+}
+
+impl MprisTrack {
+    pub fn track_id(&self) -> String {
+        format!("/org/mpris/MediaPlayer2/Track/{}", self.index)
+    }
+}
+
+/// The `Metadata` dict for `track`.
+fn metadata(track: Option<&MprisTrack>) -> HashMap<String, OwnedValue> {
+    let mut map = HashMap::new();
+    let mut put = |key: &str, value: Value<'_>| {
+        if let Ok(v) = OwnedValue::try_from(value) {
+            map.insert(key.to_string(), v);
+        }
+    };
+    let Some(t) = track else {
+        if let Ok(path) = ObjectPath::try_from(NO_TRACK) {
+            put("mpris:trackid", Value::from(path));
+        }
+        return map;
+    };
+    if let Ok(path) = ObjectPath::try_from(t.track_id()) {
+        put("mpris:trackid", Value::from(path));
+    }
+    if let Some(secs) = t.duration_secs {
+        put("mpris:length", Value::from(secs as i64 * 1_000_000));
+    }
+    put("xesam:title", Value::from(t.title.clone()));
+    put("xesam:artist", Value::from(vec![t.author.clone()]));
+    put("xesam:trackNumber", Value::from(t.current_song as i32));
+    if let Some(url) = &t.url {
+        put("xesam:url", Value::from(url.clone()));
+    }
+    put("phosphor:released", Value::from(t.released.clone()));
+    put("phosphor:subtune", Value::from(t.current_song as u32));
+    put("phosphor:subtunes", Value::from(t.songs as u32));
+    if let Some(p) = &t.hvsc_path {
+        put("phosphor:hvscPath", Value::from(p.clone()));
+    }
+    map
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  D-Bus interfaces
+// ─────────────────────────────────────────────────────────────────────────────
+
+struct Root;
+
+#[interface(name = "org.mpris.MediaPlayer2")]
+impl Root {
+    fn raise(&self) {}
+
+    fn quit(&self) {}
+
+    #[zbus(property)]
+    fn can_quit(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_raise(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn has_track_list(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn identity(&self) -> &str {
+        "Phosphor"
+    }
+
+    #[zbus(property)]
+    fn desktop_entry(&self) -> &str {
+        "phosphor"
+    }
+
+    #[zbus(property)]
+    fn supported_uri_schemes(&self) -> Vec<String> {
+        vec!["file".into()]
+    }
+
+    #[zbus(property)]
+    fn supported_mime_types(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+struct Player {
+    state: Arc<Mutex<MprisState>>,
+    cmd_tx: Sender<MprisCmd>,
+}
+
+impl Player {
+    fn snapshot(&self) -> MprisState {
+        self.state.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    fn send(&self, cmd: MprisCmd) {
+        if self.cmd_tx.try_send(cmd).is_err() {
+            eprintln!("[mpris] Command dropped — App not polling");
+        }
+    }
+}
+
+#[interface(name = "org.mpris.MediaPlayer2.Player")]
+impl Player {
+    fn play(&self) {
+        self.send(MprisCmd::Play);
+    }
+
+    fn pause(&self) {
+        self.send(MprisCmd::Pause);
+    }
+
+    fn play_pause(&self) {
+        self.send(MprisCmd::PlayPause);
+    }
+
+    fn stop(&self) {
+        self.send(MprisCmd::Stop);
+    }
+
+    fn next(&self) {
+        self.send(MprisCmd::Next);
+    }
+
+    fn previous(&self) {
+        self.send(MprisCmd::Previous);
+    }
+
+    fn seek(&self, offset: i64) {
+        self.send(MprisCmd::Seek(offset));
+    }
+
+    fn set_position(&self, track_id: ObjectPath<'_>, position: i64) {
+        self.send(MprisCmd::SetPosition(track_id.to_string(), position));
+    }
+
+    fn open_uri(&self, _uri: &str) -> fdo::Result<()> {
+        Err(fdo::Error::NotSupported("OpenUri is not supported".into()))
+    }
+
+    #[zbus(signal)]
+    async fn seeked(emitter: &SignalEmitter<'_>, position: i64) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn playback_status(&self) -> String {
+        let status = self.snapshot().status;
+        if status.is_empty() { "Stopped" } else { status }.to_string()
+    }
+
+    #[zbus(property)]
+    fn rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn set_rate(&self, _rate: f64) {}
+
+    #[zbus(property)]
+    fn minimum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn maximum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn metadata(&self) -> HashMap<String, OwnedValue> {
+        metadata(self.snapshot().track.as_ref())
+    }
+
+    #[zbus(property)]
+    fn volume(&self) -> f64 {
+        self.snapshot().volume as f64
+    }
+
+    #[zbus(property)]
+    fn set_volume(&self, volume: f64) {
+        self.send(MprisCmd::SetVolume(volume.clamp(0.0, 1.0) as f32));
+    }
+
+    #[zbus(property(emits_changed_signal = "false"))]
+    fn position(&self) -> i64 {
+        self.snapshot().elapsed.as_micros() as i64
+    }
+
+    #[zbus(property)]
+    fn can_go_next(&self) -> bool {
+        self.snapshot().can_go_next
+    }
+
+    #[zbus(property)]
+    fn can_go_previous(&self) -> bool {
+        self.snapshot().can_go_previous
+    }
+
+    #[zbus(property)]
+    fn can_play(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_pause(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_seek(&self) -> bool {
+        true
+    }
+
+    #[zbus(property(emits_changed_signal = "const"))]
+    fn can_control(&self) -> bool {
+        true
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Service thread
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Spawn the D-Bus service thread. Failing to reach the session bus (no
+/// desktop session, SSH login) is logged and otherwise ignored.
+pub fn start(state: Arc<Mutex<MprisState>>, cmd_tx: Sender<MprisCmd>) {
+    let spawned = thread::Builder::new()
+        .name("phosphor-mpris".into())
+        .spawn(move || {
+            if let Err(e) = serve(state, cmd_tx) {
+                eprintln!("[mpris] D-Bus service unavailable: {e}");
+            }
+        });
+    if let Err(e) = spawned {
+        eprintln!("[mpris] Failed to spawn thread: {e}");
+    }
+}
+
+fn serve(state: Arc<Mutex<MprisState>>, cmd_tx: Sender<MprisCmd>) -> zbus::Result<()> {
+    let player = Player {
+        state: Arc::clone(&state),
+        cmd_tx,
+    };
+    let conn = zbus::blocking::connection::Builder::session()?
+        .name(BUS_NAME)?
+        .serve_at(OBJECT_PATH, Root)?
+        .serve_at(OBJECT_PATH, player)?
+        .build()?;
+    eprintln!("[mpris] Registered {BUS_NAME}");
+
+    let iface = conn.object_server().interface::<_, Player>(OBJECT_PATH)?;
+    let emitter = iface.signal_emitter();
+    let mut last = state.lock().map(|s| s.clone()).unwrap_or_default();
+    let mut last_at = Instant::now();
+
+    loop {
+        thread::sleep(POLL);
+        let now = state.lock().map(|s| s.clone()).unwrap_or_default();
+        let player = iface.get();
+
+        if now.status != last.status {
+            zbus::block_on(player.playback_status_changed(emitter))?;
+        }
+        if now.track != last.track {
+            zbus::block_on(player.metadata_changed(emitter))?;
+        }
+        if now.volume != last.volume {
+            zbus::block_on(player.volume_changed(emitter))?;
+        }
+        if now.can_go_next != last.can_go_next {
+            zbus::block_on(player.can_go_next_changed(emitter))?;
+        }
+        if now.can_go_previous != last.can_go_previous {
+            zbus::block_on(player.can_go_previous_changed(emitter))?;
+        }
+        // Position isn't signalled; clients extrapolate from the last
+        // read. Tell them when it jumped, as MPRIS asks.
+        if now.track == last.track && jumped(&last, &now, last_at.elapsed()) {
+            zbus::block_on(Player::seeked(emitter, now.elapsed.as_micros() as i64))?;
+        }
+        drop(player);
+
+        last = now;
+        last_at = Instant::now();
+    }
+}
+
+/// Whether the position moved further than `wall` of playback explains.
+fn jumped(last: &MprisState, now: &MprisState, wall: Duration) -> bool {
+    let expected = if last.status == "Playing" {
+        last.elapsed + wall
+    } else {
+        last.elapsed
+    };
+    now.elapsed + SEEK_SLACK < expected || now.elapsed > expected + SEEK_SLACK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(status: &'static str, secs: u64) -> MprisState {
+        MprisState {
+            status,
+            elapsed: Duration::from_secs(secs),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn seek_detection_allows_for_playback() {
+        let wall = Duration::from_millis(250);
+        assert!(!jumped(&state("Playing", 10), &state("Playing", 10), wall));
+        assert!(jumped(&state("Playing", 10), &state("Playing", 40), wall));
+        assert!(jumped(&state("Playing", 40), &state("Playing", 0), wall));
+        assert!(!jumped(&state("Paused", 10), &state("Paused", 10), wall));
+        assert!(jumped(&state("Paused", 10), &state("Paused", 20), wall));
+    }
+
+    #[test]
+    fn metadata_carries_track_fields() {
+        let track = MprisTrack {
+            index: 3,
+            title: "Commando".into(),
+            author: "Rob Hubbard".into(),
+            released: "1985 Elite".into(),
+            current_song: 2,
+            songs: 3,
+            hvsc_path: Some("/MUSICIANS/H/Hubbard_Rob/Commando.sid".into()),
+            url: None,
+            duration_secs: Some(90),
+        };
+        let map = metadata(Some(&track));
+        assert_eq!(i64::try_from(&map["mpris:length"]).unwrap(), 90 * 1_000_000);
+        assert_eq!(i32::try_from(&map["xesam:trackNumber"]).unwrap(), 2);
+        assert_eq!(
+            <&str>::try_from(&map["phosphor:hvscPath"]).unwrap(),
+            "/MUSICIANS/H/Hubbard_Rob/Commando.sid"
+        );
+        assert!(!map.contains_key("xesam:url"));
+
+        let empty = metadata(None);
+        assert_eq!(empty.len(), 1);
+        assert!(empty.contains_key("mpris:trackid"));
+    }
+}
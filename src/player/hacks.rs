@@ -1,6 +1,25 @@
 // Tune-specific patches for known timing-critical RSID files.
 //
-// Ported from WebSid/compute! hacks.c + cpu_operations.inc by Jürgen Wothke.
+// The patches are data, not code: `assets/hacks.json` ships the built-in
+// set (ported from WebSid/compute! hacks.c + cpu_operations.inc by Jürgen
+// Wothke), and `<config_dir>/hacks.json` can add more or replace a
+// built-in entry of the same name, so a fix for a newly found tune doesn't
+// need a release. Same schema for both:
+//
+//   { "version": 1, "hacks": [ {
+//       "name":  "Feeling_Good",
+//       "note":  "NMI fires during RTI",              (optional)
+//       "md5":   "<hvsc md5>",                        (optional)
+//       "path":  "MUSICIANS/X/Xyz/Tune.sid",          (optional, path suffix)
+//       "init":  "1000",                              (optional, INIT address)
+//       "match": { "addr": "1cf7", "bytes": "ee e5 1c" },   (optional)
+//       "pokes": [ { "addr": "0835", "bytes": "0b" } ],     (optional)
+//       "flags": [ "nmi_needs_i_flag_clear" ]               (optional)
+//   } ] }
+//
+// Addresses and bytes are hex. Every criterion given must hold, and an
+// entry needs at least one. `match` compares RAM after the payload is
+// loaded; `pokes` are written there before INIT.
 //
 // cpuHackNMI(on) semantics (from cpu_operations.inc):
 //   _no_nmi_hack = !on
@@ -13,31 +32,36 @@
 // song load to reset the default, then individual patches call cpuHackNMI(1)
 // for songs where the NMI-must-wait-for-I-flag guard is needed.
 
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
 use super::rsid_bus::RsidBus;
 
+const BUILTIN: &str = include_str!("../../assets/hacks.json");
+
 // ─────────────────────────────────────────────────────────────────────────────
 //  Public API
 // ─────────────────────────────────────────────────────────────────────────────
 
-/// Apply any needed hack to `bus` for the tune with the given `init_addr`.
-/// Call this after the tune payload has been loaded into RAM but before INIT.
+/// Apply any needed hack to `bus` for the tune with the given `init_addr`,
+/// HVSC `md5` and file `path`. Call this after the tune payload has been
+/// loaded into RAM but before INIT.
 ///
 /// Returns `HackFlags` that must be honoured by both emulation loops.
-pub fn apply_hacks(bus: &mut RsidBus, init_addr: u16) -> HackFlags {
+pub fn apply_hacks(bus: &mut RsidBus, init_addr: u16, md5: &str, path: &Path) -> HackFlags {
     // Always reset to defaults first (mirrors cpuHackNMI(0) at top of hackIfNeeded)
     let mut flags = HackFlags::default();
-
-    patch_mr_meaner_if_needed(bus, init_addr, &mut flags);
-    patch_feeling_good_if_needed(bus, init_addr, &mut flags);
-    patch_4non_blondes_if_needed(bus, init_addr, &mut flags);
-    patch_game_player_if_needed(bus, init_addr, &mut flags);
-    patch_synthmeld_if_needed(bus, init_addr, &mut flags);
-    patch_immigrant_song_if_needed(bus, init_addr, &mut flags);
-    patch_utopia6_if_needed(bus, init_addr, &mut flags);
-    patch_swallow_if_needed(bus, init_addr, &mut flags);
-    patch_we_are_demo_if_needed(bus, init_addr, &mut flags);
-    patch_graphixsmania_if_needed(bus, init_addr, &mut flags);
-
+    let tune = TuneKey {
+        init_addr,
+        md5,
+        path,
+    };
+    for hack in load_db() {
+        if hack.matches(&bus.c64.ram.ram, &tune) {
+            hack.apply(&mut bus.c64.ram.ram, &mut flags);
+        }
+    }
     flags
 }
 
@@ -52,7 +76,7 @@ pub fn apply_hacks(bus: &mut RsidBus, init_addr: u16) -> HackFlags {
 ///
 /// `disable_badline_stun`:
 ///   When true, skip the BA stun loop entirely for this tune.
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct HackFlags {
     /// NMI may only fire when the CPU I-flag is clear.
     /// Mirrors: `_no_nmi_hack = 0` (cpuHackNMI(1)).
@@ -63,124 +87,294 @@ pub struct HackFlags {
     pub disable_badline_stun: bool,
 }
 
+impl HackFlags {
+    /// Set the flag called `name` in the database. False if unknown.
+    fn set_named(&mut self, name: &str) -> bool {
+        match name {
+            "nmi_needs_i_flag_clear" => self.nmi_needs_i_flag_clear = true,
+            "disable_badline_stun" => self.disable_badline_stun = true,
+            _ => return false,
+        }
+        true
+    }
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
-//  Helper
+//  Database
 // ─────────────────────────────────────────────────────────────────────────────
 
-fn mem_match(ram: &[u8], addr: usize, pattern: &[u8]) -> bool {
-    let end = addr + pattern.len();
-    if end > ram.len() {
-        return false;
+/// `hacks.json` as written on disk.
+#[derive(Debug, Deserialize)]
+struct HackFile {
+    #[serde(default)]
+    hacks: Vec<RawHack>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawHack {
+    name: String,
+    #[serde(default)]
+    note: String,
+    md5: Option<String>,
+    path: Option<String>,
+    init: Option<String>,
+    #[serde(rename = "match")]
+    pattern: Option<RawBytes>,
+    #[serde(default)]
+    pokes: Vec<RawBytes>,
+    #[serde(default)]
+    flags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawBytes {
+    addr: String,
+    bytes: String,
+}
+
+/// One validated database entry.
+#[derive(Debug, Clone, PartialEq)]
+struct Hack {
+    name: String,
+    note: String,
+    md5: Option<String>,
+    /// Lowercase, `/`-separated path suffix, matched on whole components.
+    path: Option<String>,
+    init: Option<u16>,
+    pattern: Option<(u16, Vec<u8>)>,
+    pokes: Vec<(u16, Vec<u8>)>,
+    flags: HackFlags,
+}
+
+/// What an entry can match a tune on.
+struct TuneKey<'a> {
+    init_addr: u16,
+    md5: &'a str,
+    path: &'a Path,
+}
+
+impl Hack {
+    fn from_raw(raw: RawHack) -> Result<Self, String> {
+        let mut flags = HackFlags::default();
+        for name in &raw.flags {
+            if !flags.set_named(name) {
+                return Err(format!("unknown flag \"{name}\""));
+            }
+        }
+        let hack = Self {
+            md5: raw.md5.map(|m| m.trim().to_lowercase()),
+            path: raw
+                .path
+                .map(|p| normalize_path(&p).trim_start_matches('/').to_string()),
+            init: raw.init.as_deref().map(parse_addr).transpose()?,
+            pattern: raw.pattern.map(parse_bytes_at).transpose()?,
+            pokes: raw
+                .pokes
+                .into_iter()
+                .map(parse_bytes_at)
+                .collect::<Result<_, _>>()?,
+            flags,
+            note: raw.note,
+            name: raw.name,
+        };
+        if hack.md5.is_none()
+            && hack.path.is_none()
+            && hack.init.is_none()
+            && hack.pattern.is_none()
+        {
+            return Err("needs at least one of md5, path, init or match".into());
+        }
+        Ok(hack)
     }
-    ram[addr..end] == *pattern
+
+    fn matches(&self, ram: &[u8], tune: &TuneKey) -> bool {
+        self.md5.as_ref().is_none_or(|m| m == tune.md5)
+            && self
+                .path
+                .as_ref()
+                .is_none_or(|p| path_ends_with(&normalize_path(&tune.path.to_string_lossy()), p))
+            && self.init.is_none_or(|a| a == tune.init_addr)
+            && self
+                .pattern
+                .as_ref()
+                .is_none_or(|(addr, bytes)| mem_match(ram, *addr as usize, bytes))
+    }
+
+    fn apply(&self, ram: &mut [u8], flags: &mut HackFlags) {
+        for (addr, bytes) in &self.pokes {
+            let start = *addr as usize;
+            let end = (start + bytes.len()).min(ram.len());
+            ram[start..end].copy_from_slice(&bytes[..end - start]);
+        }
+        flags.nmi_needs_i_flag_clear |= self.flags.nmi_needs_i_flag_clear;
+        flags.disable_badline_stun |= self.flags.disable_badline_stun;
+        if self.note.is_empty() {
+            eprintln!("[hacks] {}", self.name);
+        } else {
+            eprintln!("[hacks] {}: {}", self.name, self.note);
+        }
+    }
+}
+
+/// Built-in entries, with `<config_dir>/hacks.json` merged on top: a user
+/// entry replaces the built-in one of the same name, others are added.
+fn load_db() -> Vec<Hack> {
+    let mut hacks = parse_db(BUILTIN, "built-in hacks.json");
+    let Some(p) = user_db_path() else {
+        return hacks;
+    };
+    let Ok(text) = std::fs::read_to_string(&p) else {
+        return hacks;
+    };
+    for hack in parse_db(&text, &p.display().to_string()) {
+        match hacks.iter_mut().find(|h| h.name == hack.name) {
+            Some(slot) => *slot = hack,
+            None => hacks.push(hack),
+        }
+    }
+    hacks
+}
+
+/// Parse a database, skipping (and logging) entries that don't validate.
+fn parse_db(text: &str, source: &str) -> Vec<Hack> {
+    let file: HackFile = match serde_json::from_str(text) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("[hacks] {source}: {e}");
+            return Vec::new();
+        }
+    };
+    file.hacks
+        .into_iter()
+        .filter_map(|raw| {
+            let name = raw.name.clone();
+            Hack::from_raw(raw)
+                .map_err(|e| eprintln!("[hacks] {source}: skipping \"{name}\": {e}"))
+                .ok()
+        })
+        .collect()
+}
+
+fn user_db_path() -> Option<PathBuf> {
+    crate::config::config_dir().map(|d| d.join("hacks.json"))
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
-//  Individual patches
+//  Helpers
 // ─────────────────────────────────────────────────────────────────────────────
 
-/// Mr_Meaner.sid — NMI/IRQ handling issue.
-/// Disable badline stun; the INT-flag gets stuck otherwise.
-fn patch_mr_meaner_if_needed(bus: &mut RsidBus, init_addr: u16, flags: &mut HackFlags) {
-    let pattern: &[u8] = &[0xad, 0x0e, 0xdd, 0xad, 0xd9, 0x16, 0x8d, 0xd6, 0x16];
-    if init_addr == 0x1000 && mem_match(&bus.c64.ram.ram, 0x157c, pattern) {
-        flags.disable_badline_stun = true;
-        eprintln!("[hacks] Mr_Meaner: disabling badline stun");
+fn mem_match(ram: &[u8], addr: usize, pattern: &[u8]) -> bool {
+    let end = addr + pattern.len();
+    if end > ram.len() {
+        return false;
     }
+    ram[addr..end] == *pattern
 }
 
-/// Feeling_Good.sid — NMI fires during RTI when it shouldn't.
-/// cpuHackNMI(1): NMI only fires when I-flag is clear.
-fn patch_feeling_good_if_needed(bus: &mut RsidBus, init_addr: u16, flags: &mut HackFlags) {
-    let pattern: &[u8] = &[0xee, 0xe5, 0x1c, 0xee, 0x1a, 0x1d];
-    if init_addr == 0x1000 && mem_match(&bus.c64.ram.ram, 0x1cf7, pattern) {
-        flags.nmi_needs_i_flag_clear = true;
-        eprintln!("[hacks] Feeling_Good: NMI gated on I-flag clear");
-    }
+fn parse_addr(s: &str) -> Result<u16, String> {
+    let hex = s.trim().trim_start_matches('$').trim_start_matches("0x");
+    u16::from_str_radix(hex, 16).map_err(|_| format!("bad address \"{s}\""))
 }
 
-/// 4_Non_Blondes-Whats_Up_Remix.sid — VIC badline timing issue.
-/// Disable the display (clear DEN bit) to prevent badlines.
-fn patch_4non_blondes_if_needed(bus: &mut RsidBus, init_addr: u16, _flags: &mut HackFlags) {
-    let pattern: &[u8] = &[0x84, 0xFD, 0xA0, 0x00, 0xB1, 0xFA];
-    if init_addr == 0x082E && mem_match(&bus.c64.ram.ram, 0x0903, pattern) {
-        bus.c64.ram.ram[0x0835] = 0x0b;
-        eprintln!("[hacks] 4_Non_Blondes: disabling display (D011 patch at $0835)");
+/// Hex bytes, optionally space-separated: "ad 0e dd" or "ad0edd".
+fn parse_bytes(s: &str) -> Result<Vec<u8>, String> {
+    let hex: String = s.split_whitespace().collect();
+    if hex.is_empty() || !hex.len().is_multiple_of(2) {
+        return Err(format!("bad byte string \"{s}\""));
     }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| format!("bad byte string \"{s}\""))
+        })
+        .collect()
 }
 
-/// Game_Player.sid — NMI fires during RTI.
-/// cpuHackNMI(1): NMI only fires when I-flag is clear.
-fn patch_game_player_if_needed(bus: &mut RsidBus, init_addr: u16, flags: &mut HackFlags) {
-    let pattern: &[u8] = &[0x8D, 0xD8, 0x0B, 0xAD, 0xAE, 0x21];
-    if init_addr == 0x0810 && mem_match(&bus.c64.ram.ram, 0x0B85, pattern) {
-        flags.nmi_needs_i_flag_clear = true;
-        eprintln!("[hacks] Game_Player: NMI gated on I-flag clear");
-    }
+fn parse_bytes_at(raw: RawBytes) -> Result<(u16, Vec<u8>), String> {
+    Ok((parse_addr(&raw.addr)?, parse_bytes(&raw.bytes)?))
 }
 
-/// Synthmeld.sid — timer-B chained IRQ fires at wrong moment.
-fn patch_synthmeld_if_needed(bus: &mut RsidBus, init_addr: u16, _flags: &mut HackFlags) {
-    let pattern: &[u8] = &[
-        0xEE, 0x9C, 0xE8, 0xA9, 0x07, 0x10, 0x3A, 0xC9, 0xE0, 0x90, 0x1F,
-    ];
-    if init_addr == 0x0b00 && mem_match(&bus.c64.ram.ram, 0xB398, pattern) {
-        bus.c64.ram.ram[0xB39C] = 0x00;
-        bus.c64.ram.ram[0xB398] = 0xea;
-        bus.c64.ram.ram[0xB399] = 0xea;
-        bus.c64.ram.ram[0xB39a] = 0xea;
-        eprintln!("[hacks] Synthmeld: patching timer counter loop");
-    }
+fn normalize_path(p: &str) -> String {
+    p.trim().replace('\\', "/").to_lowercase()
 }
 
-/// Immigrant_Song.sid — absolute unforgiving NMI/badline timing.
-/// Disable the display (clear DEN bit) to eliminate badlines.
-fn patch_immigrant_song_if_needed(bus: &mut RsidBus, init_addr: u16, _flags: &mut HackFlags) {
-    let pattern: &[u8] = &[0xd1, 0x0b, 0x20, 0xcc, 0x0c, 0x20, 0x39];
-    if init_addr == 0x080d && mem_match(&bus.c64.ram.ram, 0x0826, pattern) {
-        bus.c64.ram.ram[0x0821] = 0x0b;
-        eprintln!("[hacks] Immigrant_Song: disabling display (patch at $0821)");
-    }
+/// `path` ends with `suffix` on a component boundary, so `commando.sid`
+/// doesn't also match `xcommando.sid`.
+fn path_ends_with(path: &str, suffix: &str) -> bool {
+    path.strip_suffix(suffix)
+        .is_some_and(|head| head.is_empty() || head.ends_with('/'))
 }
 
-/// Utopia_tune_6.sid — sprite-DMA bad-cycles cause timing drift.
-/// Disable badline stun; also disable IRQ ACK to let handler re-fire.
-fn patch_utopia6_if_needed(bus: &mut RsidBus, init_addr: u16, flags: &mut HackFlags) {
-    let pattern: &[u8] = &[0xce, 0x16, 0xd0, 0xee, 0x16, 0xd0];
-    if init_addr == 0x9200 && mem_match(&bus.c64.ram.ram, 0x8b05, pattern) {
-        flags.disable_badline_stun = true;
-        // Disable IRQ ACKN — causes IRQ handler to be immediately called again.
-        bus.c64.ram.ram[0x8E49] = 0x00;
-        eprintln!("[hacks] Utopia_tune_6: disabling badline stun + IRQ ACKN patch");
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_db_parses_completely() {
+        let file: HackFile = serde_json::from_str(BUILTIN).unwrap();
+        let total = file.hacks.len();
+        assert_eq!(parse_db(BUILTIN, "test").len(), total);
+        assert!(total >= 11);
     }
-}
 
-/// Comaland_tune_3.sid & Fantasmolytic_tune_2.sid ("Swallow" tunes).
-fn patch_swallow_if_needed(bus: &mut RsidBus, init_addr: u16, flags: &mut HackFlags) {
-    let p1: &[u8] = &[0x8E, 0x16, 0xD0, 0xA5, 0xE0, 0x69, 0x29];
-    let p2: &[u8] = &[0x8E, 0x16, 0xD0, 0xA5, 0xC1, 0x69, 0x29];
-    if init_addr == 0x2000
-        && (mem_match(&bus.c64.ram.ram, 0x28C8, p1) || mem_match(&bus.c64.ram.ram, 0x28C8, p2))
-    {
-        flags.disable_badline_stun = true;
-        eprintln!("[hacks] Swallow (Comaland/Fantasmolytic): disabling badline stun");
+    #[test]
+    fn entry_matches_on_every_criterion_and_pokes_ram() {
+        let db = r#"{ "hacks": [ {
+            "name": "T", "path": "GAMES\\A-F/Tune.sid", "init": "$1000",
+            "match": { "addr": "2000", "bytes": "ee 1a 1d" },
+            "pokes": [ { "addr": "2001", "bytes": "0b0c" } ],
+            "flags": [ "disable_badline_stun" ]
+        } ] }"#;
+        let hack = &parse_db(db, "test")[0];
+        let mut ram = vec![0u8; 0x10000];
+        ram[0x2000..0x2003].copy_from_slice(&[0xEE, 0x1A, 0x1D]);
+        let tune = TuneKey {
+            init_addr: 0x1000,
+            md5: "0123456789abcdef0123456789abcdef",
+            path: Path::new("/hvsc/C64Music/GAMES/A-F/tune.sid"),
+        };
+        assert!(hack.matches(&ram, &tune));
+        assert!(!hack.matches(
+            &ram,
+            &TuneKey {
+                init_addr: 0x1001,
+                ..tune
+            }
+        ));
+
+        let mut flags = HackFlags::default();
+        hack.apply(&mut ram, &mut flags);
+        assert_eq!(&ram[0x2000..0x2003], &[0xEE, 0x0B, 0x0C]);
+        assert!(flags.disable_badline_stun && !flags.nmi_needs_i_flag_clear);
+        assert!(!hack.matches(&ram, &tune));
     }
-}
 
-/// We_Are_Demo_tune_2.sid — sprite-related bad-cycles.
-fn patch_we_are_demo_if_needed(bus: &mut RsidBus, init_addr: u16, flags: &mut HackFlags) {
-    let pattern: &[u8] = &[0x8E, 0x18, 0xD4, 0x79, 0x00, 0x09, 0x85, 0xE1];
-    if init_addr == 0x0c60 && mem_match(&bus.c64.ram.ram, 0x0B10, pattern) {
-        flags.disable_badline_stun = true;
-        eprintln!("[hacks] We_Are_Demo: disabling badline stun");
+    #[test]
+    fn path_matches_whole_components_only() {
+        let db = r#"{ "hacks": [ { "name": "T", "path": "/GAMES/A-F/Commando.sid" } ] }"#;
+        let hack = &parse_db(db, "test")[0];
+        let ram = vec![0u8; 0x10000];
+        let tune = |path: &'static str| TuneKey {
+            init_addr: 0x1000,
+            md5: "",
+            path: Path::new(path),
+        };
+        assert!(hack.matches(&ram, &tune("/hvsc/C64Music/GAMES/A-F/Commando.sid")));
+        assert!(hack.matches(&ram, &tune("GAMES/A-F/Commando.sid")));
+        assert!(!hack.matches(&ram, &tune("/hvsc/C64Music/GAMES/A-F/XCommando.sid")));
+        assert!(!hack.matches(&ram, &tune("/hvsc/C64Music/XGAMES/A-F/Commando.sid")));
     }
-}
 
-/// Graphixmania_2_part_6.sid — unnecessary D418 write in IRQ player.
-fn patch_graphixsmania_if_needed(bus: &mut RsidBus, init_addr: u16, _flags: &mut HackFlags) {
-    let pattern: &[u8] = &[0xB8, 0x29, 0x0F, 0x8D, 0x18, 0xD4];
-    if init_addr == 0x7000 && mem_match(&bus.c64.ram.ram, 0x1214, pattern) {
-        bus.c64.ram.ram[0x48F9] = 0xad;
-        eprintln!("[hacks] Graphixsmania_2_part_6: patching D418 write");
+    #[test]
+    fn invalid_entries_are_skipped() {
+        let db = r#"{ "hacks": [
+            { "name": "no criteria", "flags": [ "disable_badline_stun" ] },
+            { "name": "bad flag", "init": "1000", "flags": [ "turbo" ] },
+            { "name": "bad bytes", "init": "1000", "pokes": [ { "addr": "1000", "bytes": "abc" } ] },
+            { "name": "ok", "md5": "ABCDEF" }
+        ] }"#;
+        let hacks = parse_db(db, "test");
+        assert_eq!(hacks.len(), 1);
+        assert_eq!(hacks[0].md5.as_deref(), Some("abcdef"));
     }
 }
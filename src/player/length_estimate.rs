@@ -0,0 +1,208 @@
+// Background song-length estimation for tunes the HVSC Songlength DB
+// doesn't know.
+//
+// Runs each subtune headlessly through the same engine `setup_playback`
+// picks for live playback, with no device attached. Every frame's SID
+// register state and write stream is folded into a 64-bit hash; once the
+// hash sequence ends in a stretch that repeats with a fixed period, the
+// tune has looped. The song length is the intro plus one pass of the
+// loop — or just the intro when the "loop" is a few frames long, which
+// is what a tune that has stopped (or droned on one note) looks like.
+//
+// Results go into the user-local songlength overlay (see
+// `SonglengthDb::overlay`), so a tune is only analysed once.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+use super::sid_file::SidFile;
+use super::{load_sid_or_mus, setup_playback, SidWrite};
+
+/// Give up on a subtune after this much emulated time. Loops up to half
+/// of this long can be detected.
+const MAX_SECONDS: u64 = 10 * 60;
+/// Don't look for a loop before this much has been emulated.
+const MIN_SECONDS: u64 = 30;
+/// How often (emulated time) to re-run the loop search.
+const CHECK_EVERY_SECONDS: u64 = 10;
+/// A repeating region must cover at least this much time before it is
+/// believed — short enough to catch a tune that has stopped, long enough
+/// that a repeated bar inside a song doesn't pass for the loop.
+const MIN_REPEAT_SECONDS: u64 = 45;
+/// Periods shorter than this mean the tune has ended or is holding a
+/// single note; the length is then where the repetition started.
+const MIN_LOOP_SECONDS: u64 = 2;
+/// Registers that make up a chip's state ($D400–$D418; the read-only
+/// paddle/osc/env registers are never written).
+const REGS_PER_SID: usize = 0x19;
+const SID_REG_SIZE: usize = 0x20;
+
+/// Estimate for one subtune. `length_secs == 0` means no loop was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubtuneEstimate {
+    pub length_secs: u32,
+    /// Length of the repeating section; 0 when the tune ends instead of
+    /// looping.
+    pub loop_secs: u32,
+}
+
+/// Estimates for every subtune of one file.
+#[derive(Debug, Clone)]
+pub struct LengthEstimate {
+    pub subtunes: Vec<SubtuneEstimate>,
+}
+
+impl LengthEstimate {
+    /// Per-subtune `(length, loop)` seconds, the shape
+    /// `SonglengthDb::overlay` uses.
+    pub fn lengths(&self) -> Vec<(u32, u32)> {
+        self.subtunes
+            .iter()
+            .map(|s| (s.length_secs, s.loop_secs))
+            .collect()
+    }
+}
+
+/// Analyse every subtune of `path`. Blocking and CPU-heavy — run it on a
+/// worker thread.
+pub fn estimate(path: &Path) -> Result<LengthEstimate, String> {
+    let sid_file = load_sid_or_mus(path)?;
+    let songs = sid_file.header.songs.max(1);
+    let is_rsid = sid_file.header.is_rsid
+        || (sid_file.header.play_address == 0 && sid_file.header.magic == "PSID");
+
+    let mut subtunes = Vec::with_capacity(songs as usize);
+    for song in 1..=songs {
+        let est = estimate_subtune(sid_file.clone(), path, song, is_rsid);
+        eprintln!(
+            "[length] {} song {song}/{songs}: length={}s loop={}s",
+            path.display(),
+            est.length_secs,
+            est.loop_secs,
+        );
+        subtunes.push(est);
+    }
+    Ok(LengthEstimate { subtunes })
+}
+
+fn estimate_subtune(sid_file: SidFile, path: &Path, song: u16, is_rsid: bool) -> SubtuneEstimate {
+    let mut ctx = setup_playback(
+        sid_file,
+        path.to_path_buf(),
+        song,
+        false,
+        is_rsid,
+        &mut None,
+    );
+    let unknown = SubtuneEstimate {
+        length_secs: 0,
+        loop_secs: 0,
+    };
+    if ctx.is_native() {
+        return unknown;
+    }
+
+    let frame_us = ctx.frame_us.max(1);
+    let frames_for = |secs: u64| (secs * 1_000_000 / frame_us) as usize;
+    let to_secs = |frames: usize| (frames as u64 * frame_us).div_ceil(1_000_000) as u32;
+    let max_frames = frames_for(MAX_SECONDS);
+    let min_frames = frames_for(MIN_SECONDS);
+    let check_every = frames_for(CHECK_EVERY_SECONDS).max(1);
+    let min_repeat = frames_for(MIN_REPEAT_SECONDS);
+    let num_sids = ctx.track_info.num_sids.clamp(1, 4);
+
+    let mut hashes: Vec<u64> = Vec::with_capacity(max_frames);
+    while hashes.len() < max_frames {
+        ctx.emulate_frame();
+        hashes.push(frame_hash(&ctx.sid_regs(), ctx.sid_writes(), num_sids));
+
+        let n = hashes.len();
+        if n < min_frames || !n.is_multiple_of(check_every) {
+            continue;
+        }
+        if let Some((start, period)) = find_loop(&hashes, min_repeat) {
+            return if period < frames_for(MIN_LOOP_SECONDS) {
+                SubtuneEstimate {
+                    length_secs: to_secs(start),
+                    loop_secs: 0,
+                }
+            } else {
+                SubtuneEstimate {
+                    length_secs: to_secs(start + period),
+                    loop_secs: to_secs(period),
+                }
+            };
+        }
+    }
+    unknown
+}
+
+/// Fold one frame's register state and writes into a single hash.
+fn frame_hash(regs: &[u8], writes: &[SidWrite], num_sids: usize) -> u64 {
+    let mut h = DefaultHasher::new();
+    for sid in 0..num_sids {
+        let base = sid * SID_REG_SIZE;
+        if let Some(chip) = regs.get(base..base + REGS_PER_SID) {
+            chip.hash(&mut h);
+        }
+    }
+    for &(_cycle, reg, val) in writes {
+        (reg, val).hash(&mut h);
+    }
+    h.finish()
+}
+
+/// Find where `hashes` settles into a repeating pattern. Returns
+/// `(start, period)` in frames for the shortest period whose repetition
+/// reaches the end of the sequence and covers at least `min_repeat`
+/// frames and two full periods.
+fn find_loop(hashes: &[u64], min_repeat: usize) -> Option<(usize, usize)> {
+    let n = hashes.len();
+    let last = *hashes.last()?;
+    for period in 1..=n / 2 {
+        if hashes[n - 1 - period] != last {
+            continue;
+        }
+        // Walk back while each frame matches the one a period later.
+        let mut start = n - period;
+        while start > 0 && hashes[start - 1] == hashes[start - 1 + period] {
+            start -= 1;
+        }
+        if n - start >= min_repeat.max(2 * period) {
+            return Some((start, period));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_intro_and_loop() {
+        // 7-frame intro, then a 5-frame loop repeated 6 times.
+        let mut h: Vec<u64> = (100..107).collect();
+        for _ in 0..6 {
+            h.extend([1, 2, 3, 2, 4]);
+        }
+        assert_eq!(find_loop(&h, 12), Some((7, 5)));
+    }
+
+    #[test]
+    fn static_tail_reads_as_period_one() {
+        let mut h: Vec<u64> = (0..20).collect();
+        h.extend([9; 30]);
+        assert_eq!(find_loop(&h, 25), Some((20, 1)));
+    }
+
+    #[test]
+    fn short_repetition_is_not_enough() {
+        // Sustained note inside a song: repeats, but not for long.
+        let mut h: Vec<u64> = (0..40).collect();
+        h.extend([7; 8]);
+        assert_eq!(find_loop(&h, 20), None);
+        assert_eq!(find_loop(&[], 20), None);
+    }
+}
@@ -0,0 +1,82 @@
+// Background loudness measurement for normalisation.
+//
+// Renders the tune's start song headlessly on an offline reSID, the same
+// way `render` does, and runs the samples through a `LoudnessMeter`.
+// `SCAN_SECONDS` of audio is enough to settle the gated loudness of a
+// typical SID and takes well under a second of CPU.
+//
+// Only the start song's first `SCAN_SECONDS` are measured, and the gain
+// is applied to every subtune: a quiet intro or a louder later subtune
+// can leave the gain off, which `audio_volume::limit` catches on the
+// loud side.
+
+use std::path::Path;
+
+use super::{load_sid_or_mus, send_sid_writes, setup_playback};
+use crate::loudness::{LoudnessMeter, Measurement};
+use crate::sid_device::{create_offline_engine, SidDevice};
+
+/// Emulated time analysed per tune.
+const SCAN_SECONDS: u64 = 30;
+const SAMPLE_RATE: u32 = 44_100;
+
+/// Measure the integrated loudness of `path`'s start song. Blocking and
+/// CPU-heavy — run it on a worker thread.
+pub fn measure(path: &Path) -> Result<Measurement, String> {
+    let sid_file = load_sid_or_mus(path)?;
+    let song = sid_file
+        .header
+        .start_song
+        .clamp(1, sid_file.header.songs.max(1));
+    let is_rsid = sid_file.header.is_rsid
+        || (sid_file.header.play_address == 0 && sid_file.header.magic == "PSID");
+
+    let mut device = create_offline_engine("resid", SAMPLE_RATE)?;
+    device.init()?;
+    let mut bridge: Option<Box<dyn SidDevice>> = Some(device);
+    let mut ctx = setup_playback(
+        sid_file,
+        path.to_path_buf(),
+        song,
+        false,
+        is_rsid,
+        &mut bridge,
+    );
+    if ctx.is_native() {
+        return Err("Native engines cannot be measured offline".into());
+    }
+
+    let mut meter = LoudnessMeter::new(SAMPLE_RATE);
+    let total = SCAN_SECONDS * SAMPLE_RATE as u64;
+    // Same stall guard as `render`: 2× the expected frames plus slack.
+    let max_frames = (SCAN_SECONDS * 1_000_000 / ctx.frame_us.max(1)) * 2 + 500;
+    let mut measured: u64 = 0;
+    let mut frames: u64 = 0;
+
+    while measured < total && frames <= max_frames {
+        let cycles = ctx.emulate_frame();
+        let Some(ref mut br) = bridge else { break };
+        br.set_cycles_per_frame(cycles);
+        send_sid_writes(br.as_mut(), ctx.sid_writes(), ctx.mirror_mono, cycles, 100);
+        br.flush();
+
+        let pairs = br.take_rendered();
+        let take = ((total - measured) as usize).min(pairs.len());
+        meter.push_pairs(&pairs[..take]);
+        measured += take as u64;
+
+        frames += 1;
+        ctx.frame_count = ctx.frame_count.wrapping_add(1);
+    }
+
+    let m = meter
+        .finish()
+        .ok_or_else(|| "Silent — nothing to measure".to_string())?;
+    eprintln!(
+        "[loudness] {} song {song}: {:.1} LUFS, peak {:.2}",
+        path.display(),
+        m.lufs,
+        m.peak,
+    );
+    Ok(m)
+}
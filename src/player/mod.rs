@@ -2,23 +2,31 @@
 // with the GUI via crossbeam channels. USB I/O goes through the
 // setuid usbsid-bridge helper (fixed-size protocol, async ring buffer).
 pub mod hacks;
+pub mod length_estimate;
 pub mod libsidplayfp;
+pub mod loudness_scan;
 pub mod memory;
 mod priority;
+pub mod refcompare;
+pub mod regdump;
+pub mod render;
 pub mod rsid_bus;
 pub mod sid_file;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::thread;
 use std::time::{Duration, Instant};
 
-use crossbeam_channel::{bounded, select, tick, Receiver, Sender};
+use crossbeam_channel::{at, bounded, never, select, tick, Receiver, Sender};
 use mos6502::cpu::CPU;
 use mos6502::instruction::Nmos6502;
 use mos6502::memory::Bus;
 use mos6502::registers::{StackPointer, Status};
 
+use crate::audio_volume;
 use crate::sid_device::{create_engine, SidDevice};
+use crate::tempo;
+use crate::voice_mask::{self, VoiceMask};
 use hacks::{apply_hacks, HackFlags};
 use memory::*;
 use rsid_bus::RsidBus;
@@ -35,15 +43,25 @@ pub enum PlayerCmd {
         path: PathBuf,
         song: u16,
         force_stereo: bool,
-        sid4_addr: u16,
         /// Some(port) → start U64 audio stream on this port after native playback begins.
         audio_port: Option<u16>,
         /// Close and reopen the USB device before loading (macOS).
         restart_usb_on_load: bool,
+        /// Per-entry clock override: Some(true) forces PAL, Some(false)
+        /// NTSC, None follows the header. Kept for SetSubtune and Seek.
+        force_pal: Option<bool>,
     },
     Stop,
     TogglePause,
     SetSubtune(u16),
+    /// Jump to a position in the current subtune. Forward seeks run the
+    /// emulation silently from where we are; backward seeks restart the
+    /// subtune first. Ignored on native (U64) playback.
+    Seek(Duration),
+    /// Ramp the output down to silence over this long, starting now.
+    /// The GUI follows up with Stop or the next track once it's over;
+    /// Play, SetSubtune and Seek all cancel a running fade.
+    FadeOut(Duration),
     SetEngine(String, String, String), // (engine_name, u64_address, u64_password)
     UpdateU64Config(String, String),   // (u64_address, u64_password) — no device teardown
     /// Switch macOS USB transport ("bridge" or "direct"). Drops the current
@@ -226,7 +244,8 @@ pub struct TrackInfo {
 /// Send cycle-stamped SID writes to hardware via async ring buffer.
 ///
 /// Converts absolute frame cycles to delta format (cycles since previous
-/// write) and pushes them through `ring_cycled`. The device's background
+/// write), stretched for a tempo of `stretch_pct` percent (see `tempo`),
+/// and pushes them through `ring_cycled`. The device's background
 /// thread drains the ring buffer to USB asynchronously with cycle-accurate
 /// timing on the firmware side.
 fn send_sid_writes(
@@ -234,6 +253,7 @@ fn send_sid_writes(
     writes: &[(u32, u8, u8)],
     mirror_mono: bool,
     cycles_per_frame: u32,
+    stretch_pct: u16,
 ) {
     if writes.is_empty() {
         return;
@@ -248,7 +268,10 @@ fn send_sid_writes(
     //     set_flush() pads correctly from the right position.
     // Musically: overflow writes land at the very end of the audio frame — correct,
     // since they happened "past" the nominal frame boundary anyway.
-    let clamp = |c: u32| c.min(cycles_per_frame);
+    let clamp = |c: u32| tempo::stretch(c.min(cycles_per_frame), stretch_pct);
+    // Mute/solo and the hardware fade are applied here, before mirroring,
+    // so every engine (and the mono SID2 copy) sees the same filtered stream.
+    let filter = OutputFilter::current(bridge);
 
     if mirror_mono {
         // Mono: duplicate each write for SID2 at delta=0 (same cycle position).
@@ -258,6 +281,7 @@ fn send_sid_writes(
         for &(cycle, reg, val) in writes {
             let cycle = clamp(cycle);
             let delta = cycle.saturating_sub(prev_cycle).min(0xFFFF) as u16;
+            let val = filter.apply(reg, val);
             cycled.push((delta, reg, val));
             if reg <= SID_VOL_REG {
                 cycled.push((0, reg + SID_REG_SIZE, val));
@@ -274,7 +298,7 @@ fn send_sid_writes(
         for &(cycle, reg, val) in writes {
             let cycle = clamp(cycle);
             let delta = cycle.saturating_sub(prev_cycle).min(0xFFFF) as u16;
-            cycled.push((delta, reg, val));
+            cycled.push((delta, reg, filter.apply(reg, val)));
             prev_cycle = cycle;
         }
 
@@ -282,6 +306,38 @@ fn send_sid_writes(
     }
 }
 
+/// Rewrites applied to every SID write on its way to the device:
+/// per-voice mute/solo, plus the fade-out gain on engines that can't
+/// fade in the host audio path (real chips get $D418 scaled instead).
+#[derive(Clone, Copy)]
+struct OutputFilter {
+    mask: VoiceMask,
+    fade: f32,
+}
+
+impl OutputFilter {
+    fn current(bridge: &dyn SidDevice) -> Self {
+        Self {
+            mask: voice_mask::current(),
+            fade: if bridge.has_host_gain() {
+                1.0
+            } else {
+                audio_volume::fade()
+            },
+        }
+    }
+
+    fn apply(self, reg: u8, val: u8) -> u8 {
+        let val = self.mask.filter(reg, val);
+        if self.fade < 1.0 && reg % SID_REG_SIZE == SID_VOL_REG {
+            let vol = ((val & 0x0F) as f32 * self.fade).round() as u8;
+            (val & 0xF0) | vol.min(0x0F)
+        } else {
+            val
+        }
+    }
+}
+
 /// Wait until `deadline` using sleep for bulk + spin for precision.
 /// Used for frame pacing — sleeps most of the duration, then spin-waits
 /// the last ~1ms for sub-millisecond accuracy without burning 100% CPU.
@@ -388,6 +444,10 @@ fn player_loop(
     loop {
         match state {
             PlayState::Stopped | PlayState::Paused => {
+                // A seek issued while paused still runs to its target,
+                // slice by slice between commands.
+                let seeking = play_ctx.as_ref().is_some_and(|c| c.seek_to.is_some());
+                let seek_wake = if seeking { at(Instant::now()) } else { never() };
                 select! {
                     recv(cmd_rx) -> msg => {
                         match msg {
@@ -402,6 +462,12 @@ fn player_loop(
                             Err(_) => break,
                         }
                     }
+                    recv(seek_wake) -> _ => {
+                        if let Some(ref mut ctx) = play_ctx {
+                            seek_step(ctx, &mut bridge);
+                        }
+                        send_status(&state, &play_ctx, &last_error, &status_tx);
+                    }
                     recv(idle_tick) -> _ => {
                         send_status(&state, &play_ctx, &last_error, &status_tx);
                     }
@@ -444,7 +510,46 @@ fn player_loop(
                         continue;
                     }
 
+                    // A seek in progress takes the place of the frame.
+                    if let Some(ctx) = play_ctx.as_mut().filter(|c| c.seek_to.is_some()) {
+                        seek_step(ctx, &mut bridge);
+                        send_status(&state, &play_ctx, &last_error, &status_tx);
+                        continue;
+                    }
+
                     if let Some(ref mut ctx) = play_ctx {
+                        // Advance any running fade-out. Software engines pick
+                        // the gain up in their audio callback; hardware needs
+                        // $D418 re-sent whenever the 4-bit volume step moves.
+                        let gain = ctx.fade_gain();
+                        audio_volume::set_fade(gain);
+                        let fade_step = (gain * 15.0).round() as u8;
+
+                        // Tempo changed since the last frame (native playback
+                        // runs on the Ultimate's clock and ignores it; a
+                        // replayed log keeps the recorded frame length).
+                        let tempo = (tempo::current(), tempo::pitch_follows());
+                        if tempo != ctx.tempo && !ctx.is_native() && engine_name != "replay" {
+                            if let Some(ref mut br) = bridge {
+                                apply_tempo(ctx, br.as_mut(), tempo);
+                            }
+                        }
+
+                        // Mute/solo or fade step changed since the last frame:
+                        // rewrite the gate/volume registers now so held notes
+                        // stop (or resume) immediately rather than at their
+                        // next write.
+                        let mask = voice_mask::current();
+                        if mask != ctx.voice_mask || fade_step != ctx.fade_step {
+                            if let Some(ref mut br) = bridge {
+                                if !matches!(ctx.engine, PlayEngine::Native { .. }) {
+                                    refresh_filtered_regs(ctx, br.as_mut());
+                                }
+                            }
+                            ctx.voice_mask = mask;
+                            ctx.fade_step = fade_step;
+                        }
+
                         match &mut ctx.engine {
                             PlayEngine::Rsid { cpu, prev_nmi } => {
                                 // ── RSID (c64_emu) ───────────────────────────
@@ -459,6 +564,7 @@ fn player_loop(
                                         &cpu.memory.sid_writes,
                                         ctx.mirror_mono,
                                         ctx.cycles_per_frame,
+                                        ctx.stretch_pct,
                                     );
                                     let dt = t0.elapsed();
                                     if dt.as_millis() > 10 || nw > 500 {
@@ -484,6 +590,7 @@ fn player_loop(
                                         &cpu.memory.sid_writes,
                                         ctx.mirror_mono,
                                         ctx.cycles_per_frame,
+                                        ctx.stretch_pct,
                                     );
                                 }
                             }
@@ -519,13 +626,17 @@ fn player_loop(
                                 }
 
                                 if let Some(ref mut br) = bridge {
-                                    br.set_cycles_per_frame(actual);
+                                    br.set_cycles_per_frame(tempo::stretch(
+                                        actual,
+                                        ctx.stretch_pct,
+                                    ));
                                     let t0 = Instant::now();
                                     send_sid_writes(
                                         br.as_mut(),
                                         &fp.sid_writes,
                                         ctx.mirror_mono,
                                         actual,
+                                        ctx.stretch_pct,
                                     );
                                     let dt = t0.elapsed();
                                     if dt.as_millis() > 10 {
@@ -620,7 +731,9 @@ fn player_loop(
                         }
 
                         // ── Absolute-timeline frame pacing ───────────────────
-                        ctx.next_frame += frame_dur;
+                        // `elapsed` stays in tune time; only the wall-clock
+                        // deadline follows the tempo.
+                        ctx.next_frame += tempo::stretch_duration(frame_dur, ctx.tempo.0);
 
                         let now = Instant::now();
                         if ctx.next_frame < now {
@@ -692,7 +805,14 @@ fn ensure_hardware(
     if bridge.is_some() {
         return Ok(());
     }
-    let mut br = create_engine(engine_name, u64_address, u64_password, macos_usb_mode)?;
+    // Every engine goes through the write-log recorder; it is a plain
+    // pass-through until a log is armed.
+    let dev = create_engine(engine_name, u64_address, u64_password, macos_usb_mode)?;
+    let mut br = crate::sid_log::RecordingDevice::wrap(crate::sid_device::attach_mirror(
+        dev,
+        engine_name,
+        macos_usb_mode,
+    ));
     br.init()?;
     *bridge = Some(br);
     Ok(())
@@ -742,9 +862,9 @@ fn handle_cmd(
             path,
             song,
             force_stereo,
-            sid4_addr,
             audio_port,
             restart_usb_on_load,
+            force_pal,
         } => {
             *last_error = None;
             stop_playback(play_ctx, bridge);
@@ -789,7 +909,7 @@ fn handle_cmd(
                 .map(|e| e.eq_ignore_ascii_case("mus"))
                 .unwrap_or(false);
 
-            let sid_file = match load_sid(&data) {
+            let mut sid_file = match load_sid(&data) {
                 Ok(s) => s,
                 Err(_) if is_mus => sid_file::load_mus_stub(&data, Some(&path)),
                 Err(e) => {
@@ -799,6 +919,7 @@ fn handle_cmd(
                     return;
                 }
             };
+            apply_clock_override(&mut sid_file, force_pal);
 
             let is_rsid = sid_file.header.is_rsid
                 || (sid_file.header.play_address == 0 && sid_file.header.magic == "PSID");
@@ -816,7 +937,8 @@ fn handle_cmd(
             // If the engine supports play_sid_native, skip CPU emulation
             // entirely and let the real hardware do everything.
             let native = if let Some(ref mut br) = bridge {
-                match br.play_sid_native(&data, song) {
+                let native_data = sid_file::clock_patched_raw(&sid_file);
+                match br.play_sid_native(native_data.as_deref().unwrap_or(&sid_file.raw), song) {
                     Ok(true) => {
                         eprintln!("[phosphor] Native playback active — skipping CPU emulation");
                         // Start audio streaming back to host if configured.
@@ -840,9 +962,8 @@ fn handle_cmd(
             if native {
                 // Build context with shadow CPU for visualization.
                 let header = &sid_file.header;
-                let num_sids = 1
-                    + (header.extra_sid_addrs[0] != 0) as usize
-                    + (header.extra_sid_addrs[1] != 0) as usize;
+                let sid_bases = compute_sid_bases(header.extra_sid_addrs);
+                let num_sids = sid_bases.len();
                 let sid_type = match num_sids {
                     1 => "Mono".to_string(),
                     2 => "2SID Stereo".to_string(),
@@ -879,13 +1000,6 @@ fn handle_cmd(
                 };
 
                 // Build shadow emulation for visualization
-                let mut sid_bases: Vec<u16> = vec![0xD400];
-                if header.extra_sid_addrs[0] != 0 {
-                    sid_bases.push(header.extra_sid_addrs[0]);
-                }
-                if header.extra_sid_addrs[1] != 0 {
-                    sid_bases.push(header.extra_sid_addrs[1]);
-                }
                 let mapper = SidMapper::new(&sid_bases);
                 let mono_mode = num_sids <= 1;
                 let trampoline: u16 = 0x0300;
@@ -893,13 +1007,7 @@ fn handle_cmd(
 
                 let shadow = if is_rsid {
                     let engine = setup_rsid_engine(
-                        &sid_file,
-                        song,
-                        &mapper,
-                        mono_mode,
-                        cycles_per_frame,
-                        trampoline,
-                        halt_pc,
+                        &sid_file, song, &mapper, mono_mode, &path, trampoline, halt_pc,
                     );
                     match engine {
                         PlayEngine::Rsid { mut cpu, prev_nmi } => {
@@ -946,17 +1054,15 @@ fn handle_cmd(
                     u64_screen_read_at: None,
                     u64_screen_total_secs: None,
                     device_connected: true,
+                    voice_mask: VoiceMask::default(),
+                    fade: None,
+                    seek_to: None,
+                    fade_step: 15,
+                    tempo: (100, false),
+                    stretch_pct: 100,
                 });
             } else {
-                let mut ctx = setup_playback(
-                    sid_file,
-                    path,
-                    song,
-                    force_stereo,
-                    sid4_addr,
-                    is_rsid,
-                    bridge,
-                );
+                let mut ctx = setup_playback(sid_file, path, song, force_stereo, is_rsid, bridge);
                 ctx.audio_port = audio_port;
                 *play_ctx = Some(ctx);
             }
@@ -1030,12 +1136,13 @@ fn handle_cmd(
             if let Some(ref ctx) = play_ctx {
                 let path = ctx.track_info.path.clone();
                 let stereo = ctx.mirror_mono;
-                let is_rsid = ctx.is_rsid();
+                let is_rsid = ctx.track_info.is_rsid;
                 let was_native = ctx.is_native();
+                // Keep the clock the tune was started with.
+                let force_pal = Some(ctx.track_info.is_pal);
                 // Preserve the audio port so we can restart streaming after the
                 // subtune change — stop_playback kills the audio stream.
                 let saved_audio_port = ctx.audio_port;
-                let sid4 = 0;
                 // Keep audio stream alive — it's a continuous UDP flow from the
                 // U64 that doesn't need to be restarted on a subtune change.
                 stop_playback_keep_audio(play_ctx, bridge);
@@ -1043,13 +1150,18 @@ fn handle_cmd(
                 if was_native {
                     if let Ok(data) = std::fs::read(&path) {
                         if let Some(ref mut br) = bridge {
-                            match br.play_sid_native(&data, song) {
+                            let native_data = load_sid(&data).ok().and_then(|mut s| {
+                                apply_clock_override(&mut s, force_pal);
+                                sid_file::clock_patched_raw(&s)
+                            });
+                            match br.play_sid_native(native_data.as_deref().unwrap_or(&data), song)
+                            {
                                 Ok(true) => {
-                                    if let Ok(sid_file) = load_sid(&data) {
+                                    if let Ok(mut sid_file) = load_sid(&data) {
+                                        apply_clock_override(&mut sid_file, force_pal);
                                         let header = &sid_file.header;
-                                        let num_sids = 1
-                                            + (header.extra_sid_addrs[0] != 0) as usize
-                                            + (header.extra_sid_addrs[1] != 0) as usize;
+                                        let sid_bases = compute_sid_bases(header.extra_sid_addrs);
+                                        let num_sids = sid_bases.len();
                                         let sid_type = match num_sids {
                                             1 => "Mono".to_string(),
                                             2 => "2SID Stereo".to_string(),
@@ -1086,13 +1198,6 @@ fn handle_cmd(
                                         };
 
                                         // Build shadow CPU for visualization
-                                        let mut sid_bases: Vec<u16> = vec![0xD400];
-                                        if header.extra_sid_addrs[0] != 0 {
-                                            sid_bases.push(header.extra_sid_addrs[0]);
-                                        }
-                                        if header.extra_sid_addrs[1] != 0 {
-                                            sid_bases.push(header.extra_sid_addrs[1]);
-                                        }
                                         let mapper = SidMapper::new(&sid_bases);
                                         let mono_mode = num_sids <= 1;
                                         let trampoline: u16 = 0x0300;
@@ -1100,13 +1205,8 @@ fn handle_cmd(
 
                                         let shadow = if is_rsid {
                                             let engine = setup_rsid_engine(
-                                                &sid_file,
-                                                song,
-                                                &mapper,
-                                                mono_mode,
-                                                cycles_per_frame,
-                                                trampoline,
-                                                halt_pc,
+                                                &sid_file, song, &mapper, mono_mode, &path,
+                                                trampoline, halt_pc,
                                             );
                                             match engine {
                                                 PlayEngine::Rsid { mut cpu, prev_nmi } => {
@@ -1157,6 +1257,12 @@ fn handle_cmd(
                                             u64_screen_read_at: None,
                                             u64_screen_total_secs: None,
                                             device_connected: true,
+                                            voice_mask: VoiceMask::default(),
+                                            fade: None,
+                                            seek_to: None,
+                                            fade_step: 15,
+                                            tempo: (100, false),
+                                            stretch_pct: 100,
                                         });
                                         *state = PlayState::Playing;
                                     }
@@ -1168,9 +1274,9 @@ fn handle_cmd(
                         }
                     }
                 } else if let Ok(data) = std::fs::read(&path) {
-                    if let Ok(sid_file) = load_sid(&data) {
-                        let new_ctx =
-                            setup_playback(sid_file, path, song, stereo, sid4, is_rsid, bridge);
+                    if let Ok(mut sid_file) = load_sid(&data) {
+                        apply_clock_override(&mut sid_file, force_pal);
+                        let new_ctx = setup_playback(sid_file, path, song, stereo, is_rsid, bridge);
                         *play_ctx = Some(new_ctx);
                         *state = PlayState::Playing;
                     }
@@ -1179,6 +1285,67 @@ fn handle_cmd(
             send_status(state, play_ctx, last_error, status_tx);
         }
 
+        PlayerCmd::Seek(target) => {
+            let target = target.min(MAX_SEEK);
+            let Some(ctx) = play_ctx.as_ref() else {
+                return;
+            };
+            if ctx.is_native() {
+                eprintln!("[player] Seek not supported on native playback");
+                return;
+            }
+            // The log plays frame for frame from its own start.
+            if engine_name == "replay" {
+                eprintln!("[player] Seek not supported while replaying a SID write log");
+                return;
+            }
+
+            // Going backwards means replaying from the top of the subtune.
+            if target < ctx.elapsed {
+                let path = ctx.track_info.path.clone();
+                let song = ctx.track_info.current_song;
+                let stereo = ctx.mirror_mono;
+                let is_rsid = ctx.track_info.is_rsid;
+                let force_pal = Some(ctx.track_info.is_pal);
+                stop_playback_keep_audio(play_ctx, bridge);
+                match load_sid_or_mus(&path) {
+                    Ok(mut sid_file) => {
+                        apply_clock_override(&mut sid_file, force_pal);
+                        *play_ctx = Some(setup_playback(
+                            sid_file, path, song, stereo, is_rsid, bridge,
+                        ));
+                    }
+                    Err(e) => {
+                        eprintln!("[player] Seek restart failed: {e}");
+                        *last_error = Some(e);
+                        *state = PlayState::Stopped;
+                        send_status(state, play_ctx, last_error, status_tx);
+                        return;
+                    }
+                }
+            }
+
+            if let Some(ref mut ctx) = play_ctx {
+                ctx.fade = None;
+                // Mute for the catch-up; `seek_step` restores the register
+                // shadow once the target is reached.
+                if let Some(ref mut br) = bridge {
+                    br.mute();
+                }
+                ctx.seek_to = Some(target);
+            }
+            send_status(state, play_ctx, last_error, status_tx);
+        }
+
+        PlayerCmd::FadeOut(len) => {
+            if let Some(ref mut ctx) = play_ctx {
+                if ctx.fade.is_none() {
+                    eprintln!("[player] Fade-out over {:.1}s", len.as_secs_f32());
+                    ctx.fade = Some((ctx.elapsed, len));
+                }
+            }
+        }
+
         PlayerCmd::SetEngine(name, addr, pass) => {
             eprintln!("[phosphor] Engine switch → '{name}'");
             stop_playback(play_ctx, bridge);
@@ -1283,6 +1450,150 @@ fn handle_cmd(
     }
 }
 
+/// Upper bound for `PlayerCmd::Seek` — keeps a bogus request from
+/// spinning the emulation for minutes of catch-up.
+const MAX_SEEK: Duration = Duration::from_secs(60 * 60);
+
+/// Wall time a seek may emulate before the player loop gets to handle
+/// commands and publish progress again.
+const SEEK_SLICE: Duration = Duration::from_millis(20);
+
+/// Read and parse a tune from disk, falling back to the MUS stub loader
+/// for `.mus` files (same rule as `PlayerCmd::Play`).
+fn load_sid_or_mus(path: &std::path::Path) -> Result<SidFile, String> {
+    let data = std::fs::read(path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+    let is_mus = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("mus"))
+        .unwrap_or(false);
+    match load_sid(&data) {
+        Ok(s) => Ok(s),
+        Err(_) if is_mus => Ok(sid_file::load_mus_stub(&data, Some(path))),
+        Err(e) => Err(e),
+    }
+}
+
+/// Bring the device in line with a new tempo. A fresh `PlayContext`
+/// starts at (100, false), matching the unscaled clock `set_clock_rate`
+/// leaves behind, so a non-default tempo is re-applied on its first frame.
+fn apply_tempo(ctx: &mut PlayContext, br: &mut dyn SidDevice, (pct, follow): (u16, bool)) {
+    let clocked = br.set_clock_scale(if follow { pct } else { 100 }) && follow;
+    ctx.stretch_pct = if clocked { 100 } else { pct };
+    br.set_cycles_per_frame(tempo::stretch(ctx.cycles_per_frame, ctx.stretch_pct));
+    ctx.tempo = (pct, follow);
+    eprintln!(
+        "[player] Tempo {pct}%{}",
+        if clocked { ", pitch follows" } else { "" }
+    );
+}
+
+/// Apply a PAL/NTSC override to a freshly loaded tune. Everything
+/// downstream — `frame_us`, the device clock, the CIA defaults and the
+/// C64 model — follows `header.is_pal`. MUS files and v1 headers keep
+/// their clock: libsidplayfp reads it from the file, and those have no
+/// clock flags to patch.
+fn apply_clock_override(sid_file: &mut SidFile, force_pal: Option<bool>) {
+    let header = &mut sid_file.header;
+    let Some(pal) = force_pal.filter(|&pal| pal != header.is_pal) else {
+        return;
+    };
+    let name = |pal: bool| if pal { "PAL" } else { "NTSC" };
+    if header.magic == "MUS" || header.version < 2 {
+        eprintln!(
+            "[phosphor] Clock override ignored: {} v{} has no clock flags",
+            header.magic, header.version
+        );
+        return;
+    }
+    eprintln!(
+        "[phosphor] Clock forced to {} (header says {})",
+        name(pal),
+        name(header.is_pal)
+    );
+    header.is_pal = pal;
+}
+
+/// Run one slice of a pending seek: emulate silently towards
+/// `ctx.seek_to` for at most `SEEK_SLICE` of wall time.
+///
+/// No writes are sent during the catch-up, so the emulated engines
+/// generate nothing and hardware stays quiet. Once the target is reached,
+/// the engine's register shadow is pushed to the device so the voices pick
+/// up where the tune is, and frame pacing restarts from now.
+fn seek_step(ctx: &mut PlayContext, bridge: &mut Option<Box<dyn SidDevice>>) {
+    let Some(target) = ctx.seek_to else {
+        return;
+    };
+    let t0 = Instant::now();
+    let frame_dur = Duration::from_micros(ctx.frame_us);
+    while ctx.elapsed + frame_dur <= target {
+        if t0.elapsed() >= SEEK_SLICE {
+            return;
+        }
+        ctx.emulate_frame();
+        ctx.frame_count += 1;
+        ctx.elapsed += frame_dur;
+    }
+
+    ctx.seek_to = None;
+    if let Some(ref mut br) = bridge {
+        restore_sid_regs(ctx, br.as_mut());
+    }
+    ctx.next_frame = Instant::now();
+    eprintln!("[player] Seek → {:.1}s", ctx.elapsed.as_secs_f64());
+}
+
+/// Write the engine's SID register shadow to the device — voice, filter
+/// and volume registers of every active chip (mirrored to SID2 in mono
+/// mode, same as `send_sid_writes`).
+fn restore_sid_regs(ctx: &PlayContext, br: &mut dyn SidDevice) {
+    let regs = ctx.sid_regs();
+    let chips = if ctx.mirror_mono {
+        1
+    } else {
+        ctx.track_info.num_sids.clamp(1, 4)
+    };
+    let filter = OutputFilter::current(br);
+    for chip in 0..chips {
+        let base = chip as u8 * SID_REG_SIZE;
+        for reg in 0..=SID_VOL_REG {
+            let val = regs.get((base + reg) as usize).copied().unwrap_or(0);
+            let val = filter.apply(base + reg, val);
+            br.write(base + reg, val);
+            if ctx.mirror_mono {
+                br.write(base + reg + SID_REG_SIZE, val);
+            }
+        }
+    }
+    br.flush();
+}
+
+/// Re-send each voice control register and $D418 from the shadow through
+/// the current `OutputFilter`. Unmuting restores the tune's own gate, so a
+/// held note re-attacks rather than waiting for the player to touch it again.
+fn refresh_filtered_regs(ctx: &PlayContext, br: &mut dyn SidDevice) {
+    let filter = OutputFilter::current(br);
+    let regs = ctx.sid_regs();
+    let chips = if ctx.mirror_mono {
+        1
+    } else {
+        ctx.track_info.num_sids.clamp(1, 4)
+    };
+    for chip in 0..chips {
+        let base = chip as u8 * SID_REG_SIZE;
+        for reg in [4u8, 11, 18, SID_VOL_REG] {
+            let val = regs.get((base + reg) as usize).copied().unwrap_or(0);
+            let val = filter.apply(base + reg, val);
+            br.write(base + reg, val);
+            if ctx.mirror_mono {
+                br.write(base + reg + SID_REG_SIZE, val);
+            }
+        }
+    }
+    br.flush();
+}
+
 fn stop_playback(ctx: &mut Option<PlayContext>, bridge: &mut Option<Box<dyn SidDevice>>) {
     stop_playback_inner(ctx, bridge, true);
 }
@@ -1354,6 +1665,22 @@ struct PlayContext {
     /// every player frame so the GUI's "Disconnected" indicator stays
     /// current within one frame of the device coming/going.
     device_connected: bool,
+    /// Mute/solo mask last pushed to the device. Compared against the
+    /// global mask each frame to detect changes from the GUI or remote.
+    voice_mask: VoiceMask,
+    /// Running fade-out as `(elapsed at start, length)`.
+    fade: Option<(Duration, Duration)>,
+    /// Pending seek target. The player loop catches up towards it one
+    /// `SEEK_SLICE` at a time, handling commands in between.
+    seek_to: Option<Duration>,
+    /// Fade gain quantised to $D418 steps (15 = full), last pushed to
+    /// hardware. Lets the frame loop re-send $D418 only on a change.
+    fade_step: u8,
+    /// Tempo last applied to the device: (percent, pitch follows speed).
+    tempo: (u16, bool),
+    /// Percent the device-side frame is stretched by — the tempo, or 100
+    /// when the engine follows it with its own SID clock.
+    stretch_pct: u16,
 }
 
 enum PlayEngine {
@@ -1421,6 +1748,18 @@ impl PlayContext {
         }
     }
 
+    /// Current fade-out gain in [0, 1]; 1.0 when no fade is running.
+    fn fade_gain(&self) -> f32 {
+        match self.fade {
+            Some((start, len)) if !len.is_zero() => {
+                let t = self.elapsed.saturating_sub(start).as_secs_f32() / len.as_secs_f32();
+                (1.0 - t).clamp(0.0, 1.0)
+            }
+            Some(_) => 0.0,
+            None => 1.0,
+        }
+    }
+
     /// Return a copy of the raw SID register shadow for the UI panel.
     fn sid_regs(&self) -> Vec<u8> {
         match &self.engine {
@@ -1434,6 +1773,29 @@ impl PlayContext {
         }
     }
 
+    /// Run the tune for one frame without touching any device, leaving
+    /// the frame's writes in `sid_writes()`. Returns the C64 cycles the
+    /// frame actually covered — libsidplayfp can stop short of
+    /// `cycles_per_frame`. Native engines have nothing to run; returns 0.
+    fn emulate_frame(&mut self) -> u32 {
+        match &mut self.engine {
+            PlayEngine::Rsid { cpu, prev_nmi } => {
+                cpu.memory.clear_writes();
+                run_rsid_sub_emu(cpu, self.cycles_per_frame, prev_nmi);
+                self.cycles_per_frame
+            }
+            PlayEngine::Psid(cpu) => {
+                cpu.memory.clear_writes();
+                cpu.registers.program_counter = self.trampoline;
+                cpu.registers.stack_pointer = StackPointer(0xFD);
+                run_until(cpu, self.halt_pc, 200_000);
+                self.cycles_per_frame
+            }
+            PlayEngine::SidPlayFp(fp) => fp.run_frame(self.cycles_per_frame),
+            PlayEngine::Native { .. } => 0,
+        }
+    }
+
     fn clear_writes(&mut self) {
         match &mut self.engine {
             PlayEngine::Psid(cpu) => cpu.memory.clear_writes(),
@@ -1450,29 +1812,33 @@ impl PlayContext {
 /// Build the ordered list of SID chip base addresses for a tune.
 ///
 /// SID1 is always at `0xD400` (canonical C64 SID base).
-/// SID2 and SID3 come from the PSID header's `extra_sid_addrs` — a
-/// zero value in either slot means "no chip".
-/// SID4 comes from the caller-supplied `sid4_addr` (a `--sid4 XXXX`
-/// CLI override) — again, `0` means "no chip".
-///
-/// **CRITICAL**: `sid4_addr` MUST be `0` for tunes that aren't
-/// explicitly 4-SID. Passing `0xd420` (or any non-zero value) here
-/// on a 1SID tune inflates `num_sids` to 2, which flips downstream
-/// stereo routing and leaves the right channel silent. This bug
-/// bit every call site except `play_track()` for months — see PR
-/// TODO for the diagnostic + fix.
-pub(crate) fn compute_sid_bases(extra_sid_addrs: [u16; 2], sid4_addr: u16) -> Vec<u16> {
+/// SID2–SID4 come from the header's `extra_sid_addrs` — a zero value in
+/// any slot means "no chip", and the chips that are present keep their
+/// header order.
+pub(crate) fn compute_sid_bases(extra_sid_addrs: [u16; 3]) -> Vec<u16> {
     let mut sid_bases: Vec<u16> = vec![0xD400];
-    if extra_sid_addrs[0] != 0 {
-        sid_bases.push(extra_sid_addrs[0]);
-    }
-    if extra_sid_addrs[1] != 0 {
-        sid_bases.push(extra_sid_addrs[1]);
+    sid_bases.extend(extra_sid_addrs.iter().copied().filter(|&a| a != 0));
+    sid_bases
+}
+
+/// Model hint for each chip slot handed to `SidDevice::set_sid_models`.
+///
+/// Slots follow `compute_sid_bases`, so a header gap doesn't shift a
+/// model onto the wrong chip. Extra chips the header leaves unspecified
+/// inherit SID1's model; a non-zero `overrides` entry (from
+/// `Config::sid_models`) wins over the header.
+pub(crate) fn resolve_sid_models(header: &SidHeader, overrides: [u8; 4]) -> [u8; 4] {
+    let mut models = [header.sid_model; 4];
+    let present = (1..4).filter(|&chip| header.extra_sid_addrs[chip - 1] != 0);
+    for (slot, chip) in (1..).zip(present) {
+        models[slot] = header.chip_model(chip);
     }
-    if sid4_addr != 0 {
-        sid_bases.push(sid4_addr);
+    for (model, &forced) in models.iter_mut().zip(&overrides) {
+        if forced != 0 {
+            *model = forced;
+        }
     }
-    sid_bases
+    models
 }
 
 fn setup_playback(
@@ -1480,13 +1846,13 @@ fn setup_playback(
     path: PathBuf,
     song: u16,
     force_stereo: bool,
-    sid4_addr: u16,
     is_rsid: bool,
     bridge: &mut Option<Box<dyn SidDevice>>,
 ) -> PlayContext {
     let header = &sid_file.header;
+    let config = crate::config::Config::load();
 
-    let sid_bases = compute_sid_bases(header.extra_sid_addrs, sid4_addr);
+    let sid_bases = compute_sid_bases(header.extra_sid_addrs);
 
     let num_sids = sid_bases.len();
     let is_multi = num_sids > 1;
@@ -1548,11 +1914,17 @@ fn setup_playback(
     // ── Configure hardware ───────────────────────────────────────────────
     if let Some(ref mut br) = bridge {
         br.set_clock_rate(header.is_pal);
+        br.set_tune(&track_info.md5, song);
         br.reset();
-        // Emulated (resid-rs) engine swaps 6581/8580 per tune; the
-        // other engines default no-op this. Called after reset() so the
+        // The software engines swap 6581/8580 per chip; the other
+        // engines default no-op this. Called after reset() so the
         // fresh chip state comes up with the correct model.
-        br.set_sid_model(header.sid_model);
+        br.set_sid_models(resolve_sid_models(header, config.sid_models));
+        br.set_emu_tuning(crate::emu_tuning::resolve(
+            &config,
+            &track_info.md5,
+            num_sids,
+        ));
         thread::sleep(Duration::from_millis(50));
 
         if use_stereo {
@@ -1590,10 +1962,22 @@ fn setup_playback(
     // Fall back to the built-in mos6502 engine only if libsidplayfp fails.
     // MUS files can only be played by libsidplayfp — no built-in fallback.
     let is_mus = sid_file.header.magic == "MUS";
+    // The cycle-exact PSID option skips libsidplayfp as well: the point is
+    // to drive the device from our own c64_emu core.
+    let psid_emu = config.psid_cycle_exact && !is_rsid && !is_mus;
     // For MUS files, use file-path loading so libsidplayfp can find
     // companion .str files for stereo playback automatically.
-    let fp_result = if is_mus {
+    let fp_result = if psid_emu {
+        Err("cycle-exact PSID core selected".to_string())
+    } else if num_sids > 3 {
+        // libsidplayfp emulates at most three chips; a 4SID tune would
+        // lose every write to the fourth.
+        Err(format!("{num_sids}SID tunes need the built-in CPU"))
+    } else if is_mus {
         libsidplayfp::LibSidPlayFp::new_from_file(&path, song)
+    } else if let Some(raw) = sid_file::clock_patched_raw(&sid_file) {
+        // Clock override: libsidplayfp takes the clock from the file.
+        libsidplayfp::LibSidPlayFp::new(&raw, song)
     } else {
         libsidplayfp::LibSidPlayFp::new(&sid_file.raw, song)
     };
@@ -1616,17 +2000,17 @@ fn setup_playback(
             // Built-in CPU engine cannot play MUS — give up.
             setup_psid_engine(&sid_file, song, &mapper, mono_mode, trampoline, halt_pc)
         }
+        Err(_) if psid_emu => {
+            setup_psid_emu_engine(&sid_file, &path, song, &mapper, mono_mode, trampoline)
+                .unwrap_or_else(|| {
+                    setup_psid_engine(&sid_file, song, &mapper, mono_mode, trampoline, halt_pc)
+                })
+        }
         Err(e) => {
             eprintln!("[phosphor] libsidplayfp failed: {e}, falling back to built-in CPU");
             if is_rsid {
                 setup_rsid_engine(
-                    &sid_file,
-                    song,
-                    &mapper,
-                    mono_mode,
-                    cycles_per_frame,
-                    trampoline,
-                    halt_pc,
+                    &sid_file, song, &mapper, mono_mode, &path, trampoline, halt_pc,
                 )
             } else {
                 setup_psid_engine(&sid_file, song, &mapper, mono_mode, trampoline, halt_pc)
@@ -1635,9 +2019,12 @@ fn setup_playback(
     };
 
     // For MUS files, update num_sids and capture comments from libsidplayfp.
+    // Its MUS loader adds SID2 at $D500 when it finds the .str; the stub
+    // header already declares it, so the tracker and visualiser get all
+    // six voices even if the loader reported fewer chips.
     if is_mus {
         if let PlayEngine::SidPlayFp(ref fp) = engine {
-            track_info.num_sids = fp.num_sids;
+            track_info.num_sids = fp.num_sids.max(num_sids);
             track_info.mus_comments = fp.comments.clone();
         }
     }
@@ -1668,15 +2055,7 @@ fn setup_playback(
     // play call rate. Many 2SID tunes use ~100Hz (2× frame rate).
     let engine = match engine {
         PlayEngine::Psid(mut cpu) => {
-            // Check if this song uses CIA timing
-            let song_idx = song.saturating_sub(1) as u32;
-            let speed_bit = if song_idx < 32 {
-                (header.speed >> song_idx) & 1
-            } else {
-                (header.speed >> 31) & 1
-            };
-
-            if speed_bit == 1 && !header.is_rsid {
+            if psid_uses_cia(header, song) && !header.is_rsid {
                 let cia_latch = cpu.memory.cia1.timer_a.latch as u64;
                 if cia_latch > 0 && cia_latch < 0xFFFF {
                     let clock = if header.is_pal { 985248u64 } else { 1022727u64 };
@@ -1824,6 +2203,12 @@ fn setup_playback(
         u64_screen_read_at: None,
         u64_screen_total_secs: None,
         device_connected: true,
+        voice_mask: VoiceMask::default(),
+        fade: None,
+        seek_to: None,
+        fade_step: 15,
+        tempo: (100, false),
+        stretch_pct: 100,
     }
 }
 
@@ -1868,6 +2253,116 @@ fn setup_psid_engine(
     PlayEngine::Psid(cpu)
 }
 
+/// PSID speed flag for `song`: true when PLAY is paced by CIA1 timer A,
+/// false for the VBI (50/60 Hz raster) default.
+fn psid_uses_cia(header: &SidHeader, song: u16) -> bool {
+    let song_idx = song.saturating_sub(1).min(31) as u32;
+    (header.speed >> song_idx) & 1 == 1
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Cycle-exact PSID engine setup (c64_emu + an IRQ-driven PSID driver)
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Where the PSID driver lives when the header declares no free pages:
+/// the cassette buffer, which PSIDs rarely load into.
+const PSID_DRIVER_ADDR: u16 = 0x033C;
+
+/// Address for the PSID driver: the first of the header's declared free
+/// pages (relocStartPage / relocPages) that is RAM whatever the banking,
+/// as `prg_export` picks its driver slot, else the cassette buffer.
+/// `None` when the tune loads over the chosen spot.
+fn psid_driver_addr(sid_file: &SidFile) -> Option<u16> {
+    let load = sid_file.load_address as u32;
+    let load_end = load + sid_file.payload.len() as u32;
+    let len = crate::prg_export::PSID_IRQ_HANDLER_LEN as u32;
+    let clear = |at: u32| at + len <= load || at >= load_end;
+    if let Some((page, count)) = sid_file::reloc_pages(sid_file) {
+        let visible = |p: u32| (0x04..0xA0).contains(&p) || (0xC0..0xD0).contains(&p);
+        let declared = (page as u32..page as u32 + count as u32)
+            .filter(|&p| visible(p))
+            .map(|p| p << 8)
+            .find(|&at| clear(at));
+        if let Some(at) = declared {
+            return Some(at as u16);
+        }
+        eprintln!("[phosphor] PSID's declared free pages can't hold the driver");
+    }
+    let at = PSID_DRIVER_ADDR as u32;
+    clear(at).then_some(at as u16)
+}
+
+/// Run a PSID on `RsidBus` the way a real C64 would with sidplay's
+/// driver: INIT runs from the trampoline with interrupts off, then PLAY
+/// is called from a raster or CIA IRQ, so SID writes carry the cycle
+/// they'd happen on — badline stalls included. Returns `None` when the
+/// tune leaves no room for the driver, so the caller can fall back.
+fn setup_psid_emu_engine(
+    sid_file: &SidFile,
+    path: &Path,
+    song: u16,
+    mapper: &SidMapper,
+    mono_mode: bool,
+    trampoline: u16,
+) -> Option<PlayEngine> {
+    let header = &sid_file.header;
+
+    let Some(driver) = psid_driver_addr(sid_file) else {
+        eprintln!("[phosphor] No free memory for the PSID driver, using the simple CPU");
+        return None;
+    };
+
+    let mut bus = RsidBus::new(header.is_pal, mapper.clone(), mono_mode);
+    bus.load(sid_file.load_address, &sid_file.payload);
+    bus.setup_machine_state(header.is_pal);
+    // Bank out BASIC / KERNAL ROM where the tune's code lives.
+    bus.c64.set_byte(0x0001, psid_bank(sid_file));
+    bus.install_kernal_stubs();
+    bus.install_software_vectors();
+    bus.set_hw_vector(0xFFFA, 0xFE43); // NMI → KERNAL NMI entry
+    bus.setup_rsid_cia_defaults(header.is_pal);
+    let vbi = !psid_uses_cia(header, song);
+    if !vbi {
+        bus.setup_psid_cia_latch(header.is_pal);
+    }
+    bus.install_psid_driver(driver, header.play_address, vbi);
+
+    let hack_flags = apply_hacks(
+        &mut bus,
+        header.init_address,
+        &compute_hvsc_md5(sid_file),
+        path,
+    );
+
+    // INIT trampoline, then CLI + idle loop: the first IRQ after INIT
+    // returns starts PLAY.
+    bus.install_trampoline(trampoline, header.init_address);
+    bus.c64.ram.ram[0x0303] = 0x58; // CLI
+    bus.c64.ram.ram[0x0304] = 0x4C; // JMP $0304  (idle loop)
+    bus.c64.ram.ram[0x0305] = 0x04;
+    bus.c64.ram.ram[0x0306] = 0x03;
+
+    let mut cpu = CPU::new(bus, Nmos6502);
+    cpu.registers.program_counter = trampoline;
+    cpu.registers.stack_pointer = StackPointer(0xFD);
+    cpu.registers.accumulator = song.saturating_sub(1) as u8;
+    cpu.registers.status.insert(Status::PS_DISABLE_INTERRUPTS);
+    cpu.memory.hack_flags = hack_flags;
+
+    eprintln!(
+        "[phosphor] PSID (c64_emu): load=${:04X} init=${:04X} play=${:04X}, {} IRQ",
+        sid_file.load_address,
+        header.init_address,
+        header.play_address,
+        if vbi { "raster" } else { "CIA" },
+    );
+
+    Some(PlayEngine::Rsid {
+        cpu,
+        prev_nmi: false,
+    })
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 //  RSID engine setup (uses c64_emu for accurate emulation)
 // ─────────────────────────────────────────────────────────────────────────────
@@ -1876,7 +2371,7 @@ fn setup_rsid_engine(
     song: u16,
     mapper: &SidMapper,
     mono_mode: bool,
-    _cycles_per_frame: u32,
+    path: &Path,
     trampoline: u16,
     _halt_pc: u16,
 ) -> PlayEngine {
@@ -1901,7 +2396,12 @@ fn setup_rsid_engine(
         );
     }
 
-    let hack_flags = apply_hacks(&mut bus, header.init_address);
+    let hack_flags = apply_hacks(
+        &mut bus,
+        header.init_address,
+        &compute_hvsc_md5(sid_file),
+        path,
+    );
 
     // ── BASIC RSID path ──────────────────────────────────────────────────
     let (mut bus, effective_init_addr, is_basic_mode) = if header.is_basic {
@@ -2304,61 +2804,136 @@ fn deliver_nmi_emu(cpu: &mut CPU<RsidBus, Nmos6502>) -> u32 {
 mod tests {
     use super::*;
 
-    /// Regression: a 1SID tune (`extra_sid_addrs = [0, 0]`) with the
-    /// canonical `sid4_addr = 0` must yield a single-entry base list.
-    /// Prior to the fix, five `PlayerCmd::Play` call sites hardcoded
-    /// `sid4_addr = 0xd420`, which pushed a phantom SID4 onto the list,
-    /// inflated `num_sids` from 1 → 2, and left the right channel
-    /// silent because the emulator's SID2 slot received no writes.
     #[test]
-    fn single_sid_stays_single_when_sid4_is_zero() {
-        let bases = compute_sid_bases([0, 0], 0);
+    fn hardware_fade_scales_only_volume_nibble() {
+        let filter = OutputFilter {
+            mask: VoiceMask::default(),
+            fade: 0.5,
+        };
+        assert_eq!(filter.apply(SID_VOL_REG, 0x1F), 0x18);
+        assert_eq!(filter.apply(SID_REG_SIZE + SID_VOL_REG, 0x7A), 0x75);
+        assert_eq!(filter.apply(0x04, 0x41), 0x41);
+        let silent = OutputFilter {
+            fade: 0.0,
+            ..filter
+        };
+        assert_eq!(silent.apply(SID_VOL_REG, 0x3F), 0x30);
+    }
+
+    /// Regression: a 1SID tune must yield a single-entry base list.
+    /// Callers used to pass a separate SID4 address that defaulted to
+    /// `0xd420` at several call sites, which pushed a phantom chip onto
+    /// the list, inflated `num_sids` from 1 → 2 and left the right
+    /// channel silent.
+    #[test]
+    fn single_sid_stays_single() {
+        let bases = compute_sid_bases([0, 0, 0]);
         assert_eq!(bases, vec![0xD400]);
         assert_eq!(bases.len(), 1, "num_sids must be 1 for a 1SID tune");
     }
 
     #[test]
-    fn single_sid_with_nonzero_sid4_becomes_multi() {
-        // Documents the historical bug: if the caller mistakenly passes
-        // sid4_addr = 0xd420 on a 1SID tune, num_sids inflates to 2.
-        // This test enforces that anyone touching this helper knows the
-        // semantics and doesn't accidentally restore the bug.
-        let bases = compute_sid_bases([0, 0], 0xD420);
-        assert_eq!(bases, vec![0xD400, 0xD420]);
-        assert_eq!(
-            bases.len(),
-            2,
-            "non-zero sid4_addr adds a chip — callers must pass 0 for 1SID tunes"
-        );
+    fn stereo_mus_maps_d500_to_the_second_chip() {
+        // load_mus_stub's header for a MUS with a .str companion.
+        let bases = compute_sid_bases([0xD500, 0, 0]);
+        assert_eq!(bases, vec![0xD400, 0xD500]);
+        let mapper = SidMapper::new(&bases);
+        assert_eq!(mapper.map(0xD504), Some(SID_REG_SIZE + 4));
     }
 
     #[test]
-    fn two_sid_from_header_no_sid4_override() {
+    fn two_sid_from_header() {
         // PSID v3+ tune with SID2 base at $D420 declared in the header.
-        let bases = compute_sid_bases([0xD420, 0], 0);
+        let bases = compute_sid_bases([0xD420, 0, 0]);
         assert_eq!(bases, vec![0xD400, 0xD420]);
     }
 
     #[test]
-    fn three_sid_from_header_no_sid4_override() {
+    fn three_sid_from_header() {
         // PSID v4 tune with SID2 and SID3 both declared in the header.
-        let bases = compute_sid_bases([0xD420, 0xD440], 0);
+        let bases = compute_sid_bases([0xD420, 0xD440, 0]);
         assert_eq!(bases, vec![0xD400, 0xD420, 0xD440]);
     }
 
     #[test]
-    fn four_sid_via_cli_override() {
-        // 3SID header + explicit `--sid4 D460` CLI override → 4 chips.
-        let bases = compute_sid_bases([0xD420, 0xD440], 0xD460);
+    fn four_sid_from_header() {
+        // v4 header with the SID4 extension byte → 4 chips, no CLI flag.
+        let bases = compute_sid_bases([0xD420, 0xD440, 0xD460]);
         assert_eq!(bases, vec![0xD400, 0xD420, 0xD440, 0xD460]);
     }
 
+    fn header_with(sid_model: u8, addrs: [u16; 3], models: [u8; 3]) -> SidHeader {
+        let mut sid = load_mus_stub(&[], None);
+        sid.header.sid_model = sid_model;
+        sid.header.extra_sid_addrs = addrs;
+        sid.header.extra_sid_models = models;
+        sid.header
+    }
+
+    #[test]
+    fn mixed_models_follow_chip_slots() {
+        // SID3 missing: SID4's model must land in slot 2, next to its base.
+        let h = header_with(1, [0xD420, 0, 0xD460], [2, 1, 2]);
+        assert_eq!(compute_sid_bases(h.extra_sid_addrs).len(), 3);
+        assert_eq!(resolve_sid_models(&h, [0; 4]), [1, 2, 2, 1]);
+    }
+
+    #[test]
+    fn unspecified_extra_chips_inherit_sid1() {
+        let h = header_with(2, [0xD420, 0xD440, 0], [0, 1, 0]);
+        assert_eq!(resolve_sid_models(&h, [0; 4]), [2, 2, 1, 2]);
+    }
+
+    #[test]
+    fn config_override_wins_per_chip() {
+        let h = header_with(1, [0xD420, 0, 0], [1, 0, 0]);
+        assert_eq!(resolve_sid_models(&h, [0, 2, 0, 0]), [1, 2, 1, 1]);
+    }
+
+    fn psid_at(load: u16, len: usize, reloc: (u8, u8)) -> SidFile {
+        let mut sid = load_mus_stub(&[], None);
+        sid.header.version = 2;
+        sid.load_address = load;
+        sid.payload = vec![0; len];
+        sid.raw = vec![0; 0x7C];
+        (sid.raw[0x78], sid.raw[0x79]) = reloc;
+        sid
+    }
+
+    #[test]
+    fn psid_driver_uses_declared_free_pages() {
+        // No declaration: the cassette buffer, unless the tune sits there.
+        assert_eq!(
+            psid_driver_addr(&psid_at(0x1000, 0x800, (0, 0))),
+            Some(0x033C)
+        );
+        assert_eq!(psid_driver_addr(&psid_at(0x0300, 0x800, (0, 0))), None);
+        // Declared pages win, skipping ones behind BASIC ROM.
+        assert_eq!(
+            psid_driver_addr(&psid_at(0x1000, 0x800, (0xC0, 4))),
+            Some(0xC000)
+        );
+        assert_eq!(
+            psid_driver_addr(&psid_at(0x1000, 0x800, (0xBF, 2))),
+            Some(0xC000)
+        );
+        // Also when the tune covers the cassette buffer.
+        assert_eq!(
+            psid_driver_addr(&psid_at(0x0300, 0x800, (0x20, 1))),
+            Some(0x2000)
+        );
+    }
+
     #[test]
-    fn parse_sid4_from_args_defaults_to_zero() {
-        // `parse_sid4_from_args()` inspects `std::env::args()`. In the
-        // test binary's process, --sid4 is not passed, so it must
-        // return 0. This locks in the "safe default" every caller
-        // depends on to avoid re-introducing the phantom-SID4 bug.
-        assert_eq!(crate::parse_sid4_from_args(), 0);
+    fn psid_speed_bit_per_song() {
+        let mut h = header_with(1, [0, 0, 0], [0, 0, 0]);
+        h.speed = 0b10;
+        assert!(!psid_uses_cia(&h, 1));
+        assert!(psid_uses_cia(&h, 2));
+        // Songs past 32 share the top bit.
+        h.speed = 1 << 31;
+        assert!(psid_uses_cia(&h, 32));
+        assert!(psid_uses_cia(&h, 40));
+        assert!(!psid_uses_cia(&h, 31));
     }
 }
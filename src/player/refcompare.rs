@@ -0,0 +1,407 @@
+// Reference comparison — `phosphor --compare <file-or-dir>`.
+//
+// Runs a subtune on libsidplayfp and on our own c64_emu core (`RsidBus`,
+// the RSID path; PSIDs go through the cycle-exact PSID driver) side by
+// side and reports the first SID register write that differs, with the
+// CPU registers and the RAM the two machines disagree on at that point.
+//
+// Only the order and values of the writes are compared. The two engines
+// start INIT at different points after reset and use different drivers,
+// so a PLAY burst can straddle a frame boundary on one side and not the
+// other. Each side's writes are therefore compared as one flat stream
+// with a cursor per side; frame numbers only label where the mismatch
+// happened. Cycle stamps are shown in the report but not held against us.
+
+use std::collections::VecDeque;
+use std::path::Path;
+
+use mos6502::cpu::CPU;
+use mos6502::instruction::Nmos6502;
+
+use super::libsidplayfp::LibSidPlayFp;
+use super::memory::{SidMapper, SidWrite, NTSC_CYCLES_PER_FRAME, PAL_CYCLES_PER_FRAME};
+use super::rsid_bus::RsidBus;
+use super::{
+    compute_sid_bases, load_sid_or_mus, run_rsid_sub_emu, setup_psid_emu_engine, setup_rsid_engine,
+    sid_file, PlayEngine,
+};
+
+/// RAM differences listed in a report; the rest are only counted.
+const MAX_RAM_DIFFS: usize = 16;
+
+/// Result of one comparison run.
+pub enum Outcome {
+    /// The write streams agreed for `frames` frames.
+    Match {
+        frames: u32,
+    },
+    Diverged(Box<Divergence>),
+}
+
+/// First write where the two engines' streams differ.
+pub struct Divergence {
+    /// Frame holding the mismatch on our side and on libsidplayfp's
+    /// (they differ when one engine started writing later).
+    pub our_frame: u32,
+    pub ref_frame: u32,
+    /// Index of the differing write within our frame and theirs.
+    pub write: usize,
+    pub ref_write: usize,
+    ours: Vec<SidWrite>,
+    theirs: Vec<SidWrite>,
+    context: String,
+}
+
+impl Divergence {
+    /// Multi-line report: both frames' writes, then the machine state.
+    pub fn report(&self) -> String {
+        let mut out = format!(
+            "First divergence: our frame {} write #{}, libsidplayfp frame {} write #{}\n",
+            self.our_frame, self.write, self.ref_frame, self.ref_write
+        );
+        out.push_str("   #  ours (cycle: reg=val)     libsidplayfp\n");
+        let mark = |i: usize, at: usize| if i == at { '>' } else { ' ' };
+        for i in 0..self.ours.len().max(self.theirs.len()) {
+            out.push_str(&format!(
+                "{}{i:3}  {:<21}  {}  {}\n",
+                mark(i, self.write),
+                fmt_write(self.ours.get(i)),
+                mark(i, self.ref_write),
+                fmt_write(self.theirs.get(i)),
+            ));
+        }
+        out.push_str(&self.context);
+        out
+    }
+}
+
+/// Run `song` (1-based; `None` = header start song) for up to `frames`
+/// frames on both engines. Returns the outcome and the song that ran.
+pub fn compare(path: &Path, song: Option<u16>, frames: u32) -> Result<(Outcome, u16), String> {
+    let sid_file = load_sid_or_mus(path)?;
+    let header = &sid_file.header;
+    if header.magic == "MUS" {
+        return Err("MUS files only play on libsidplayfp".into());
+    }
+    let sid_bases = compute_sid_bases(header.extra_sid_addrs);
+    if sid_bases.len() > 3 {
+        return Err(format!(
+            "{}SID tunes are beyond libsidplayfp",
+            sid_bases.len()
+        ));
+    }
+    let songs = header.songs.max(1);
+    let song = song.unwrap_or(header.start_song).clamp(1, songs);
+    let is_rsid = header.is_rsid || (header.play_address == 0 && header.magic == "PSID");
+    let cycles_per_frame = if header.is_pal {
+        PAL_CYCLES_PER_FRAME
+    } else {
+        NTSC_CYCLES_PER_FRAME
+    };
+
+    let raw = sid_file::clock_patched_raw(&sid_file).unwrap_or_else(|| sid_file.raw.clone());
+    let mut reference = LibSidPlayFp::new(&raw, song)?;
+
+    let mapper = SidMapper::new(&sid_bases);
+    let mono_mode = sid_bases.len() <= 1;
+    let trampoline: u16 = 0x0300;
+    let engine = if is_rsid {
+        setup_rsid_engine(
+            &sid_file,
+            song,
+            &mapper,
+            mono_mode,
+            path,
+            trampoline,
+            trampoline + 3,
+        )
+    } else {
+        setup_psid_emu_engine(&sid_file, path, song, &mapper, mono_mode, trampoline)
+            .ok_or("PSID loads over the cycle-exact driver")?
+    };
+    let PlayEngine::Rsid {
+        mut cpu,
+        mut prev_nmi,
+    } = engine
+    else {
+        return Err("c64_emu engine did not start".into());
+    };
+
+    let load = sid_file.load_address as usize;
+    let load_end = (load + sid_file.payload.len()).min(0x1_0000);
+    let mut aligner = WriteAligner::default();
+    // libsidplayfp can run a frame short or long; label its writes by
+    // the cycles it actually ran.
+    let mut ref_cycles = 0u64;
+    for frame in 0..frames {
+        cpu.memory.clear_writes();
+        run_rsid_sub_emu(&mut cpu, cycles_per_frame, &mut prev_nmi);
+        let ref_frame = (ref_cycles / cycles_per_frame as u64) as u32;
+        ref_cycles += reference.run_frame(cycles_per_frame) as u64;
+
+        let ours = aligner.push(Side::Ours, frame, cpu.memory.sid_writes.clone());
+        let theirs = aligner.push(Side::Reference, ref_frame, reference.sid_writes.clone());
+        if let Some(mut d) = ours.or(theirs) {
+            d.context = context_dump(&cpu, &reference, load..load_end);
+            return Ok((Outcome::Diverged(Box::new(d)), song));
+        }
+    }
+    if let Some(mut d) = aligner.finish() {
+        d.context = context_dump(&cpu, &reference, load..load_end);
+        return Ok((Outcome::Diverged(Box::new(d)), song));
+    }
+    Ok((Outcome::Match { frames }, song))
+}
+
+/// Frames one side may run ahead of the other, counted from each side's
+/// first write, before its unmatched writes count as a divergence.
+const MAX_LAG: u32 = 2;
+
+#[derive(Clone, Copy)]
+enum Side {
+    Ours,
+    Reference,
+}
+
+/// One side's write stream, kept per frame for labelling and reports.
+#[derive(Default)]
+struct Stream {
+    /// Frames holding unmatched writes, oldest first.
+    frames: VecDeque<(u32, Vec<SidWrite>)>,
+    /// Next unmatched write in the front frame.
+    next: usize,
+    /// Frame of this side's first write.
+    start: Option<u32>,
+    /// Latest frame pushed.
+    last: u32,
+}
+
+impl Stream {
+    fn push(&mut self, frame: u32, writes: Vec<SidWrite>) {
+        self.last = frame;
+        if !writes.is_empty() {
+            self.start.get_or_insert(frame);
+            self.frames.push_back((frame, writes));
+        }
+    }
+
+    fn peek(&self) -> Option<&SidWrite> {
+        self.frames.front().map(|(_, w)| &w[self.next])
+    }
+
+    fn advance(&mut self) {
+        self.next += 1;
+        if self
+            .frames
+            .front()
+            .is_some_and(|(_, w)| self.next == w.len())
+        {
+            self.frames.pop_front();
+            self.next = 0;
+        }
+    }
+
+    /// Frames since this side's first write.
+    fn progress(&self, frame: u32) -> u32 {
+        frame - self.start.unwrap_or(frame)
+    }
+
+    /// True when the front frame's writes have waited more than
+    /// `MAX_LAG` frames for `other` to produce their counterpart.
+    fn stalled_against(&self, other: &Stream) -> bool {
+        self.frames.front().is_some_and(|&(f, _)| {
+            other.start.is_some() && other.progress(other.last) > self.progress(f) + MAX_LAG
+        })
+    }
+
+    /// Frame, writes and write index where the stream stands.
+    fn position(&self) -> (u32, Vec<SidWrite>, usize) {
+        match self.frames.front() {
+            Some((f, w)) => (*f, w.clone(), self.next),
+            None => (self.last, Vec::new(), 0),
+        }
+    }
+}
+
+/// Compares the two write streams write by write, whichever frames the
+/// writes land in.
+#[derive(Default)]
+struct WriteAligner {
+    sides: [Stream; 2],
+}
+
+impl WriteAligner {
+    fn push(&mut self, side: Side, frame: u32, writes: Vec<SidWrite>) -> Option<Divergence> {
+        self.sides[side as usize].push(frame, writes);
+        loop {
+            let [ours, theirs] = &mut self.sides;
+            match (ours.peek(), theirs.peek()) {
+                (Some(a), Some(b)) if (a.1, a.2) != (b.1, b.2) => return Some(self.divergence()),
+                (Some(_), Some(_)) => {
+                    ours.advance();
+                    theirs.advance();
+                }
+                _ => break,
+            }
+        }
+        let [ours, theirs] = &self.sides;
+        (ours.stalled_against(theirs) || theirs.stalled_against(ours)).then(|| self.divergence())
+    }
+
+    /// Writes one side made that the other never matched.
+    fn finish(&self) -> Option<Divergence> {
+        let [ours, theirs] = &self.sides;
+        let unmatched = |s: &Stream, o: &Stream| s.frames.front().is_some() && o.start.is_none();
+        (unmatched(ours, theirs) || unmatched(theirs, ours)).then(|| self.divergence())
+    }
+
+    fn divergence(&self) -> Divergence {
+        let (our_frame, ours, write) = self.sides[0].position();
+        let (ref_frame, theirs, ref_write) = self.sides[1].position();
+        Divergence {
+            our_frame,
+            ref_frame,
+            write,
+            ref_write,
+            ours,
+            theirs,
+            context: String::new(),
+        }
+    }
+}
+
+fn fmt_write(w: Option<&SidWrite>) -> String {
+    match w {
+        Some(&(cycle, reg, val)) => format!("{cycle:5}: {reg:02X}={val:02X}"),
+        None => "-".to_string(),
+    }
+}
+
+/// CPU registers, code at PC, and the zero-page / tune RAM bytes the two
+/// machines disagree on.
+fn context_dump(
+    cpu: &CPU<RsidBus, Nmos6502>,
+    reference: &LibSidPlayFp,
+    tune: std::ops::Range<usize>,
+) -> String {
+    let r = &cpu.registers;
+    let pc = r.program_counter;
+    let mut out = format!(
+        "c64_emu CPU: PC=${pc:04X} A=${:02X} X=${:02X} Y=${:02X} SP=${:02X} P=${:02X}\n",
+        r.accumulator,
+        r.index_x,
+        r.index_y,
+        r.stack_pointer.0,
+        r.status.bits(),
+    );
+
+    let ram = &cpu.memory.c64.ram.ram;
+    let start = pc.wrapping_sub(8) & 0xFFF8;
+    let row = |read: &dyn Fn(u16) -> u8| -> String {
+        (0..16u16)
+            .map(|i| format!("{:02X}", read(start.wrapping_add(i))))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    out.push_str(&format!(
+        "RAM ${start:04X}  ours: {}\n            ref:  {}\n",
+        row(&|a| ram[a as usize]),
+        row(&|a| reference.read_mem(a)),
+    ));
+
+    // The drivers, stack and I/O differ by design; compare only the zero
+    // page above the CPU port and the range the tune was loaded into.
+    let diffs: Vec<usize> = (0x02..0x100)
+        .chain(tune.filter(|a| *a >= 0x100))
+        .filter(|&a| ram[a] != reference.read_mem(a as u16))
+        .collect();
+    if diffs.is_empty() {
+        out.push_str("RAM: zero page and tune area identical\n");
+    } else {
+        out.push_str(&format!("RAM: {} bytes differ", diffs.len()));
+        if diffs.len() > MAX_RAM_DIFFS {
+            out.push_str(&format!(" (first {MAX_RAM_DIFFS})"));
+        }
+        out.push('\n');
+        for &a in diffs.iter().take(MAX_RAM_DIFFS) {
+            out.push_str(&format!(
+                "  ${a:04X}  ours={:02X} ref={:02X}\n",
+                ram[a],
+                reference.read_mem(a as u16)
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(writes: &[(u8, u8)]) -> Vec<SidWrite> {
+        writes
+            .iter()
+            .enumerate()
+            .map(|(i, &(reg, val))| (i as u32 * 20, reg, val))
+            .collect()
+    }
+
+    #[test]
+    fn streams_line_up_after_leading_silence() {
+        let mut a = WriteAligner::default();
+        // Reference starts writing one frame later; cycles differ too.
+        assert!(a.push(Side::Ours, 0, frame(&[(0x18, 0x0F)])).is_none());
+        assert!(a.push(Side::Reference, 0, Vec::new()).is_none());
+        assert!(a.push(Side::Ours, 1, frame(&[(0x04, 0x41)])).is_none());
+        assert!(a
+            .push(Side::Reference, 1, vec![(900, 0x18, 0x0F)])
+            .is_none());
+        assert!(a.push(Side::Ours, 2, Vec::new()).is_none());
+        assert!(a.push(Side::Reference, 2, frame(&[(0x04, 0x41)])).is_none());
+        assert!(a.finish().is_none());
+    }
+
+    #[test]
+    fn burst_split_by_a_frame_boundary_still_matches() {
+        let mut a = WriteAligner::default();
+        let burst = [(0x00, 0x10), (0x01, 0x20), (0x04, 0x41)];
+        assert!(a.push(Side::Ours, 0, frame(&burst)).is_none());
+        // Their frame ends after two writes; the third lands in frame 1.
+        assert!(a.push(Side::Reference, 0, frame(&burst[..2])).is_none());
+        assert!(a.push(Side::Ours, 1, frame(&[(0x18, 0x0F)])).is_none());
+        assert!(a
+            .push(Side::Reference, 1, frame(&[(0x04, 0x41), (0x18, 0x0F)]))
+            .is_none());
+        assert!(a.finish().is_none());
+    }
+
+    #[test]
+    fn mismatch_points_at_first_differing_write() {
+        let mut a = WriteAligner::default();
+        assert!(a.push(Side::Ours, 0, frame(&[(0, 1)])).is_none());
+        assert!(a.push(Side::Reference, 0, frame(&[(0, 1)])).is_none());
+        assert!(a.push(Side::Ours, 1, frame(&[(0, 1), (1, 2)])).is_none());
+        let d = a
+            .push(Side::Reference, 1, frame(&[(0, 1), (1, 3)]))
+            .unwrap();
+        assert_eq!(
+            (d.our_frame, d.write, d.ref_frame, d.ref_write),
+            (1, 1, 1, 1)
+        );
+    }
+
+    #[test]
+    fn writes_the_other_side_never_makes_diverge() {
+        let mut a = WriteAligner::default();
+        assert!(a.push(Side::Ours, 0, frame(&[(0x18, 0x0F)])).is_none());
+        assert!(a.push(Side::Reference, 0, frame(&[(0x18, 0x0F)])).is_none());
+        assert!(a.push(Side::Ours, 1, frame(&[(0x01, 0x10)])).is_none());
+        let mut d = None;
+        for f in 1..=MAX_LAG + 2 {
+            d = d.or(a.push(Side::Reference, f, Vec::new()));
+        }
+        let d = d.unwrap();
+        assert_eq!((d.our_frame, d.write), (1, 0));
+        assert!(d.theirs.is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+// Headless SIDdump export — `phosphor --siddump <file.sid>`.
+//
+// Runs a subtune through the same engine `setup_playback` picks for live
+// playback, with no device attached, and writes one `crate::siddump` row
+// per play call. Unlike the live tracker ring this isn't limited to the
+// last few seconds, so it's what to use for transcribing a whole tune.
+
+use std::path::Path;
+
+use super::{load_sid_or_mus, setup_playback};
+use crate::siddump::SidDump;
+
+/// Dump `frames` play calls of `song` (1-based; `None` = header start
+/// song). Returns the dump text and the song that was dumped.
+pub fn dump(path: &Path, song: Option<u16>, frames: u32) -> Result<(String, u16), String> {
+    let sid_file = load_sid_or_mus(path)?;
+    let songs = sid_file.header.songs.max(1);
+    let song = song.unwrap_or(sid_file.header.start_song).clamp(1, songs);
+    let is_rsid = sid_file.header.is_rsid
+        || (sid_file.header.play_address == 0 && sid_file.header.magic == "PSID");
+
+    let mut ctx = setup_playback(
+        sid_file,
+        path.to_path_buf(),
+        song,
+        false,
+        is_rsid,
+        &mut None,
+    );
+    if ctx.is_native() {
+        return Err("Native engines cannot be dumped".into());
+    }
+
+    let mut dump = SidDump::new(ctx.track_info.num_sids, ctx.track_info.is_pal);
+    for frame in 0..frames {
+        ctx.emulate_frame();
+        dump.push(frame as u64, &ctx.sid_regs());
+    }
+    Ok((dump.finish(), song))
+}
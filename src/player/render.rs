@@ -0,0 +1,523 @@
+// Headless offline render — `phosphor render <file.sid> -o out.wav`.
+//
+// Drives the same engine `setup_playback` picks for live playback
+// (libsidplayfp first, built-in PSID/RSID CPU as fallback), but without
+// frame pacing: each frame's SID writes go straight into an offline
+// reSID / SIDLite device and the samples it generates are appended to a
+// WAV or FLAC file. A 3-minute tune renders in a couple of seconds.
+//
+// FLAC output uses VERBATIM subframes only — bit-exact and readable by
+// every decoder, just not compressed. Good enough for listening tests;
+// re-encode with `flac -8` if size matters.
+
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::sid_file::{compute_hvsc_md5, load_mus_stub, load_sid, SidFile};
+use super::{send_sid_writes, setup_playback};
+use crate::sid_device::{create_offline_engine, SidDevice};
+
+/// Length used when neither `--seconds` nor the Songlength DB nor the
+/// configured default song length give us one.
+const FALLBACK_SECONDS: u32 = 180;
+
+/// FLAC block size (samples per channel per frame).
+const FLAC_BLOCK_SIZE: usize = 4096;
+
+/// Everything `phosphor render` needs, already parsed from the command line.
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    pub input: PathBuf,
+    pub output: PathBuf,
+    /// 1-based subtune; `None` = header start song.
+    pub song: Option<u16>,
+    /// Render length; `None` = Songlength DB, then config default.
+    pub seconds: Option<u32>,
+    /// "resid" (alias "emulated") or "sidlite".
+    pub engine: String,
+    pub sample_rate: u32,
+}
+
+/// What actually got rendered, for the CLI summary line.
+#[derive(Debug, Clone)]
+pub struct RenderSummary {
+    pub song: u16,
+    pub seconds: u32,
+    pub sample_rate: u32,
+    pub samples: u64,
+}
+
+/// Render one subtune to `opts.output`. Format is picked from the output
+/// extension: `.flac` → FLAC, anything else → 16-bit stereo WAV.
+pub fn render(opts: &RenderOptions) -> Result<RenderSummary, String> {
+    let data = std::fs::read(&opts.input)
+        .map_err(|e| format!("Cannot read {}: {e}", opts.input.display()))?;
+
+    let is_mus = has_extension(&opts.input, "mus");
+    let sid_file = match load_sid(&data) {
+        Ok(s) => s,
+        Err(_) if is_mus => load_mus_stub(&data, Some(&opts.input)),
+        Err(e) => return Err(format!("SID parse error: {e}")),
+    };
+
+    let songs = sid_file.header.songs.max(1);
+    let song = opts
+        .song
+        .unwrap_or(sid_file.header.start_song)
+        .clamp(1, songs);
+    let is_rsid = sid_file.header.is_rsid
+        || (sid_file.header.play_address == 0 && sid_file.header.magic == "PSID");
+
+    let seconds = match opts.seconds {
+        Some(s) => s,
+        None => resolve_length(&sid_file, &opts.input, song),
+    };
+
+    let sample_rate = if opts.engine == "sidlite" {
+        opts.sample_rate.min(crate::sid_sidlite::MAX_SAMPLE_RATE)
+    } else {
+        opts.sample_rate
+    };
+
+    let mut device = create_offline_engine(&opts.engine, sample_rate)?;
+    device.init()?;
+    let mut bridge: Option<Box<dyn SidDevice>> = Some(device);
+
+    eprintln!(
+        "[render] \"{}\" by {} — song {}/{}, {}s, {} @ {}Hz → {}",
+        sid_file.header.name,
+        sid_file.header.author,
+        song,
+        songs,
+        seconds,
+        opts.engine,
+        sample_rate,
+        opts.output.display(),
+    );
+
+    let mut ctx = setup_playback(
+        sid_file,
+        opts.input.clone(),
+        song,
+        false,
+        is_rsid,
+        &mut bridge,
+    );
+    if ctx.is_native() {
+        return Err("Native engines cannot render offline".into());
+    }
+
+    let mut out = AudioFileWriter::create(&opts.output, sample_rate)?;
+    let total = seconds as u64 * sample_rate as u64;
+    let frame_dur = Duration::from_micros(ctx.frame_us);
+    // Give up if the engine stops producing samples — 2× the expected
+    // frame count plus slack covers CIA-paced tunes with short frames.
+    let max_frames = (seconds as u64 * 1_000_000 / ctx.frame_us.max(1)) * 2 + 500;
+    let mut written: u64 = 0;
+    let mut frames: u64 = 0;
+
+    while written < total {
+        if frames > max_frames {
+            return Err(format!(
+                "Engine stalled after {frames} frames ({written}/{total} samples)"
+            ));
+        }
+        let cycles = ctx.emulate_frame();
+        let Some(ref mut br) = bridge else { break };
+        br.set_cycles_per_frame(cycles);
+        send_sid_writes(br.as_mut(), ctx.sid_writes(), ctx.mirror_mono, cycles, 100);
+        br.flush();
+
+        let pairs = br.take_rendered();
+        let take = ((total - written) as usize).min(pairs.len());
+        out.write_pairs(&pairs[..take])?;
+        written += take as u64;
+
+        frames += 1;
+        ctx.frame_count = ctx.frame_count.wrapping_add(1);
+        ctx.elapsed += frame_dur;
+    }
+
+    out.finish()?;
+
+    Ok(RenderSummary {
+        song,
+        seconds,
+        sample_rate,
+        samples: written,
+    })
+}
+
+/// HVSC Songlength DB first (md5, then HVSC-relative path, then the local
+/// estimate overlay), then the configured default song length, then
+/// `FALLBACK_SECONDS`.
+fn resolve_length(sid_file: &SidFile, path: &Path, song: u16) -> u32 {
+    let config = crate::config::Config::load();
+    let md5 = compute_hvsc_md5(sid_file);
+    let subtune = song.saturating_sub(1) as usize;
+
+    let from_db = crate::config::songlength_db_path()
+        .filter(|p| p.exists())
+        .and_then(|p| crate::playlist::SonglengthDb::load(&p).ok())
+        .and_then(|db| {
+            db.lookup_with_path_fallback(
+                Some(&md5),
+                path,
+                config.hvsc_root.as_deref().map(Path::new),
+                subtune,
+            )
+        });
+
+    if let Some(secs) = from_db {
+        eprintln!("[render] Songlength DB: {secs}s");
+        secs
+    } else if config.default_song_length_secs > 0 {
+        eprintln!(
+            "[render] No Songlength entry, using default length {}s",
+            config.default_song_length_secs
+        );
+        config.default_song_length_secs
+    } else {
+        eprintln!("[render] No Songlength entry, rendering {FALLBACK_SECONDS}s");
+        FALLBACK_SECONDS
+    }
+}
+
+fn has_extension(path: &Path, ext: &str) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case(ext))
+        .unwrap_or(false)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Output files
+// ─────────────────────────────────────────────────────────────────────────────
+
+enum AudioFileWriter {
+    Wav(WavWriter),
+    Flac(FlacWriter),
+}
+
+impl AudioFileWriter {
+    fn create(path: &Path, sample_rate: u32) -> Result<Self, String> {
+        let file =
+            File::create(path).map_err(|e| format!("Cannot create {}: {e}", path.display()))?;
+        let w = BufWriter::new(file);
+        if has_extension(path, "flac") {
+            Ok(Self::Flac(FlacWriter::new(w, sample_rate)?))
+        } else {
+            Ok(Self::Wav(WavWriter::new(w, sample_rate)?))
+        }
+    }
+
+    fn write_pairs(&mut self, pairs: &[(i16, i16)]) -> Result<(), String> {
+        match self {
+            Self::Wav(w) => w.write_pairs(pairs),
+            Self::Flac(f) => f.write_pairs(pairs),
+        }
+    }
+
+    fn finish(self) -> Result<(), String> {
+        match self {
+            Self::Wav(w) => w.finish(),
+            Self::Flac(f) => f.finish(),
+        }
+    }
+}
+
+/// 16-bit stereo PCM WAV. Sizes are patched in `finish()`.
+struct WavWriter {
+    out: BufWriter<File>,
+    data_bytes: u32,
+}
+
+impl WavWriter {
+    fn new(mut out: BufWriter<File>, sample_rate: u32) -> Result<Self, String> {
+        out.write_all(&wav_header(sample_rate, 0))
+            .map_err(|e| format!("WAV write: {e}"))?;
+        Ok(Self { out, data_bytes: 0 })
+    }
+
+    fn write_pairs(&mut self, pairs: &[(i16, i16)]) -> Result<(), String> {
+        let mut buf = Vec::with_capacity(pairs.len() * 4);
+        for &(l, r) in pairs {
+            buf.extend_from_slice(&l.to_le_bytes());
+            buf.extend_from_slice(&r.to_le_bytes());
+        }
+        self.out
+            .write_all(&buf)
+            .map_err(|e| format!("WAV write: {e}"))?;
+        self.data_bytes = self.data_bytes.saturating_add(buf.len() as u32);
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<(), String> {
+        let patch = |out: &mut BufWriter<File>, at: u64, v: u32| -> std::io::Result<()> {
+            out.seek(SeekFrom::Start(at))?;
+            out.write_all(&v.to_le_bytes())
+        };
+        (|| -> std::io::Result<()> {
+            patch(&mut self.out, 4, 36 + self.data_bytes)?;
+            patch(&mut self.out, 40, self.data_bytes)?;
+            self.out.flush()
+        })()
+        .map_err(|e| format!("WAV finalise: {e}"))
+    }
+}
+
+/// Canonical 44-byte RIFF/WAVE header for 16-bit stereo PCM.
+fn wav_header(sample_rate: u32, data_bytes: u32) -> [u8; 44] {
+    let mut h = [0u8; 44];
+    h[0..4].copy_from_slice(b"RIFF");
+    h[4..8].copy_from_slice(&(36 + data_bytes).to_le_bytes());
+    h[8..12].copy_from_slice(b"WAVE");
+    h[12..16].copy_from_slice(b"fmt ");
+    h[16..20].copy_from_slice(&16u32.to_le_bytes());
+    h[20..22].copy_from_slice(&1u16.to_le_bytes()); // PCM
+    h[22..24].copy_from_slice(&2u16.to_le_bytes()); // channels
+    h[24..28].copy_from_slice(&sample_rate.to_le_bytes());
+    h[28..32].copy_from_slice(&(sample_rate * 4).to_le_bytes()); // byte rate
+    h[32..34].copy_from_slice(&4u16.to_le_bytes()); // block align
+    h[34..36].copy_from_slice(&16u16.to_le_bytes()); // bits per sample
+    h[36..40].copy_from_slice(b"data");
+    h[40..44].copy_from_slice(&data_bytes.to_le_bytes());
+    h
+}
+
+/// Minimal FLAC encoder: fixed 4096-sample blocks, stereo, 16-bit,
+/// VERBATIM subframes. STREAMINFO's sample count and MD5 are patched
+/// in `finish()`.
+struct FlacWriter {
+    out: BufWriter<File>,
+    sample_rate: u32,
+    pending: Vec<(i16, i16)>,
+    frame_number: u32,
+    total_samples: u64,
+    md5: md5::Context,
+}
+
+impl FlacWriter {
+    fn new(mut out: BufWriter<File>, sample_rate: u32) -> Result<Self, String> {
+        let mut head = Vec::with_capacity(42);
+        head.extend_from_slice(b"fLaC");
+        // Last-metadata-block flag + type 0 (STREAMINFO), length 34.
+        head.extend_from_slice(&[0x80, 0x00, 0x00, 34]);
+        head.extend_from_slice(&flac_streaminfo(sample_rate, 0, [0; 16]));
+        out.write_all(&head)
+            .map_err(|e| format!("FLAC write: {e}"))?;
+        Ok(Self {
+            out,
+            sample_rate,
+            pending: Vec::with_capacity(FLAC_BLOCK_SIZE),
+            frame_number: 0,
+            total_samples: 0,
+            md5: md5::Context::new(),
+        })
+    }
+
+    fn write_pairs(&mut self, pairs: &[(i16, i16)]) -> Result<(), String> {
+        for &pair in pairs {
+            self.pending.push(pair);
+            if self.pending.len() == FLAC_BLOCK_SIZE {
+                self.write_frame()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_frame(&mut self) -> Result<(), String> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let frame = flac_frame(&self.pending, self.frame_number);
+        self.out
+            .write_all(&frame)
+            .map_err(|e| format!("FLAC write: {e}"))?;
+
+        // STREAMINFO MD5 covers the interleaved little-endian samples.
+        let mut le = Vec::with_capacity(self.pending.len() * 4);
+        for &(l, r) in &self.pending {
+            le.extend_from_slice(&l.to_le_bytes());
+            le.extend_from_slice(&r.to_le_bytes());
+        }
+        self.md5.consume(&le);
+
+        self.total_samples += self.pending.len() as u64;
+        self.frame_number += 1;
+        self.pending.clear();
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<(), String> {
+        self.write_frame()?;
+        let digest = self.md5.compute();
+        let info = flac_streaminfo(self.sample_rate, self.total_samples, digest.0);
+        (|| -> std::io::Result<()> {
+            self.out.seek(SeekFrom::Start(8))?;
+            self.out.write_all(&info)?;
+            self.out.flush()
+        })()
+        .map_err(|e| format!("FLAC finalise: {e}"))
+    }
+}
+
+/// 34-byte STREAMINFO body.
+fn flac_streaminfo(sample_rate: u32, total_samples: u64, md5: [u8; 16]) -> [u8; 34] {
+    let mut b = [0u8; 34];
+    let bs = FLAC_BLOCK_SIZE as u16;
+    b[0..2].copy_from_slice(&bs.to_be_bytes()); // min block size
+    b[2..4].copy_from_slice(&bs.to_be_bytes()); // max block size
+
+    // [4..10] min/max frame size: 0 = unknown.
+    // 20 bits rate | 3 bits channels-1 | 5 bits bps-1 | 36 bits total samples
+    let packed: u64 = ((sample_rate as u64 & 0xF_FFFF) << 44)
+        | (1u64 << 41)
+        | (15u64 << 36)
+        | (total_samples & 0xF_FFFF_FFFF);
+    b[10..18].copy_from_slice(&packed.to_be_bytes());
+    b[18..34].copy_from_slice(&md5);
+    b
+}
+
+/// One complete FLAC frame (header, two VERBATIM subframes, CRC-16).
+fn flac_frame(block: &[(i16, i16)], frame_number: u32) -> Vec<u8> {
+    let mut f = Vec::with_capacity(16 + block.len() * 4);
+    // Sync code + fixed-blocksize strategy.
+    f.extend_from_slice(&[0xFF, 0xF8]);
+    // Block-size code: 0b1100 = 4096, 0b0111 = 16-bit value follows.
+    // Sample-rate code 0 = take it from STREAMINFO.
+    let full = block.len() == FLAC_BLOCK_SIZE;
+    f.push(if full { 0xC0 } else { 0x70 });
+    // Channels 0b0001 (L/R independent), sample size 0b100 (16 bit).
+    f.push(0x18);
+    f.extend_from_slice(&flac_utf8(frame_number));
+    if !full {
+        f.extend_from_slice(&((block.len() - 1) as u16).to_be_bytes());
+    }
+    f.push(crc8(&f));
+
+    // Subframe header 0b0_000001_0 = VERBATIM, no wasted bits.
+    f.push(0x02);
+    for &(l, _) in block {
+        f.extend_from_slice(&l.to_be_bytes());
+    }
+    f.push(0x02);
+    for &(_, r) in block {
+        f.extend_from_slice(&r.to_be_bytes());
+    }
+
+    let crc = crc16(&f);
+    f.extend_from_slice(&crc.to_be_bytes());
+    f
+}
+
+/// FLAC's "UTF-8"-style variable-length frame number coding.
+fn flac_utf8(n: u32) -> Vec<u8> {
+    if n < 0x80 {
+        return vec![n as u8];
+    }
+    let (len, lead): (usize, u8) = match n {
+        0..=0x7FF => (2, 0xC0),
+        0x800..=0xFFFF => (3, 0xE0),
+        0x1_0000..=0x1F_FFFF => (4, 0xF0),
+        0x20_0000..=0x3FF_FFFF => (5, 0xF8),
+        _ => (6, 0xFC),
+    };
+    let mut out = vec![0u8; len];
+    let mut v = n;
+    for i in (1..len).rev() {
+        out[i] = 0x80 | (v & 0x3F) as u8;
+        v >>= 6;
+    }
+    out[0] = lead | v as u8;
+    out
+}
+
+/// CRC-8, polynomial x^8 + x^2 + x + 1 (0x07), init 0.
+fn crc8(data: &[u8]) -> u8 {
+    let mut crc: u8 = 0;
+    for &b in data {
+        crc ^= b;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// CRC-16, polynomial x^16 + x^15 + x^2 + 1 (0x8005), init 0.
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &b in data {
+        crc ^= (b as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc_check_values() {
+        // Standard "123456789" check values for CRC-8/SMBUS and
+        // CRC-16/UMTS (a.k.a. BUYPASS) — the two CRCs FLAC uses.
+        assert_eq!(crc8(b"123456789"), 0xF4);
+        assert_eq!(crc16(b"123456789"), 0xFEE8);
+    }
+
+    #[test]
+    fn utf8_frame_numbers() {
+        assert_eq!(flac_utf8(0), vec![0x00]);
+        assert_eq!(flac_utf8(0x7F), vec![0x7F]);
+        assert_eq!(flac_utf8(0x80), vec![0xC2, 0x80]);
+        assert_eq!(flac_utf8(0x7FF), vec![0xDF, 0xBF]);
+        assert_eq!(flac_utf8(0x800), vec![0xE0, 0xA0, 0x80]);
+    }
+
+    #[test]
+    fn streaminfo_packs_rate_channels_bps() {
+        let b = flac_streaminfo(44_100, 1_000, [0; 16]);
+        // 44100 = 0x0AC44 fills the top 20 bits; byte 12 ends with
+        // channels-1 (0b001) and the MSB of bps-1 (15 = 0b01111).
+        assert_eq!(&b[10..13], &[0x0A, 0xC4, 0x42]);
+        // Low four bps bits, then the top nibble of the sample count.
+        assert_eq!(b[13], 0xF0);
+        assert_eq!(&b[14..18], &1_000u32.to_be_bytes());
+    }
+
+    #[test]
+    fn short_final_frame_carries_explicit_block_size() {
+        let frame = flac_frame(&[(1, -1); 10], 3);
+        assert_eq!(&frame[..4], &[0xFF, 0xF8, 0x70, 0x18]);
+        assert_eq!(frame[4], 3);
+        assert_eq!(&frame[5..7], &9u16.to_be_bytes());
+        assert_eq!(frame[7], crc8(&frame[..7]));
+        // header(8) + 2 × (1 + 10×2) + crc16(2)
+        assert_eq!(frame.len(), 8 + 2 * 21 + 2);
+    }
+
+    #[test]
+    fn wav_header_layout() {
+        let h = wav_header(48_000, 400);
+        assert_eq!(&h[0..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes(h[4..8].try_into().unwrap()), 436);
+        assert_eq!(u32::from_le_bytes(h[24..28].try_into().unwrap()), 48_000);
+        assert_eq!(u32::from_le_bytes(h[28..32].try_into().unwrap()), 192_000);
+        assert_eq!(u32::from_le_bytes(h[40..44].try_into().unwrap()), 400);
+    }
+}
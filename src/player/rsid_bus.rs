@@ -1,7 +1,9 @@
 // RSID bus: wraps the cycle-accurate c64_emu::C64 core and intercepts
 // SID register writes so they can be forwarded to the USBSID hardware.
 //
-// Only used for RSID playback. PSID continues to use the simpler C64Memory.
+// Used for RSID playback, and for PSID playback when the cycle-exact
+// PSID option is on (see `install_psid_driver`). Otherwise PSID uses the
+// simpler C64Memory.
 
 use mos6502::memory::Bus;
 
@@ -10,6 +12,8 @@ use crate::c64_emu::c64::{C64CiaModel, C64Model, C64};
 use crate::c64_emu::cia::timer::{CIAT_COUNT2, CIAT_COUNT3, CIAT_CR_START, CIAT_PHI2IN};
 use crate::c64_emu::mmu::PageMapping;
 
+use crate::prg_export::{psid_irq_handler, PSID_IRQ_HANDLER_LEN};
+
 use super::hacks::HackFlags;
 use super::memory::{SidMapper, SidWrite, SID_REG_SIZE};
 
@@ -401,6 +405,15 @@ impl RsidBus {
         self.c64.cia1.write(0x0D, 0x81); // ICR: set Timer A mask
     }
 
+    /// PSID "CIA speed": the KERNAL's 60 Hz timer A latch (0x4025 PAL /
+    /// 0x4295 NTSC), as in `C64Memory::setup_rsid_defaults`. Call after
+    /// `setup_rsid_cia_defaults`, which starts the timer at one frame.
+    pub fn setup_psid_cia_latch(&mut self, is_pal: bool) {
+        let latch = if is_pal { 0x4025 } else { 0x4295 };
+        self.c64.cia1.timer_a.latch = latch;
+        self.c64.cia1.timer_a.counter = latch;
+    }
+
     /// Install trampoline at `at`: JSR target; JMP halt
     pub fn install_trampoline(&mut self, at: u16, target: u16) {
         let a = at as usize;
@@ -412,6 +425,24 @@ impl RsidBus {
         self.c64.ram.ram[a + 5] = ((at + 3) >> 8) as u8;
     }
 
+    /// Install a PSID driver at `at` — the job sidplay's psiddrv does on a
+    /// real machine. PLAY is called from a genuine IRQ, so the tune runs
+    /// with badlines and CIA timing like any RSID: a raster IRQ on line 0
+    /// for VBI tunes, CIA1 timer A (as set by `setup_rsid_cia_defaults`,
+    /// or reprogrammed by INIT) for CIA tunes. The IRQ vector points
+    /// straight at the driver in both ROM and RAM, so PLAY keeps running
+    /// when INIT banks the KERNAL out.
+    pub fn install_psid_driver(&mut self, at: u16, play: u16, vbi: bool) {
+        let a = at as usize;
+        self.c64.ram.ram[a..a + PSID_IRQ_HANDLER_LEN].copy_from_slice(&psid_irq_handler(at, play));
+        self.set_hw_vector(0xFFFE, at);
+        if vbi {
+            self.c64.cia1.write(0x0D, 0x01); // ICR: clear timer A mask
+            self.c64.set_byte(0xD012, 0x00); // raster compare line 0
+            self.c64.set_byte(0xD01A, 0x01); // enable raster IRQ
+        }
+    }
+
     /// Set a hardware vector in both RAM and KERNAL ROM overlay.
     pub fn set_hw_vector(&mut self, addr: u16, value: u16) {
         let lo = (value & 0xFF) as u8;
@@ -21,19 +21,36 @@ pub struct SidHeader {
     /// True when an RSID file contains a C64 BASIC program (flags bit 1 set).
     /// These tunes require a full KERNAL/BASIC boot before INIT is called.
     pub is_basic: bool,
-    /// SID chip model hint from flags[7:4] (0 = unknown/6581, 1 = 6581,
-    /// 2 = 8580, 3 = both).  Two nibbles: first SID in [7:4], second in [11:8].
+    /// SID1 chip model hint from flags[5:4] (0 = unknown/6581, 1 = 6581,
+    /// 2 = 8580, 3 = both). The clock in flags[3:2] applies to every chip.
     pub sid_model: u8,
-    /// C64 addresses of extra SIDs (0 = unused). Index 0 = SID2, 1 = SID3.
-    pub extra_sid_addrs: [u16; 2],
+    /// C64 addresses of extra SIDs (0 = unused). Index 0 = SID2, 1 = SID3,
+    /// 2 = SID4.
+    pub extra_sid_addrs: [u16; 3],
+    /// Model hints for the extra SIDs, same encoding as `sid_model`:
+    /// SID2 in flags[7:6], SID3 in [9:8], SID4 in [11:10]. 0 means
+    /// "same as SID1".
+    pub extra_sid_models: [u8; 3],
 }
 
 impl SidHeader {
-    /// Number of SID chips the tune uses (1–3 from header alone).
+    /// Number of SID chips the tune uses (1–4 from header alone).
     pub fn num_sids(&self) -> usize {
         1 + self.extra_sid_addrs.iter().filter(|&&a| a != 0).count()
     }
 
+    /// Model hint for chip `chip` (0 = SID1), falling back to SID1's
+    /// model when the header leaves an extra chip unspecified.
+    pub fn chip_model(&self, chip: usize) -> u8 {
+        match chip
+            .checked_sub(1)
+            .and_then(|i| self.extra_sid_models.get(i))
+        {
+            Some(&m) if m != 0 => m,
+            _ => self.sid_model,
+        }
+    }
+
     /// Frame rate in Hz.
     #[allow(dead_code)]
     pub fn frame_rate(&self) -> f64 {
@@ -93,6 +110,35 @@ fn decode_sid_addr_byte(b: u8) -> u16 {
 
 // ── Public API ───────────────────────────────────────────────────────────
 
+/// $01 value a PSID player selects before INIT: BASIC ROM out once the
+/// tune's data or its INIT/PLAY address reaches $A000, KERNAL out once
+/// it reaches $E000. I/O stays visible either way.
+pub fn psid_bank(sid: &SidFile) -> u8 {
+    let h = &sid.header;
+    let data_end = (sid.load_address as u32 + sid.payload.len() as u32).saturating_sub(1);
+    let top = data_end
+        .max(h.init_address as u32)
+        .max(h.play_address as u32);
+    if top < 0xA000 {
+        0x37
+    } else if top < 0xE000 {
+        0x36
+    } else {
+        0x35
+    }
+}
+
+/// Free memory a PSID v2+ header declares for a player driver
+/// (relocStartPage, relocPages). `None` when the header declares none
+/// or says there is no free page.
+pub fn reloc_pages(sid: &SidFile) -> Option<(u8, u8)> {
+    if sid.header.version < 2 || sid.raw.len() <= 0x79 {
+        return None;
+    }
+    let (page, count) = (sid.raw[0x78], sid.raw[0x79]);
+    (page != 0 && page != 0xFF && count > 0).then_some((page, count))
+}
+
 /// Returns true if the extension (lowercase, no dot) is a playable SID format.
 pub fn is_sid_extension(ext: &str) -> bool {
     matches!(ext, "sid" | "mus")
@@ -102,15 +148,19 @@ pub fn is_sid_extension(ext: &str) -> bool {
 /// (playlist metadata, MD5, libsidplayfp hand-off) has something to work with.
 /// The built-in CPU engine cannot play MUS — only libsidplayfp can.
 ///
-/// If `mus_path` is provided, checks for a companion `.str` file and sets
-/// `extra_sid_addrs[0]` to 0xD500 (stereo SID) when found.
+/// If `mus_path` is provided and a companion `.str` file sits next to it,
+/// the tune is stereo: `extra_sid_addrs[0]` is set to 0xD500 for the
+/// second SID and the `.str` data is appended to `raw`. MUS followed by
+/// STR in one buffer is how libsidplayfp takes a stereo tune from memory
+/// (and what the U64's player gets sent), and it's what HVSC hashes for
+/// the Songlength MD5.
 ///
 /// Extracts embedded PETSCII credit lines for title/author metadata.
 pub fn load_mus_stub(data: &[u8], mus_path: Option<&std::path::Path>) -> SidFile {
-    // Check for companion .str file → stereo.
-    let has_str = mus_path
-        .map(|p| p.with_extension("str").exists() || p.with_extension("STR").exists())
-        .unwrap_or(false);
+    let str_data = mus_path
+        .and_then(mus_companion_str)
+        .and_then(|p| std::fs::read(p).ok());
+    let has_str = str_data.is_some();
 
     // Use filename as title and parent directory as author.
     // PETSCII art credits are too garbled for reliable metadata extraction.
@@ -142,14 +192,27 @@ pub fn load_mus_stub(data: &[u8], mus_path: Option<&std::path::Path>) -> SidFile
             is_rsid: false,
             is_basic: false,
             sid_model: 0,
-            extra_sid_addrs: if has_str { [0xD500, 0] } else { [0; 2] },
+            extra_sid_addrs: if has_str { [0xD500, 0, 0] } else { [0; 3] },
+            extra_sid_models: [0; 3],
         },
         load_address: 0,
         payload: Vec::new(),
-        raw: data.to_vec(),
+        raw: match str_data {
+            Some(str_data) => [data, &str_data].concat(),
+            None => data.to_vec(),
+        },
     }
 }
 
+/// The stereo `.str` companion of a `.mus` file (voices 4–6, played on
+/// a second SID), if one sits next to it.
+pub fn mus_companion_str(mus_path: &std::path::Path) -> Option<std::path::PathBuf> {
+    ["str", "STR"]
+        .iter()
+        .map(|ext| mus_path.with_extension(ext))
+        .find(|p| p.is_file())
+}
+
 /// Parse a SID file from raw bytes.
 pub fn load_sid(data: &[u8]) -> Result<SidFile, String> {
     let header = parse_header(data)?;
@@ -196,21 +259,34 @@ pub fn parse_header(data: &[u8]) -> Result<SidHeader, String> {
     let mut is_pal = true;
     let mut is_basic = false;
     let mut sid_model: u8 = 0;
-    let mut extra_sid_addrs = [0u16; 2];
+    let mut extra_sid_addrs = [0u16; 3];
+    let mut extra_sid_models = [0u8; 3];
 
     if version >= 2 && data.len() >= 0x7C {
         let flags = read_be_u16(data, 0x76);
         is_pal = ((flags >> 2) & 0x03) != 2;
         // RSID BASIC flag: bit 1 of flags (version 2+, RSID only)
         is_basic = is_rsid && (flags & 0x02) != 0;
-        // SID model hint: bits [7:4] for SID1, bits [11:8] for SID2
+        // SID model hints: two bits per chip, SID1 at [5:4], SID2 at [7:6],
+        // SID3 at [9:8], SID4 at [11:10].
         sid_model = ((flags >> 4) & 0x03) as u8;
+        let model_at = |chip: u16| ((flags >> (4 + 2 * chip)) & 0x03) as u8;
 
         if version >= 3 && data.len() > 0x7A {
             extra_sid_addrs[0] = decode_sid_addr_byte(data[0x7A]);
+            extra_sid_models[0] = model_at(1);
         }
         if version >= 4 && data.len() > 0x7B {
             extra_sid_addrs[1] = decode_sid_addr_byte(data[0x7B]);
+            extra_sid_models[1] = model_at(2);
+        }
+        // 4SID extension: a v4 header with a data offset past 0x7C carries
+        // the SID4 address in the spare byte at 0x7C. Plain v4 files put
+        // payload there, so the data offset is what tells them apart.
+        let data_offset = read_be_u16(data, 0x06);
+        if version >= 4 && data_offset > 0x7C && data.len() > 0x7C {
+            extra_sid_addrs[2] = decode_sid_addr_byte(data[0x7C]);
+            extra_sid_models[2] = model_at(3);
         }
     }
 
@@ -232,6 +308,7 @@ pub fn parse_header(data: &[u8]) -> Result<SidHeader, String> {
         is_basic,
         sid_model,
         extra_sid_addrs,
+        extra_sid_models,
     })
 }
 
@@ -243,6 +320,20 @@ pub fn compute_hvsc_md5(sid: &SidFile) -> String {
     format!("{:x}", md5::compute(&sid.raw))
 }
 
+/// The raw file bytes with the clock flags (flags[3:2]) rewritten to
+/// match `sid.header.is_pal`, for engines that read the clock from the
+/// file themselves (libsidplayfp, U64 native playback). `None` when the
+/// file already agrees with the header, or has no flags word (v1).
+pub fn clock_patched_raw(sid: &SidFile) -> Option<Vec<u8>> {
+    let file = parse_header(&sid.raw).ok()?;
+    if file.is_pal == sid.header.is_pal || file.version < 2 || sid.raw.len() < 0x7C {
+        return None;
+    }
+    let mut raw = sid.raw.clone();
+    raw[0x77] = (raw[0x77] & !0x0C) | if sid.header.is_pal { 0x04 } else { 0x08 };
+    Some(raw)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -290,4 +381,103 @@ mod tests {
         let h = parse_header(&synth_psid(0b0000_0000_0011_0000)).unwrap();
         assert_eq!(h.sid_model, 3);
     }
+
+    /// v4 header with an extra byte before the payload, SID2–SID4 at
+    /// $D420/$D440/$D460 and per-chip models 8580/6581/8580 after a
+    /// 6581 SID1.
+    fn synth_4sid() -> Vec<u8> {
+        let mut buf = synth_psid(0b0000_1001_1001_0000);
+        buf[5] = 0x04;
+        buf[7] = 0x7E;
+        buf[0x7A] = 0x42;
+        buf[0x7B] = 0x44;
+        buf.extend([0x46, 0x00]);
+        buf
+    }
+
+    #[test]
+    fn v4_extension_byte_adds_fourth_sid() {
+        let h = parse_header(&synth_4sid()).unwrap();
+        assert_eq!(h.extra_sid_addrs, [0xD420, 0xD440, 0xD460]);
+        assert_eq!(h.num_sids(), 4);
+        assert_eq!(h.extra_sid_models, [2, 1, 2]);
+        assert_eq!(h.chip_model(0), 1);
+        assert_eq!(h.chip_model(3), 2);
+    }
+
+    #[test]
+    fn plain_v4_ignores_payload_byte() {
+        // Data offset 0x7C: the byte at 0x7C is payload, not a SID4 address.
+        let mut buf = synth_4sid();
+        buf[7] = 0x7C;
+        let h = parse_header(&buf).unwrap();
+        assert_eq!(h.extra_sid_addrs, [0xD420, 0xD440, 0]);
+        assert_eq!(h.num_sids(), 3);
+    }
+
+    #[test]
+    fn unset_chip_model_follows_sid1() {
+        let mut buf = synth_psid(0b0000_0000_0010_0000);
+        buf[5] = 0x03;
+        buf[0x7A] = 0x50;
+        let h = parse_header(&buf).unwrap();
+        assert_eq!(h.extra_sid_addrs, [0xD500, 0, 0]);
+        assert_eq!(h.chip_model(1), 2);
+    }
+
+    #[test]
+    fn psid_bank_follows_data_and_entry_points() {
+        let mut buf = synth_psid(0);
+        buf[0x0A] = 0x10; // init $1000
+        buf[0x0C] = 0x10; // play $1003
+        buf[0x0D] = 0x03;
+        buf.extend([0x00, 0x10, 0x60]); // load $1000
+        let mut sid = load_sid(&buf).unwrap();
+        assert_eq!(psid_bank(&sid), 0x37);
+        sid.header.play_address = 0xB000;
+        assert_eq!(psid_bank(&sid), 0x36);
+        sid.load_address = 0xE000;
+        assert_eq!(psid_bank(&sid), 0x35);
+    }
+
+    #[test]
+    fn clock_patch_rewrites_only_clock_bits() {
+        // 6581, PAL.
+        let raw = synth_psid(0b0000_0000_0001_0100);
+        let mut sid = SidFile {
+            header: parse_header(&raw).unwrap(),
+            load_address: 0,
+            payload: Vec::new(),
+            raw,
+        };
+        assert!(clock_patched_raw(&sid).is_none());
+
+        sid.header.is_pal = false;
+        let patched = clock_patched_raw(&sid).unwrap();
+        assert_eq!(patched[0x77], 0b0001_1000);
+        let h = parse_header(&patched).unwrap();
+        assert!(!h.is_pal);
+        assert_eq!(h.sid_model, 1);
+    }
+
+    #[test]
+    fn mus_with_str_companion_is_stereo() {
+        let dir = std::env::temp_dir().join(format!("phosphor_mus_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mus = dir.join("Tune.mus");
+        std::fs::write(&mus, [0x00, 0x00, 0x01]).unwrap();
+
+        let mono = load_mus_stub(&[0x00, 0x00, 0x01], Some(&mus));
+        assert_eq!(mono.header.extra_sid_addrs, [0; 3]);
+        assert_eq!(mono.header.num_sids(), 1);
+        assert_eq!(mono.raw, [0x00, 0x00, 0x01]);
+
+        std::fs::write(dir.join("Tune.str"), [0x00, 0x00, 0x02]).unwrap();
+        let stereo = load_mus_stub(&[0x00, 0x00, 0x01], Some(&mus));
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(stereo.header.extra_sid_addrs, [0xD500, 0, 0]);
+        // Two chips, so setup_playback reports six voices.
+        assert_eq!(stereo.header.num_sids(), 2);
+        assert_eq!(stereo.raw, [0x00, 0x00, 0x01, 0x00, 0x00, 0x02]);
+    }
 }
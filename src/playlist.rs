@@ -23,6 +23,9 @@ pub struct PlaylistEntry {
     /// Which sub-tune to play (1-based).
     pub selected_song: u16,
     pub is_pal: bool,
+    /// PAL/NTSC override saved in the M3U (`#PHOSPHOR:clock=`):
+    /// Some(true) forces PAL, Some(false) NTSC, None follows `is_pal`.
+    pub force_pal: Option<bool>,
     pub num_sids: usize,
     /// True if RSID, false if PSID.
     pub is_rsid: bool,
@@ -30,6 +33,9 @@ pub struct PlaylistEntry {
     pub md5: Option<String>,
     /// Duration from Songlength DB, if available (seconds).
     pub duration_secs: Option<u32>,
+    /// True when `duration_secs` is the configured default length
+    /// standing in for an unknown one (see `set_duration`).
+    pub duration_is_default: bool,
     /// True if a companion .wds lyrics file exists (karaoke available).
     pub has_wds: bool,
 }
@@ -54,19 +60,21 @@ impl PlaylistEntry {
 
         // Opt-in diagnostic to debug "Library-loaded SIDs show wrong
         // num_sids" reports. Enable with `PHOSPHOR_DEBUG_NUMSIDS=1`.
-        // Logs the file's PSID-version + raw bytes 0x7A/0x7B (SID2/3
+        // Logs the file's PSID-version + raw bytes 0x7A/0x7B/0x7C (SID2-4
         // address fields) + the parsed `num_sids` so we can compare
         // the same file's reading across different load paths.
         if std::env::var("PHOSPHOR_DEBUG_NUMSIDS").is_ok() {
             let b7a = sid.raw.get(0x7A).copied().unwrap_or(0);
             let b7b = sid.raw.get(0x7B).copied().unwrap_or(0);
+            let b7c = sid.raw.get(0x7C).copied().unwrap_or(0);
             eprintln!(
-                "[NUMSIDS] {}: v={} num_sids={} b[0x7A]=0x{:02X} b[0x7B]=0x{:02X} extra={:?}",
+                "[NUMSIDS] {}: v={} num_sids={} b[0x7A]=0x{:02X} b[0x7B]=0x{:02X} b[0x7C]=0x{:02X} extra={:?}",
                 path.display(),
                 h.version,
                 h.num_sids(),
                 b7a,
                 b7b,
+                b7c,
                 h.extra_sid_addrs,
             );
         }
@@ -85,10 +93,12 @@ impl PlaylistEntry {
             songs: h.songs,
             selected_song: h.start_song,
             is_pal: h.is_pal,
+            force_pal: None,
             num_sids: h.num_sids(),
             is_rsid: h.is_rsid,
             md5: Some(md5),
             duration_secs: None, // MUS duration handled by silence detection
+            duration_is_default: false,
             has_wds: if is_mus {
                 let wds = path.with_extension("wds");
                 let wds_upper = path.with_extension("WDS");
@@ -127,10 +137,12 @@ impl PlaylistEntry {
             songs: songs.unwrap_or(1),
             selected_song: selected_song.unwrap_or(1),
             is_pal: is_pal.unwrap_or(true),
+            force_pal: None,
             num_sids: num_sids.unwrap_or(1),
             is_rsid: is_rsid.unwrap_or(false),
             md5: md5.map(|s| s.to_string()),
             duration_secs,
+            duration_is_default: false,
             has_wds: {
                 let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
                 if ext.eq_ignore_ascii_case("mus") {
@@ -142,6 +154,13 @@ impl PlaylistEntry {
         })
     }
 
+    /// Set the duration from a length lookup, falling back to the
+    /// configured `default` (0 = none) and flagging it as such.
+    pub fn set_duration(&mut self, known: Option<u32>, default: u32) {
+        self.duration_is_default = known.is_none() && default > 0;
+        self.duration_secs = known.or((default > 0).then_some(default));
+    }
+
     pub fn format_duration(&self) -> String {
         match self.duration_secs {
             Some(s) => format!("{}:{:02}", s / 60, s % 60),
@@ -366,11 +385,12 @@ impl Playlist {
     /// #EXTM3U
     /// #EXTINF:123,Artist - Title
     /// #PHOSPHOR:song=2
+    /// #PHOSPHOR:clock=ntsc
     /// /absolute/path/to/file.sid
     /// ```
     ///
-    /// The `#PHOSPHOR:` line is optional metadata that preserves the
-    /// selected sub-tune. Standard M3U players ignore unknown `#` lines.
+    /// The `#PHOSPHOR:` lines are optional metadata that preserve the
+    /// selected sub-tune and any PAL/NTSC override. Standard M3U players ignore unknown `#` lines.
     /// Duration from `#EXTINF` is restored on load, so the Songlength DB
     /// doesn't need to be re-scanned for known tunes.
     #[allow(dead_code)]
@@ -383,7 +403,12 @@ impl Playlist {
         writeln!(f, "#EXTM3U").map_err(|e| format!("Write error: {e}"))?;
 
         for entry in &self.entries {
-            let duration = entry.duration_secs.unwrap_or(0) as i64;
+            // A default length isn't the tune's; leave it to be
+            // re-applied (or replaced by an estimate) on load.
+            let duration = entry
+                .duration_secs
+                .filter(|_| !entry.duration_is_default)
+                .unwrap_or(0) as i64;
             let display = if entry.author.is_empty() {
                 entry.title.clone()
             } else {
@@ -409,6 +434,10 @@ impl Playlist {
                     writeln!(f, "#PHOSPHOR:released={}", entry.released)
                         .map_err(|e| format!("Write error: {e}"))?;
                 }
+                if let Some(pal) = entry.force_pal {
+                    writeln!(f, "#PHOSPHOR:clock={}", if pal { "pal" } else { "ntsc" })
+                        .map_err(|e| format!("Write error: {e}"))?;
+                }
             }
             writeln!(f, "{}", entry.path.display()).map_err(|e| format!("Write error: {e}"))?;
         }
@@ -551,6 +580,7 @@ impl Playlist {
                                 entry.selected_song = song;
                             }
                         }
+                        entry.force_pal = item.force_pal;
                     }
                     loaded += 1;
                 } else {
@@ -700,6 +730,13 @@ pub struct SonglengthDb {
     /// `by_path`. Used by `FavoritesDb::resolve()` to heal favourite
     /// entries whose stored absolute path has gone stale.
     pub md5_to_path: HashMap<String, String>,
+    /// User-local lengths for tunes HVSC doesn't know, estimated by
+    /// `player::length_estimate` and persisted next to the config as
+    /// `Songlengths.local.md5`. One `(length, loop)` pair in seconds per
+    /// subtune; a 0 length marks a subtune that was analysed without
+    /// finding a loop, a 0 loop one that ends instead of looping.
+    /// Consulted only after every HVSC lookup misses.
+    pub overlay: HashMap<String, Vec<(u32, u32)>>,
 }
 
 impl SonglengthDb {
@@ -708,9 +745,18 @@ impl SonglengthDb {
             entries: HashMap::new(),
             by_path: HashMap::new(),
             md5_to_path: HashMap::new(),
+            overlay: HashMap::new(),
         }
     }
 
+    /// An empty HVSC DB carrying just the local overlay — used when no
+    /// Songlengths.md5 is available yet.
+    pub fn overlay_only() -> Self {
+        let mut db = Self::new();
+        db.overlay = load_overlay();
+        db
+    }
+
     /// Try to auto-load from the standard config directory.
     /// Looks in: <config_dir>/ultimate64-manager/Songlengths.md5
     /// (Same path as the ultimate64-manager player)
@@ -814,16 +860,46 @@ impl SonglengthDb {
             }
         }
 
+        db.overlay = load_overlay();
         Ok(db)
     }
 
     /// Look up duration for a specific MD5 and sub-tune (0-based).
+    /// HVSC first, then the local overlay.
     pub fn lookup(&self, md5: &str, subtune: usize) -> Option<u32> {
+        self.lookup_hvsc(md5, subtune)
+            .or_else(|| self.lookup_overlay(md5, subtune))
+    }
+
+    fn lookup_hvsc(&self, md5: &str, subtune: usize) -> Option<u32> {
         self.entries
             .get(&md5.to_lowercase())
             .and_then(|v| v.get(subtune).copied())
     }
 
+    fn lookup_overlay(&self, md5: &str, subtune: usize) -> Option<u32> {
+        self.overlay
+            .get(&md5.to_lowercase())
+            .and_then(|v| v.get(subtune))
+            .map(|&(length, _)| length)
+            .filter(|&d| d > 0)
+    }
+
+    /// True once a tune has been analysed, whether or not a length was
+    /// found — keeps it from being queued again.
+    pub fn has_overlay(&self, md5: &str) -> bool {
+        self.overlay.contains_key(&md5.to_lowercase())
+    }
+
+    /// Record estimated `(length, loop)` pairs for a tune and write the
+    /// overlay back.
+    pub fn set_overlay(&mut self, md5: &str, lengths: Vec<(u32, u32)>) {
+        self.overlay.insert(md5.to_lowercase(), lengths);
+        if let Err(e) = save_overlay(&self.overlay) {
+            eprintln!("[phosphor] Failed to save songlength overlay: {e}");
+        }
+    }
+
     /// Look up all subtune durations for a given MD5.
     #[allow(dead_code)]
     pub fn lookup_all(&self, md5: &str) -> Option<&Vec<u32>> {
@@ -843,7 +919,8 @@ impl SonglengthDb {
     }
 
     /// md5 lookup first, then fall back to path-based lookup if md5 misses
-    /// and the entry's absolute path lives under `hvsc_root`.
+    /// and the entry's absolute path lives under `hvsc_root`, then the
+    /// local overlay.
     pub fn lookup_with_path_fallback(
         &self,
         md5: Option<&str>,
@@ -851,11 +928,17 @@ impl SonglengthDb {
         hvsc_root: Option<&Path>,
         subtune: usize,
     ) -> Option<u32> {
-        if let Some(m) = md5 {
-            if let Some(d) = self.lookup(m, subtune) {
-                return Some(d);
-            }
-        }
+        md5.and_then(|m| self.lookup_hvsc(m, subtune))
+            .or_else(|| self.lookup_hvsc_path(abs_path, hvsc_root, subtune))
+            .or_else(|| md5.and_then(|m| self.lookup_overlay(m, subtune)))
+    }
+
+    fn lookup_hvsc_path(
+        &self,
+        abs_path: &Path,
+        hvsc_root: Option<&Path>,
+        subtune: usize,
+    ) -> Option<u32> {
         let root = hvsc_root?;
         let rel = abs_path.strip_prefix(root).ok()?;
         let rel_str = rel.to_string_lossy();
@@ -870,6 +953,7 @@ impl SonglengthDb {
     pub fn apply_to_playlist(&self, playlist: &mut Playlist, hvsc_root: Option<&Path>) {
         let mut applied = 0;
         let mut applied_by_path = 0;
+        let mut applied_from_overlay = 0;
         let mut skipped = 0;
         for entry in &mut playlist.entries {
             // Don't overwrite durations already loaded from the playlist file
@@ -879,21 +963,15 @@ impl SonglengthDb {
             }
             let subtune = entry.selected_song.saturating_sub(1) as usize;
             let md5_str = entry.md5.as_deref();
-            let mut md5_hit = false;
-            if let Some(m) = md5_str {
-                if let Some(dur) = self.lookup(m, subtune) {
-                    entry.duration_secs = Some(dur);
-                    applied += 1;
-                    md5_hit = true;
-                }
-            }
-            if !md5_hit {
-                if let Some(dur) =
-                    self.lookup_with_path_fallback(md5_str, &entry.path, hvsc_root, subtune)
-                {
-                    entry.duration_secs = Some(dur);
-                    applied_by_path += 1;
-                }
+            if let Some(dur) = md5_str.and_then(|m| self.lookup_hvsc(m, subtune)) {
+                entry.duration_secs = Some(dur);
+                applied += 1;
+            } else if let Some(dur) = self.lookup_hvsc_path(&entry.path, hvsc_root, subtune) {
+                entry.duration_secs = Some(dur);
+                applied_by_path += 1;
+            } else if let Some(dur) = md5_str.and_then(|m| self.lookup_overlay(m, subtune)) {
+                entry.duration_secs = Some(dur);
+                applied_from_overlay += 1;
             }
         }
         if applied_by_path > 0 {
@@ -901,7 +979,12 @@ impl SonglengthDb {
                 "[phosphor] Songlengths: {applied_by_path} entries matched by HVSC-relative path (local SID bytes differ from DB)"
             );
         }
-        let total_applied = applied + applied_by_path;
+        if applied_from_overlay > 0 {
+            eprintln!(
+                "[phosphor] Songlengths: {applied_from_overlay} entries from the local estimate overlay"
+            );
+        }
+        let total_applied = applied + applied_by_path + applied_from_overlay;
         if total_applied > 0 || skipped > 0 {
             eprintln!(
                 "[phosphor] Songlengths: applied={total_applied}, already_known={skipped}, total={}",
@@ -911,6 +994,72 @@ impl SonglengthDb {
     }
 }
 
+/// Read the local overlay. Same line format as Songlengths.md5
+/// (`md5=m:ss m:ss ...`) but without the +1 s HVSC padding, since the
+/// estimates are ours. A looping subtune carries its loop length as an
+/// attribute, `m:ss(Lm:ss)`, which HVSC-style parsers skip.
+fn load_overlay() -> HashMap<String, Vec<(u32, u32)>> {
+    let Some(path) = config::songlength_overlay_path() else {
+        return HashMap::new();
+    };
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return HashMap::new();
+    };
+    parse_overlay(&content)
+}
+
+fn parse_overlay(content: &str) -> HashMap<String, Vec<(u32, u32)>> {
+    let loop_secs = |t: &str| {
+        t.split_once("(L")
+            .and_then(|(_, rest)| parse_songlength_time(rest.trim_end_matches(')')))
+            .unwrap_or(0)
+    };
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.starts_with(';') && !l.starts_with('['))
+        .filter_map(|l| l.split_once('='))
+        .filter(|(md5, _)| md5.trim().len() == 32)
+        .map(|(md5, times)| {
+            let lengths = times
+                .split_whitespace()
+                .filter_map(|t| Some((parse_songlength_time(t)?, loop_secs(t))))
+                .collect();
+            (md5.trim().to_lowercase(), lengths)
+        })
+        .collect()
+}
+
+fn save_overlay(overlay: &HashMap<String, Vec<(u32, u32)>>) -> Result<(), String> {
+    let path = config::songlength_overlay_path()
+        .ok_or_else(|| "Cannot determine config directory".to_string())?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Cannot create {}: {e}", dir.display()))?;
+    }
+    std::fs::write(&path, format_overlay(overlay))
+        .map_err(|e| format!("Cannot write {}: {e}", path.display()))
+}
+
+fn format_overlay(overlay: &HashMap<String, Vec<(u32, u32)>>) -> String {
+    let mmss = |s: u32| format!("{}:{:02}", s / 60, s % 60);
+    let mut keys: Vec<&String> = overlay.keys().collect();
+    keys.sort();
+    let mut out =
+        String::from("; Phosphor estimated song lengths for tunes not in HVSC\n[Database]\n");
+    for md5 in keys {
+        let times: Vec<String> = overlay[md5]
+            .iter()
+            .map(|&(length, lp)| match lp {
+                0 => mmss(length),
+                _ => format!("{}(L{})", mmss(length), mmss(lp)),
+            })
+            .collect();
+        out.push_str(&format!("{md5}={}\n", times.join(" ")));
+    }
+    out
+}
+
 /// Parse "mm:ss", "mm:ss.xxx", or "mm:ss(G)" into whole seconds.
 fn parse_songlength_time(s: &str) -> Option<u32> {
     let s = s.trim();
@@ -944,6 +1093,8 @@ struct M3uMeta {
     num_sids: Option<usize>,
     is_rsid: Option<bool>,
     md5: Option<String>,
+    /// `#PHOSPHOR:clock=pal|ntsc` override.
+    force_pal: Option<bool>,
 }
 
 fn parse_m3u(content: &str, base_dir: &Path) -> Vec<M3uMeta> {
@@ -958,6 +1109,7 @@ fn parse_m3u(content: &str, base_dir: &Path) -> Vec<M3uMeta> {
     let mut pending_sids: Option<usize> = None;
     let mut pending_rsid: Option<bool> = None;
     let mut pending_md5: Option<String> = None;
+    let mut pending_clock: Option<bool> = None;
 
     for line in content.lines() {
         let line = line.trim();
@@ -1005,6 +1157,8 @@ fn parse_m3u(content: &str, base_dir: &Path) -> Vec<M3uMeta> {
                     }
                 } else if let Some(val) = part.strip_prefix("released=") {
                     pending_released = Some(val.to_string());
+                } else if let Some(val) = part.strip_prefix("clock=") {
+                    pending_clock = parse_clock(val);
                 }
             }
             continue;
@@ -1031,12 +1185,22 @@ fn parse_m3u(content: &str, base_dir: &Path) -> Vec<M3uMeta> {
             num_sids: pending_sids.take(),
             is_rsid: pending_rsid.take(),
             md5: pending_md5.take(),
+            force_pal: pending_clock.take(),
         });
     }
 
     results
 }
 
+/// `pal` / `ntsc` from a `#PHOSPHOR:clock=` directive.
+fn parse_clock(val: &str) -> Option<bool> {
+    match val.to_ascii_lowercase().as_str() {
+        "pal" => Some(true),
+        "ntsc" => Some(false),
+        _ => None,
+    }
+}
+
 /// One row in the Published Playlists preview list. Lightweight — does
 /// NOT read any SID files from disk, so a 100-track playlist parses
 /// in microseconds. Used only to render the inline ▾ preview in the
@@ -1309,6 +1473,10 @@ pub fn parse_playlist_file_with_base(
                         item.md5.as_deref(),
                         item.duration_secs,
                     )
+                    .map(|mut e| {
+                        e.force_pal = item.force_pal;
+                        e
+                    })
                 } else {
                     // No cache — read SID file the slow way.
                     PlaylistEntry::from_path(&item.path).map(|mut e| {
@@ -1320,6 +1488,7 @@ pub fn parse_playlist_file_with_base(
                                 e.selected_song = song;
                             }
                         }
+                        e.force_pal = item.force_pal;
                         e
                     })
                 };
@@ -1385,10 +1554,12 @@ pub fn parse_playlist_skeleton_with_base(
             songs: item.songs.unwrap_or(1),
             selected_song: item.selected_song.unwrap_or(1),
             is_pal: item.is_pal.unwrap_or(true),
+            force_pal: item.force_pal,
             num_sids: item.num_sids.unwrap_or(1),
             is_rsid: item.is_rsid.unwrap_or(false),
             md5: item.md5,
             duration_secs: item.duration_secs,
+            duration_is_default: false,
             has_wds: false,
         });
     }
@@ -1397,8 +1568,9 @@ pub fn parse_playlist_skeleton_with_base(
 
 /// Background enrichment: for each entry that's missing md5 (the
 /// skeleton ones), open the SID on disk, parse the header, compute the
-/// HVSC md5, and merge the real metadata back. EXTINF duration + the
-/// `#PHOSPHOR:song=N` sub-tune override from the M3U survive the merge.
+/// HVSC md5, and merge the real metadata back. EXTINF duration, the
+/// `#PHOSPHOR:song=N` sub-tune override and the `#PHOSPHOR:clock=`
+/// override from the M3U survive the merge.
 ///
 /// Missing files / parse failures leave the skeleton entry alone — the
 /// user still sees the row and can investigate; the player will raise
@@ -1420,6 +1592,7 @@ pub fn enrich_skeleton_entries(entries: Vec<PlaylistEntry>) -> Vec<PlaylistEntry
                         real.selected_song
                     },
                     duration_secs: skel.duration_secs.or(real.duration_secs),
+                    force_pal: skel.force_pal,
                     ..real
                 },
                 Err(_) => skel,
@@ -1479,3 +1652,76 @@ pub fn parse_startup(cli_args: Vec<PathBuf>, progress: LoadingProgress) -> Vec<P
 
     entries
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, force_pal: Option<bool>) -> PlaylistEntry {
+        PlaylistEntry {
+            path: PathBuf::from(path),
+            title: "Title".to_string(),
+            author: "Author".to_string(),
+            released: String::new(),
+            songs: 3,
+            selected_song: 2,
+            is_pal: true,
+            force_pal,
+            num_sids: 1,
+            is_rsid: false,
+            md5: Some("0123456789abcdef0123456789abcdef".to_string()),
+            duration_secs: Some(90),
+            duration_is_default: false,
+            has_wds: false,
+        }
+    }
+
+    #[test]
+    fn clock_override_survives_m3u_roundtrip() {
+        let mut pl = Playlist::new();
+        pl.entries = vec![
+            entry("/tunes/a.sid", Some(false)),
+            entry("/tunes/b.sid", None),
+            entry("/tunes/c.sid", Some(true)),
+        ];
+        let path = std::env::temp_dir().join(format!("phosphor_clock_{}.m3u", std::process::id()));
+        pl.save_m3u(&path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert!(content.contains("#PHOSPHOR:clock=ntsc\n"));
+        let items = parse_m3u(&content, Path::new("/"));
+        let clocks: Vec<_> = items.iter().map(|i| i.force_pal).collect();
+        assert_eq!(clocks, [Some(false), None, Some(true)]);
+        assert_eq!(items[0].selected_song, Some(2));
+    }
+
+    #[test]
+    fn overlay_keeps_loop_lengths() {
+        let md5 = "0123456789abcdef0123456789abcdef".to_string();
+        let overlay = HashMap::from([(md5.clone(), vec![(192, 100), (75, 0), (0, 0)])]);
+        let text = format_overlay(&overlay);
+        assert!(text.contains("=3:12(L1:40) 1:15 0:00\n"));
+        assert_eq!(parse_overlay(&text), overlay);
+        // Older overlays without loop attributes still load.
+        let old = parse_overlay(&format!("{md5}=3:12 1:15"));
+        assert_eq!(old[&md5], [(192, 0), (75, 0)]);
+    }
+
+    #[test]
+    fn default_length_is_not_saved_as_the_tune_length() {
+        let mut pl = Playlist::new();
+        let mut e = entry("/tunes/a.sid", None);
+        e.set_duration(None, 180);
+        assert_eq!((e.duration_secs, e.duration_is_default), (Some(180), true));
+        pl.entries = vec![e, entry("/tunes/b.sid", None)];
+        let path = std::env::temp_dir().join(format!("phosphor_dflt_{}.m3u", std::process::id()));
+        pl.save_m3u(&path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let items = parse_m3u(&content, Path::new("/"));
+        let durations: Vec<_> = items.iter().map(|i| i.duration_secs).collect();
+        assert_eq!(durations, [None, Some(90)]);
+    }
+}
@@ -0,0 +1,646 @@
+// Standalone C64 PRG export for PSID / RSID tunes.
+//
+// The PRG loads at $0801 with a `SYS 2061` BASIC line, so it runs with a
+// plain LOAD"*",8,1 / RUN on a real C64 or from a 1541 Ultimate SD card.
+// Layout after the BASIC line:
+//
+//   boot code   — clears the screen, prints title / author / released,
+//                 copies the driver to its free-memory slot, jumps to it
+//   text        — the info lines, already in screen codes
+//   driver      — assembled for the slot it will run from
+//   tune        — the SID payload as-is
+//
+// The driver copies the payload to its load address (with all RAM
+// banked in, so $D000–$DFFF loads land in RAM), sets up a raster IRQ
+// (VBI tunes) or a 60 Hz CIA 1 timer IRQ (CIA tunes), calls INIT with
+// the subtune in A and then idles while the IRQ calls PLAY. Tunes with
+// no PLAY address (RSID, and PSIDs that install their own handler) are
+// just started via INIT.
+//
+// The driver slot is the tune's declared free area when the header has
+// one (PSID v2+ relocation pages), otherwise the first page that clears
+// the load range, the PRG image itself and the ROM / I/O areas.
+
+use crate::player::sid_file::{psid_bank, reloc_pages, SidFile};
+
+const LOAD_ADDRESS: u16 = 0x0801;
+/// `SYS` target: first byte after the BASIC line.
+const BOOT_ADDRESS: u16 = 0x080D;
+/// Lowest load address the PSID spec allows (just past screen RAM).
+const MIN_TUNE_ADDRESS: u16 = 0x07E8;
+/// LOAD writes through the I/O area above this, so the image must end here.
+const MAX_PRG_END: u32 = 0xD000;
+/// Screen row of the first info line.
+const TEXT_ROW: u16 = 2;
+const SCREEN_COLS: usize = 40;
+
+/// Zero-page copy pointers — only used before INIT.
+const ZP_SRC: u8 = 0xFB;
+const ZP_DST: u8 = 0xFD;
+
+/// KERNAL clear-screen routine and IRQ exit (restore A/X/Y, RTI).
+const KERNAL_CLEAR_SCREEN: u16 = 0xE544;
+const KERNAL_IRQ_EXIT: u16 = 0xEA81;
+/// CIA 1 timer A latch for a 60 Hz IRQ on PAL / NTSC machines.
+const CIA_60HZ_PAL: u16 = 0x4025;
+const CIA_60HZ_NTSC: u16 = 0x4295;
+
+/// Build a runnable PRG that plays `song` (1-based) of `sid`.
+pub fn build(sid: &SidFile, song: u16) -> Result<Vec<u8>, String> {
+    let h = &sid.header;
+    if h.magic != "PSID" && h.magic != "RSID" {
+        return Err("Only PSID / RSID files can be exported as PRG".into());
+    }
+    if h.is_basic {
+        return Err("BASIC RSID tunes cannot be exported as PRG".into());
+    }
+    if sid.payload.is_empty() {
+        return Err("Tune has no data".into());
+    }
+    let load = sid.load_address;
+    if load < MIN_TUNE_ADDRESS {
+        return Err(format!("Load address ${load:04X} is below $07E8"));
+    }
+    let load_end = load as u32 + sid.payload.len() as u32;
+    if load_end > 0x10000 {
+        return Err("Tune data runs past $FFFF".into());
+    }
+
+    let songs = h.songs.max(1);
+    let song = song.clamp(1, songs);
+    let init = if h.init_address == 0 {
+        load
+    } else {
+        h.init_address
+    };
+    // Same banking a PSID player sets up (see `psid_bank`).
+    let bank: u8 = if h.is_rsid { 0x37 } else { psid_bank(sid) };
+    if bank == 0x35 && h.play_address != 0 && load_end > 0xFFFA {
+        return Err("Tune overlaps the IRQ vectors at $FFFA-$FFFF".into());
+    }
+    let speed_bit = (song - 1).min(31);
+    let irq = match h.play_address {
+        _ if h.is_rsid => None,
+        0 => None,
+        play => Some(Irq {
+            play,
+            cia_timer: (h.speed >> speed_bit) & 1 != 0,
+            is_pal: h.is_pal,
+            hardware_vector: bank == 0x35,
+        }),
+    };
+
+    let text = info_text(sid, song, songs);
+    let driver_params = DriverParams {
+        load,
+        len: sid.payload.len() as u32,
+        bank,
+        init,
+        song,
+        irq,
+    };
+
+    // The boot code's size doesn't depend on addresses, so a dry run
+    // gives the layout. The driver's copy loop does depend on where the
+    // tune sits in the image, which depends on the driver's size — grow
+    // the reserved size until the driver fits (shorter code is padded).
+    let boot_len = boot_code(0, 0, 0, 0, 0).len() as u16;
+    let text_addr = BOOT_ADDRESS + boot_len;
+    let driver_src = text_addr + text.len() as u16;
+    let mut driver_len = 0u16;
+    loop {
+        let len = driver_code(0, driver_src + driver_len, &driver_params)?.len() as u16;
+        if len <= driver_len {
+            break;
+        }
+        driver_len = len;
+    }
+    let tune_src = driver_src + driver_len;
+    let prg_end = tune_src as u32 + sid.payload.len() as u32;
+    if prg_end > MAX_PRG_END {
+        return Err("Tune is too large to fit in a PRG".into());
+    }
+
+    let driver_addr = pick_driver_area(sid, driver_len, prg_end)
+        .ok_or("No free memory area for the player driver")?;
+    let mut driver = driver_code(driver_addr, tune_src, &driver_params)?;
+    driver.resize(driver_len as usize, 0);
+    let boot = boot_code(
+        text_addr,
+        text.len() as u8,
+        driver_src,
+        driver_len as u8,
+        driver_addr,
+    );
+
+    let mut prg = Vec::with_capacity((prg_end - LOAD_ADDRESS as u32) as usize + 2);
+    prg.extend(LOAD_ADDRESS.to_le_bytes());
+    prg.extend(basic_line());
+    prg.extend(boot);
+    prg.extend(text);
+    prg.extend(driver);
+    prg.extend(&sid.payload);
+    Ok(prg)
+}
+
+/// `10 SYS 2061`
+fn basic_line() -> Vec<u8> {
+    let mut line = vec![0x0B, 0x08, 0x0A, 0x00, 0x9E];
+    line.extend(BOOT_ADDRESS.to_string().bytes());
+    line.extend([0x00, 0x00, 0x00]);
+    line
+}
+
+/// Title / author / released / subtune as whole 40-column screen rows.
+fn info_text(sid: &SidFile, song: u16, songs: u16) -> Vec<u8> {
+    let h = &sid.header;
+    let lines = [
+        h.name.clone(),
+        h.author.clone(),
+        h.released.clone(),
+        String::new(),
+        format!("SONG {song}/{songs}"),
+    ];
+    let mut out = Vec::with_capacity(lines.len() * SCREEN_COLS);
+    for line in &lines {
+        let mut row = vec![0x20u8; SCREEN_COLS];
+        for (cell, c) in row[1..SCREEN_COLS - 1].iter_mut().zip(line.chars()) {
+            *cell = screen_code(c);
+        }
+        out.extend(row);
+    }
+    out
+}
+
+/// ASCII → screen code for the default upper-case character set.
+fn screen_code(c: char) -> u8 {
+    match c {
+        '@' => 0x00,
+        'A'..='Z' => c as u8 - 0x40,
+        'a'..='z' => c as u8 - 0x60,
+        '[' => 0x1B,
+        ']' => 0x1D,
+        ' '..='?' => c as u8,
+        _ => b'?',
+    }
+}
+
+/// First page-aligned slot of `len` bytes the driver can run from.
+fn pick_driver_area(sid: &SidFile, len: u16, prg_end: u32) -> Option<u16> {
+    let load = sid.load_address as u32;
+    let load_end = load + sid.payload.len() as u32;
+    let fits = |start: u32| {
+        let end = start + len as u32;
+        // RAM that is visible whatever the banking: $0800–$9FFF, $C000–$CFFF.
+        let in_ram = (start >= 0x0800 && end <= 0xA000) || (start >= 0xC000 && end <= 0xD000);
+        in_ram
+            && (end <= LOAD_ADDRESS as u32 || start >= prg_end)
+            && (end <= load || start >= load_end)
+    };
+
+    // Free pages declared in a PSID v2+ header (start page, page count).
+    if let Some((page, count)) = reloc_pages(sid) {
+        let (page, count) = (page as u32, count as u32);
+        let declared = (page..page + count)
+            .map(|p| p << 8)
+            .find(|&a| a + len as u32 <= (page + count) << 8 && fits(a));
+        if let Some(addr) = declared {
+            return Some(addr as u16);
+        }
+        eprintln!("[prg] Declared free pages don't fit the driver, searching");
+    }
+
+    (0xC0..0xD0)
+        .chain((0x08..0xA0).rev())
+        .map(|p: u32| p << 8)
+        .find(|&a| fits(a))
+        .map(|a| a as u16)
+}
+
+struct Irq {
+    play: u16,
+    cia_timer: bool,
+    is_pal: bool,
+    /// KERNAL banked out — hook $FFFE instead of the $0314 vector.
+    hardware_vector: bool,
+}
+
+struct DriverParams {
+    load: u16,
+    len: u32,
+    bank: u8,
+    init: u16,
+    song: u16,
+    irq: Option<Irq>,
+}
+
+/// Boot code at $080D: clear the screen, print the info rows, move the
+/// driver into place and start it.
+fn boot_code(
+    text_addr: u16,
+    text_len: u8,
+    driver_src: u16,
+    driver_len: u8,
+    driver_addr: u16,
+) -> Vec<u8> {
+    let screen = 0x0400 + TEXT_ROW * SCREEN_COLS as u16;
+    let mut a = Asm::new(BOOT_ADDRESS);
+    a.op(SEI);
+    a.op(CLD);
+    a.abs(JSR, KERNAL_CLEAR_SCREEN);
+    a.imm(LDX_IMM, text_len);
+    a.label("text");
+    a.abs(LDA_ABS_X, text_addr.wrapping_sub(1));
+    a.abs(STA_ABS_X, screen.wrapping_sub(1));
+    a.op(DEX);
+    a.branch(BNE, "text");
+    a.imm(LDX_IMM, driver_len);
+    a.label("driver");
+    a.abs(LDA_ABS_X, driver_src.wrapping_sub(1));
+    a.abs(STA_ABS_X, driver_addr.wrapping_sub(1));
+    a.op(DEX);
+    a.branch(BNE, "driver");
+    a.abs(JMP, driver_addr);
+    a.finish().expect("boot code labels")
+}
+
+/// The resident driver, assembled to run at `org` with the tune image
+/// at `tune_src`.
+fn driver_code(org: u16, tune_src: u16, p: &DriverParams) -> Result<Vec<u8>, String> {
+    let mut a = Asm::new(org);
+    let dst = p.load as u32;
+    let src = tune_src as u32;
+    let pages = (p.len >> 8) as u8;
+    let rest = (p.len & 0xFF) as u8;
+
+    a.op(SEI);
+    if src != dst {
+        a.imm(LDA_IMM, 0x34);
+        a.zp(STA_ZP, 0x01);
+        if dst < src {
+            // Forward copy: whole pages, then the remainder.
+            a.pointers(src, dst);
+            a.imm(LDY_IMM, 0);
+            if pages > 0 {
+                a.imm(LDX_IMM, pages);
+                a.label("fwd_page");
+                a.zp(LDA_IND_Y, ZP_SRC);
+                a.zp(STA_IND_Y, ZP_DST);
+                a.op(INY);
+                a.branch(BNE, "fwd_page");
+                a.zp(INC_ZP, ZP_SRC + 1);
+                a.zp(INC_ZP, ZP_DST + 1);
+                a.op(DEX);
+                a.branch(BNE, "fwd_page");
+            }
+            if rest > 0 {
+                a.label("fwd_rest");
+                a.zp(LDA_IND_Y, ZP_SRC);
+                a.zp(STA_IND_Y, ZP_DST);
+                a.op(INY);
+                a.imm(CPY_IMM, rest);
+                a.branch(BNE, "fwd_rest");
+            }
+        } else {
+            // Backward copy for overlapping moves up: remainder first,
+            // then whole pages from the top down.
+            let whole = (pages as u32) << 8;
+            if rest > 0 {
+                a.pointers(src + whole, dst + whole);
+                a.imm(LDY_IMM, rest);
+                a.label("back_rest");
+                a.op(DEY);
+                a.zp(LDA_IND_Y, ZP_SRC);
+                a.zp(STA_IND_Y, ZP_DST);
+                a.op(TYA);
+                a.branch(BNE, "back_rest");
+            }
+            if pages > 0 {
+                a.pointers(src + whole - 0x100, dst + whole - 0x100);
+                a.imm(LDX_IMM, pages);
+                a.imm(LDY_IMM, 0);
+                a.label("back_page");
+                a.op(DEY);
+                a.zp(LDA_IND_Y, ZP_SRC);
+                a.zp(STA_IND_Y, ZP_DST);
+                a.op(TYA);
+                a.branch(BNE, "back_page");
+                a.zp(DEC_ZP, ZP_SRC + 1);
+                a.zp(DEC_ZP, ZP_DST + 1);
+                a.op(DEX);
+                a.branch(BNE, "back_page");
+            }
+        }
+    }
+    a.imm(LDA_IMM, p.bank);
+    a.zp(STA_ZP, 0x01);
+
+    if let Some(irq) = &p.irq {
+        if irq.cia_timer {
+            let latch = if irq.is_pal {
+                CIA_60HZ_PAL
+            } else {
+                CIA_60HZ_NTSC
+            };
+            a.imm(LDA_IMM, 0x00);
+            a.abs(STA_ABS, 0xD01A);
+            a.imm(LDA_IMM, 0x7F);
+            a.abs(STA_ABS, 0xDC0D);
+            a.imm(LDA_IMM, latch as u8);
+            a.abs(STA_ABS, 0xDC04);
+            a.imm(LDA_IMM, (latch >> 8) as u8);
+            a.abs(STA_ABS, 0xDC05);
+            a.imm(LDA_IMM, 0x81);
+            a.abs(STA_ABS, 0xDC0D);
+            a.imm(LDA_IMM, 0x11);
+            a.abs(STA_ABS, 0xDC0E);
+        } else {
+            a.imm(LDA_IMM, 0x7F);
+            a.abs(STA_ABS, 0xDC0D);
+            a.abs(LDA_ABS, 0xD011);
+            a.imm(AND_IMM, 0x7F);
+            a.abs(STA_ABS, 0xD011);
+            a.imm(LDA_IMM, 0x33);
+            a.abs(STA_ABS, 0xD012);
+            a.imm(LDA_IMM, 0x01);
+            a.abs(STA_ABS, 0xD01A);
+        }
+        a.abs(LDA_ABS, 0xDC0D);
+        a.imm(LDA_IMM, 0xFF);
+        a.abs(STA_ABS, 0xD019);
+        if irq.hardware_vector {
+            a.vector(0xFFFE, "irq");
+            a.vector(0xFFFA, "nmi");
+        } else {
+            a.vector(0x0314, "irq");
+        }
+    }
+
+    a.imm(LDA_IMM, (p.song - 1) as u8);
+    a.abs(JSR, p.init);
+    a.op(CLI);
+    a.label("idle");
+    a.abs_label(JMP, "idle");
+
+    if let Some(irq) = &p.irq {
+        irq_handler(&mut a, irq.play, irq.hardware_vector);
+    }
+
+    let code = a.finish()?;
+    if code.len() > 0xFF {
+        return Err("Player driver is larger than one page".into());
+    }
+    Ok(code)
+}
+
+/// IRQ handler calling `play`: ack the VIC and CIA 1, JSR PLAY, return.
+/// Behind the hardware vector it saves A/X/Y itself and ends in RTI
+/// (also the "nmi" no-op); behind $0314 the KERNAL has saved them and
+/// `KERNAL_IRQ_EXIT` restores them.
+fn irq_handler(a: &mut Asm, play: u16, hardware_vector: bool) {
+    a.label("irq");
+    if hardware_vector {
+        a.op(PHA);
+        a.op(TXA);
+        a.op(PHA);
+        a.op(TYA);
+        a.op(PHA);
+    }
+    a.imm(LDA_IMM, 0xFF);
+    a.abs(STA_ABS, 0xD019);
+    a.abs(LDA_ABS, 0xDC0D);
+    a.abs(JSR, play);
+    if hardware_vector {
+        a.op(PLA);
+        a.op(TAY);
+        a.op(PLA);
+        a.op(TAX);
+        a.op(PLA);
+        a.label("nmi");
+        a.op(RTI);
+    } else {
+        a.abs(JMP, KERNAL_IRQ_EXIT);
+    }
+}
+
+/// Size of `psid_irq_handler`'s code.
+pub const PSID_IRQ_HANDLER_LEN: usize = 22;
+
+/// The hardware-vector IRQ handler on its own, assembled at `org`, for
+/// the cycle-exact PSID engine (`RsidBus::install_psid_driver`).
+pub fn psid_irq_handler(org: u16, play: u16) -> [u8; PSID_IRQ_HANDLER_LEN] {
+    let mut a = Asm::new(org);
+    irq_handler(&mut a, play, true);
+    let code = a.finish().expect("IRQ handler labels");
+    code.try_into().expect("IRQ handler size")
+}
+
+// ── Minimal 6502 assembler ───────────────────────────────────────────────
+
+const AND_IMM: u8 = 0x29;
+const BNE: u8 = 0xD0;
+const CLD: u8 = 0xD8;
+const CLI: u8 = 0x58;
+const CPY_IMM: u8 = 0xC0;
+const DEC_ZP: u8 = 0xC6;
+const DEX: u8 = 0xCA;
+const DEY: u8 = 0x88;
+const INC_ZP: u8 = 0xE6;
+const INY: u8 = 0xC8;
+const JMP: u8 = 0x4C;
+const JSR: u8 = 0x20;
+const LDA_ABS: u8 = 0xAD;
+const LDA_ABS_X: u8 = 0xBD;
+const LDA_IMM: u8 = 0xA9;
+const LDA_IND_Y: u8 = 0xB1;
+const LDX_IMM: u8 = 0xA2;
+const LDY_IMM: u8 = 0xA0;
+const PHA: u8 = 0x48;
+const PLA: u8 = 0x68;
+const RTI: u8 = 0x40;
+const SEI: u8 = 0x78;
+const STA_ABS: u8 = 0x8D;
+const STA_ABS_X: u8 = 0x9D;
+const STA_IND_Y: u8 = 0x91;
+const STA_ZP: u8 = 0x85;
+const TAX: u8 = 0xAA;
+const TAY: u8 = 0xA8;
+const TXA: u8 = 0x8A;
+const TYA: u8 = 0x98;
+
+enum Fixup {
+    /// Relative branch offset at this index.
+    Branch,
+    /// Full 16-bit address at this index.
+    Word,
+    /// Low / high byte of the address as an immediate operand.
+    Lo,
+    Hi,
+}
+
+struct Asm {
+    org: u16,
+    code: Vec<u8>,
+    labels: Vec<(&'static str, u16)>,
+    fixups: Vec<(usize, &'static str, Fixup)>,
+}
+
+impl Asm {
+    fn new(org: u16) -> Self {
+        Self {
+            org,
+            code: Vec::new(),
+            labels: Vec::new(),
+            fixups: Vec::new(),
+        }
+    }
+
+    fn pc(&self) -> u16 {
+        self.org.wrapping_add(self.code.len() as u16)
+    }
+
+    fn label(&mut self, name: &'static str) {
+        let pc = self.pc();
+        self.labels.push((name, pc));
+    }
+
+    fn op(&mut self, op: u8) {
+        self.code.push(op);
+    }
+
+    fn imm(&mut self, op: u8, val: u8) {
+        self.code.extend([op, val]);
+    }
+
+    fn zp(&mut self, op: u8, addr: u8) {
+        self.code.extend([op, addr]);
+    }
+
+    fn abs(&mut self, op: u8, addr: u16) {
+        self.code.push(op);
+        self.code.extend(addr.to_le_bytes());
+    }
+
+    fn abs_label(&mut self, op: u8, name: &'static str) {
+        self.code.push(op);
+        self.fixups.push((self.code.len(), name, Fixup::Word));
+        self.code.extend([0, 0]);
+    }
+
+    fn branch(&mut self, op: u8, name: &'static str) {
+        self.code.push(op);
+        self.fixups.push((self.code.len(), name, Fixup::Branch));
+        self.code.push(0);
+    }
+
+    /// Point the zero-page copy pointers at `src` / `dst`.
+    fn pointers(&mut self, src: u32, dst: u32) {
+        for (zp, addr) in [(ZP_SRC, src), (ZP_DST, dst)] {
+            self.imm(LDA_IMM, addr as u8);
+            self.zp(STA_ZP, zp);
+            self.imm(LDA_IMM, (addr >> 8) as u8);
+            self.zp(STA_ZP, zp + 1);
+        }
+    }
+
+    /// Store the address of `name` into the vector at `vec`.
+    fn vector(&mut self, vec: u16, name: &'static str) {
+        for (fixup, addr) in [(Fixup::Lo, vec), (Fixup::Hi, vec + 1)] {
+            self.code.push(LDA_IMM);
+            self.fixups.push((self.code.len(), name, fixup));
+            self.code.push(0);
+            self.abs(STA_ABS, addr);
+        }
+    }
+
+    fn finish(mut self) -> Result<Vec<u8>, String> {
+        for (at, name, kind) in &self.fixups {
+            let target = self
+                .labels
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|&(_, a)| a)
+                .ok_or_else(|| format!("Undefined label '{name}'"))?;
+            match kind {
+                Fixup::Branch => {
+                    let next = self.org as i32 + *at as i32 + 1;
+                    let offset = target as i32 - next;
+                    if !(-128..=127).contains(&offset) {
+                        return Err(format!("Branch to '{name}' out of range"));
+                    }
+                    self.code[*at] = offset as i8 as u8;
+                }
+                Fixup::Word => {
+                    self.code[*at..*at + 2].copy_from_slice(&target.to_le_bytes());
+                }
+                Fixup::Lo => self.code[*at] = target as u8,
+                Fixup::Hi => self.code[*at] = (target >> 8) as u8,
+            }
+        }
+        Ok(self.code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::sid_file::load_sid;
+
+    /// PSID v2 with the given load / init / play addresses and payload.
+    fn synth_psid(load: u16, init: u16, play: u16, payload: &[u8]) -> SidFile {
+        let mut buf = vec![0u8; 0x7C];
+        buf[0..4].copy_from_slice(b"PSID");
+        buf[5] = 0x02;
+        buf[7] = 0x7C;
+        buf[0x0A..0x0C].copy_from_slice(&init.to_be_bytes());
+        buf[0x0C..0x0E].copy_from_slice(&play.to_be_bytes());
+        buf[0x0F] = 3; // songs
+        buf[0x11] = 1; // start song
+        buf[0x16..0x1B].copy_from_slice(b"Tune!");
+        buf.extend(load.to_le_bytes());
+        buf.extend(payload);
+        load_sid(&buf).unwrap()
+    }
+
+    #[test]
+    fn layout_has_sys_line_and_trailing_payload() {
+        let payload: Vec<u8> = (0..300u32).map(|i| i as u8).collect();
+        let sid = synth_psid(0x1000, 0x1000, 0x1003, &payload);
+        let prg = build(&sid, 2).unwrap();
+        assert_eq!(&prg[..2], &[0x01, 0x08]);
+        assert_eq!(
+            &prg[2..14],
+            &[0x0B, 0x08, 0x0A, 0x00, 0x9E, b'2', b'0', b'6', b'1', 0, 0, 0]
+        );
+        assert!(prg.ends_with(&payload));
+        let text = info_text(&sid, 2, 3);
+        assert_eq!(&text[1..6], &[0x14, 0x15, 0x0E, 0x05, 0x21]);
+        assert_eq!(text.len(), 5 * SCREEN_COLS);
+    }
+
+    #[test]
+    fn driver_avoids_tune_and_image() {
+        let payload = vec![0xEA; 0x2000];
+        let sid = synth_psid(0xC000, 0xC000, 0xC003, &payload);
+        let prg_end = 0x0801 + 0x2400;
+        let at = pick_driver_area(&sid, 0xA0, prg_end).unwrap() as u32;
+        assert!(at + 0xA0 <= 0x0801 || at >= prg_end);
+        assert!(at + 0xA0 <= 0xC000 || at >= 0xE000);
+        assert!(at >= 0x0800 && at + 0xA0 <= 0xD000);
+    }
+
+    #[test]
+    fn standalone_irq_handler_saves_registers_and_returns_with_rti() {
+        let code = psid_irq_handler(0x033C, 0x1003);
+        assert_eq!(&code[..5], &[PHA, TXA, PHA, TYA, PHA]);
+        assert_eq!(&code[13..16], &[JSR, 0x03, 0x10]);
+        assert_eq!(code.last(), Some(&RTI));
+    }
+
+    #[test]
+    fn rejects_basic_rsid() {
+        let mut sid = synth_psid(0x0801, 0, 0, &[0; 16]);
+        sid.header.is_basic = true;
+        assert!(build(&sid, 1).is_err());
+    }
+}
@@ -26,6 +26,14 @@ pub enum RemoteCmd {
     NextTrack,
     PrevTrack,
     SetSubtune(u16),
+    /// Jump to this many seconds into the current subtune.
+    Seek(u32),
+    /// Per-voice / per-chip mute and solo toggles, 0-based `(sid, voice)`.
+    /// The HTTP route takes 1-based numbers to match the UI labels.
+    ToggleVoiceMute(usize, usize),
+    ToggleVoiceSolo(usize, usize),
+    ToggleSidMute(usize),
+    ToggleSidSolo(usize),
     // Playback QOL — added to bring the remote API up to parity with
     // the library / QOL features that landed in the desktop UI over the
     // last release cycle. Every variant is dispatched 1:1 to an existing
@@ -36,6 +44,9 @@ pub enum RemoteCmd {
     CycleRepeat,
     SetSleepTimer(Option<u32>),
     SetVolume(f32),
+    /// Tempo in percent; clamped by `tempo::set`.
+    SetTempo(u16),
+    SetTempoPitchFollows(bool),
     Surprise,
     // Library — Published Playlists
     LoadPublishedPlaylist(String),
@@ -114,6 +125,10 @@ pub struct RemoteStatus {
     pub is_favorite: bool,
     /// Host-side master volume in `[0.0, 1.0]`.
     pub master_volume: f32,
+    /// Playback tempo in percent (100 = normal speed).
+    pub tempo_pct: u16,
+    /// Whether the SID clock follows the tempo on software engines.
+    pub tempo_pitch_follows: bool,
     /// Playlist-level playback flags.
     pub shuffle: bool,
     /// "off" | "one" | "all" — string so the web UI can render directly.
@@ -147,6 +162,11 @@ pub struct RemoteStatus {
     /// Live mirror of `config.surprise_source` — "hvsc" or "playlist".
     #[serde(default)]
     pub surprise_source: String,
+    /// Mute / solo bitmasks, bit `sid * 3 + voice`.
+    #[serde(default)]
+    pub voice_muted: u16,
+    #[serde(default)]
+    pub voice_soloed: u16,
 }
 
 /// A single row served by `GET /api/recent`. Mirrors `RecentEntry` but
@@ -565,6 +585,34 @@ pub fn start_server(
                         }
                     }
 
+                    // ── API: seek within the current subtune ─────────────
+                    ("POST", p) if p.starts_with("/api/seek/") => {
+                        if let Some(s_str) = p.strip_prefix("/api/seek/") {
+                            match s_str.parse::<f32>() {
+                                Ok(s) if s.is_finite() && s >= 0.0 => {
+                                    let _ = cmd_tx.try_send(RemoteCmd::Seek(s as u32));
+                                    respond_ok(request);
+                                }
+                                _ => respond_error(request, 400, "Invalid seconds"),
+                            }
+                        } else {
+                            respond_error(request, 400, "Missing seconds");
+                        }
+                    }
+
+                    // ── API: voice mute / solo ───────────────────────────
+                    // /api/voice/{sid}/{voice}/mute|solo, 1-based; `all` as
+                    // the voice toggles every voice of that SID at once.
+                    ("POST", p) if p.starts_with("/api/voice/") => {
+                        match parse_voice_route(&p["/api/voice/".len()..]) {
+                            Some(cmd) => {
+                                let _ = cmd_tx.try_send(cmd);
+                                respond_ok(request);
+                            }
+                            None => respond_error(request, 400, "Invalid voice route"),
+                        }
+                    }
+
                     // ── API: playlist editing ────────────────────────────
                     ("POST", "/api/playlist/clear") => {
                         let _ = cmd_tx.try_send(RemoteCmd::PlaylistClear);
@@ -723,6 +771,32 @@ pub fn start_server(
                             respond_error(request, 400, "Missing volume");
                         }
                     }
+                    ("POST", p) if p.starts_with("/api/tempo/pitch/") => {
+                        match p.strip_prefix("/api/tempo/pitch/") {
+                            Some("on") => {
+                                let _ = cmd_tx.try_send(RemoteCmd::SetTempoPitchFollows(true));
+                                respond_ok(request);
+                            }
+                            Some("off") => {
+                                let _ = cmd_tx.try_send(RemoteCmd::SetTempoPitchFollows(false));
+                                respond_ok(request);
+                            }
+                            _ => respond_error(request, 400, "Expected on or off"),
+                        }
+                    }
+                    ("POST", p) if p.starts_with("/api/tempo/") => {
+                        if let Some(pct_str) = p.strip_prefix("/api/tempo/") {
+                            match pct_str.parse::<u16>() {
+                                Ok(pct) => {
+                                    let _ = cmd_tx.try_send(RemoteCmd::SetTempo(pct));
+                                    respond_ok(request);
+                                }
+                                Err(_) => respond_error(request, 400, "Invalid tempo"),
+                            }
+                        } else {
+                            respond_error(request, 400, "Missing tempo");
+                        }
+                    }
                     ("POST", "/api/surprise") => {
                         let _ = cmd_tx.try_send(RemoteCmd::Surprise);
                         respond_ok(request);
@@ -1062,6 +1136,33 @@ fn respond_error(request: tiny_http::Request, code: u16, msg: &str) {
     let _ = request.respond(resp);
 }
 
+/// Parse the `{sid}/{voice}/{mute|solo}` tail of a `/api/voice/` route.
+fn parse_voice_route(rest: &str) -> Option<RemoteCmd> {
+    let mut parts = rest.split('/');
+    let sid = parts.next()?.parse::<usize>().ok()?.checked_sub(1)?;
+    let voice = parts.next()?;
+    let action = parts.next()?;
+    if sid >= 4 || parts.next().is_some() {
+        return None;
+    }
+    if voice == "all" {
+        return match action {
+            "mute" => Some(RemoteCmd::ToggleSidMute(sid)),
+            "solo" => Some(RemoteCmd::ToggleSidSolo(sid)),
+            _ => None,
+        };
+    }
+    let voice = voice.parse::<usize>().ok()?.checked_sub(1)?;
+    if voice >= 3 {
+        return None;
+    }
+    match action {
+        "mute" => Some(RemoteCmd::ToggleVoiceMute(sid, voice)),
+        "solo" => Some(RemoteCmd::ToggleVoiceSolo(sid, voice)),
+        _ => None,
+    }
+}
+
 /// Minimal percent-decoding for query parameter values.
 fn urldecode(s: &str) -> String {
     let mut out = String::with_capacity(s.len());
@@ -1263,7 +1364,7 @@ const WEB_UI: &str = r##"<!DOCTYPE html>
   .np-title { font-size:20px; font-weight:600; color:#e0e4e8; }
   .np-author { font-size:14px; color:#8090a0; margin-top:4px; }
   .np-info { font-size:12px; color:#506070; margin-top:6px; }
-  .progress { height:3px; background:#1a1e26; margin:0 16px; border-radius:2px; }
+  .progress { height:3px; background:#1a1e26; margin:0 16px; border-radius:2px; cursor:pointer; }
   .progress-fill { height:100%; background:linear-gradient(90deg,#3a7,#5cb870); border-radius:2px; transition:width 0.5s; }
   .controls { display:flex; justify-content:center; gap:12px; padding:16px; }
   .controls button { width:52px; height:42px; border:1px solid #2a2e36; border-radius:8px;
@@ -1520,7 +1621,7 @@ const WEB_UI: &str = r##"<!DOCTYPE html>
   <div class="np-info" id="np-info"></div>
 </div>
 
-<div class="progress"><div class="progress-fill" id="prog" style="width:0%"></div></div>
+<div class="progress" onclick="seekClick(event)" title="Click to seek"><div class="progress-fill" id="prog" style="width:0%"></div></div>
 
 <div class="controls">
   <button onclick="cmd('prev')" title="Previous">&#9198;</button>
@@ -1562,6 +1663,16 @@ const WEB_UI: &str = r##"<!DOCTYPE html>
     <input type="range" id="vol" min="0" max="100" step="1" value="100"
       oninput="setVolume(this.value)" title="Master volume 0–100%">
   </label>
+  <label title="Tempo — slows down or speeds up playback (50–200%)">&#9201;
+    <input type="range" id="tempo" min="50" max="200" step="10" value="100"
+      oninput="setTempo(this.value)" ondblclick="this.value=100;setTempo(100)"
+      title="Tempo 50–200% (double-click to reset)">
+    <span id="tempo-val">100%</span>
+  </label>
+  <label title="Pitch follows speed — software engines retune with the tempo, like a tape">
+    <input type="checkbox" id="tempo-pitch"
+      onchange="fetch('/api/tempo/pitch/'+(this.checked?'on':'off'),{method:'POST'})">&#9835;
+  </label>
   <button onclick="cmd('shuffle')" id="shuf-btn" title="Shuffle — random-order playback of the current playlist"
     style="background:none;border:1px solid #2a2e36;color:#8090a0;padding:4px 8px;border-radius:4px;cursor:pointer;">&#128256;</button>
   <button onclick="cmd('repeat')" id="rep-btn" title="Repeat — cycle through Off → All → One"
@@ -1724,12 +1835,21 @@ let entries=[], status={}, curIdx=null, total=0, loading=false, searchTimer=null
 // the web UI without a manual refresh.
 let lastPlaylistVersion=null;
 let volDebounce=null;
+let tempoDebounce=null;
 
 async function cmd(c){
   await fetch('/api/'+c,{method:'POST'});
   setTimeout(poll,150);
 }
 
+// Progress-bar click → seek to that fraction of the known song length.
+function seekClick(ev){
+  if(!status || !status.duration_secs) return;
+  const r=ev.currentTarget.getBoundingClientRect();
+  const frac=Math.min(1,Math.max(0,(ev.clientX-r.left)/r.width));
+  cmd('seek/'+Math.floor(frac*status.duration_secs));
+}
+
 // (Legacy `loadLiked` transport-row helper removed — the
 // "Load liked as playlist" verb now lives inside the Library panel's
 // ❤ Liked tab as `playAllLiked()`, matching Spotify's convention
@@ -1815,6 +1935,10 @@ function setupMediaSession(){
   navigator.mediaSession.setActionHandler('previoustrack', ()=>cmd('prev'));
   navigator.mediaSession.setActionHandler('nexttrack',     ()=>cmd('next'));
   navigator.mediaSession.setActionHandler('stop', ()=>cmd('stop'));
+  try{
+    navigator.mediaSession.setActionHandler('seekto',
+      (d)=>{ if(d && d.seekTime!=null) cmd('seek/'+Math.floor(d.seekTime)); });
+  }catch(_){}
 }
 
 // Push current-track metadata + timeline into the OS media session
@@ -1909,6 +2033,14 @@ function setVolume(pct){
   },100);
 }
 
+function setTempo(pct){
+  clearTimeout(tempoDebounce);
+  document.getElementById('tempo-val').textContent=pct+'%';
+  tempoDebounce=setTimeout(()=>{
+    fetch('/api/tempo/'+pct,{method:'POST'});
+  },100);
+}
+
 async function restoreDefault(){
   await fetch('/api/library/playlists/restore',{method:'POST'});
   setTimeout(poll,300);
@@ -2389,6 +2521,15 @@ async function poll(){
     if(vol&&document.activeElement!==vol&&status.master_volume!==undefined){
       vol.value=Math.round(status.master_volume*100);
     }
+    const tempo=document.getElementById('tempo');
+    if(tempo&&document.activeElement!==tempo&&status.tempo_pct!==undefined){
+      tempo.value=status.tempo_pct;
+      document.getElementById('tempo-val').textContent=status.tempo_pct+'%';
+    }
+    const tempoPitch=document.getElementById('tempo-pitch');
+    if(tempoPitch&&status.tempo_pitch_follows!==undefined){
+      tempoPitch.checked=status.tempo_pitch_follows;
+    }
     // Sleep timer state
     const sleepSel=document.getElementById('sleep');
     if(sleepSel&&document.activeElement!==sleepSel){
@@ -4,6 +4,10 @@
 //   "usb"      — USBSID-Pico hardware.
 //   "emulated" — resid-rs software emulation + cpal audio output
 //   "u64"      — Ultimate 64 / Ultimate-II+ via REST API (native SID playback)
+//   "replay"   — plays a recorded SID write log (`sid_log`) on another engine
+//
+// Any register-level engine can also be paired with a second one for A/B
+// listening (`sid_mirror`, `attach_mirror`).
 
 /// Common interface for all SID output backends.
 pub trait SidDevice: Send {
@@ -29,19 +33,50 @@ pub trait SidDevice: Send {
         Ok(())
     }
 
+    /// Name the tune about to be loaded — its HVSC MD5 and 1-based song —
+    /// just ahead of `reset()`. Default no-op; only the write-log
+    /// wrappers (`sid_log`) use it, to stamp and check the log header.
+    fn set_tune(&mut self, _md5: &str, _song: u16) {}
+
     /// Override cycles-per-frame for flush() audio generation.
     /// Only meaningful for emulated engine; hardware devices ignore this.
     fn set_cycles_per_frame(&mut self, _cycles: u32) {}
 
-    /// Hint the SID chip model from the loaded tune's header.
+    /// Hint the SID chip model for each chip slot (SID1–SID4), resolved
+    /// from the tune header and any user override.
     /// Values follow the PSID/RSID flags encoding:
     /// 0 = unknown, 1 = MOS6581, 2 = MOS8580, 3 = both/unknown.
     /// Default no-op — the USB / U64 engines have their own model
-    /// handling (or none), and SIDLite reads the header itself.
-    /// Only the `EmulatedDevice` (resid-rs) implementation swaps chips
-    /// per tune; without this hint every tune plays through a 6581,
-    /// which sounds muffled on 8580-composed material.
-    fn set_sid_model(&mut self, _model: u8) {}
+    /// handling (or none). The software engines (reSID, SIDLite) swap
+    /// chips per tune; without this hint every tune plays through a
+    /// 6581, which sounds muffled on 8580-composed material.
+    fn set_sid_models(&mut self, _models: [u8; 4]) {}
+
+    /// Apply the per-tune software-engine tuning (8580 digi boost, 6581
+    /// filter preset). Called after `set_sid_models`. Default no-op —
+    /// only reSID and SIDLite have anything to tune.
+    fn set_emu_tuning(&mut self, _tuning: crate::emu_tuning::EmuTuning) {}
+
+    /// Run the SID clock at `pct` percent of PAL/NTSC, for the tempo's
+    /// "pitch follows speed" mode. `set_clock_rate` resets it to 100.
+    /// Returns false when the engine's clock is fixed (real chips).
+    fn set_clock_scale(&mut self, _pct: u16) -> bool {
+        false
+    }
+
+    /// True when this engine's output runs through the host-side
+    /// `audio_volume` gain. Fade-outs then scale the sample stream;
+    /// engines returning false (real chips) get $D418 ramped instead.
+    fn has_host_gain(&self) -> bool {
+        false
+    }
+
+    /// Drain every stereo sample generated since the last call.
+    /// Only the software engines opened with `open_offline()` return
+    /// anything — used by `phosphor render` instead of a cpal stream.
+    fn take_rendered(&mut self) -> Vec<(i16, i16)> {
+        Vec::new()
+    }
 
     /// Send a complete SID file for native playback on real hardware.
     ///
@@ -120,7 +155,7 @@ pub trait SidDevice: Send {
 
 /// List of engine names available at runtime.
 pub fn available_engines() -> Vec<&'static str> {
-    vec!["usb", "emulated", "sidlite", "u64"]
+    vec!["usb", "emulated", "sidlite", "u64", "replay"]
 }
 
 /// Create a SidDevice for the given engine name.
@@ -141,6 +176,7 @@ pub fn create_engine(
         "emulated" => create_emulated(),
         "sidlite" => create_sidlite(),
         "u64" => create_u64(u64_address, u64_password),
+        "replay" => create_replay(u64_address, u64_password, macos_usb_mode),
         other => Err(format!(
             "Unknown engine '{}'. Available: {:?}",
             other,
@@ -149,6 +185,23 @@ pub fn create_engine(
     }
 }
 
+/// Open a software engine with no audio output, for `phosphor render`.
+/// Only the sample-generating engines make sense here: "resid" (alias
+/// "emulated") and "sidlite". Samples are collected via `take_rendered()`.
+pub fn create_offline_engine(name: &str, sample_rate: u32) -> Result<Box<dyn SidDevice>, String> {
+    match name {
+        "resid" | "emulated" => Ok(Box::new(crate::sid_emulated::EmulatedDevice::open_offline(
+            sample_rate,
+        ))),
+        "sidlite" => Ok(Box::new(crate::sid_sidlite::SidLiteDevice::open_offline(
+            sample_rate,
+        ))),
+        other => Err(format!(
+            "Engine '{other}' cannot render offline. Available: resid, sidlite"
+        )),
+    }
+}
+
 /// Auto: try USB → SIDLite → reSID emulated → U64 (if address set).
 /// SIDLite (libsidplayfp) is preferred over the resid-rs "emulated"
 /// engine because it picks the correct chip model per tune from the SID
@@ -237,6 +290,58 @@ fn create_sidlite() -> Result<Box<dyn SidDevice>, String> {
 /// Ultimate 64 via REST API.
 fn create_u64(address: &str, password: &str) -> Result<Box<dyn SidDevice>, String> {
     eprintln!("[phosphor] Connecting to Ultimate 64 at {address}…");
-    let dev = crate::sid_u64::U64Device::connect(address, password)?;
+    let mut dev = crate::sid_u64::U64Device::connect(address, password)?;
+    dev.set_run_as_prg(crate::config::Config::load().u64_run_as_prg);
+    Ok(Box::new(dev))
+}
+
+/// Pair `primary` with the configured mirror engine for A/B listening.
+/// Both must be register-level engines (USB, reSID, SIDLite) and differ.
+/// If the mirror can't be opened the primary plays alone.
+pub fn attach_mirror(
+    primary: Box<dyn SidDevice>,
+    engine_name: &str,
+    macos_usb_mode: &str,
+) -> Box<dyn SidDevice> {
+    let mirror = crate::config::Config::load().mirror_engine;
+    let register_level = |name: &str| matches!(name, "usb" | "emulated" | "sidlite");
+    if !register_level(engine_name) || !register_level(&mirror) || mirror == engine_name {
+        return primary;
+    }
+    match create_engine(&mirror, "", "", macos_usb_mode) {
+        Ok(secondary) => {
+            eprintln!("[phosphor] A/B mirror: '{engine_name}' + '{mirror}'");
+            Box::new(crate::sid_mirror::MirrorDevice::new(
+                primary,
+                secondary,
+                (engine_name, &mirror),
+            ))
+        }
+        Err(e) => {
+            eprintln!("[phosphor] A/B mirror '{mirror}' unavailable: {e}");
+            primary
+        }
+    }
+}
+
+/// Write-log replay: the log file and the engine it plays on come from
+/// the config. Only the register-level engines can replay — the U64 plays
+/// SIDs natively and never sees individual writes.
+fn create_replay(
+    u64_address: &str,
+    u64_password: &str,
+    macos_usb_mode: &str,
+) -> Result<Box<dyn SidDevice>, String> {
+    let config = crate::config::Config::load();
+    let path = config
+        .replay_file
+        .ok_or("No SID write log selected. Settings → Audio → SID write log.")?;
+    let target = config.replay_engine.as_str();
+    if !matches!(target, "usb" | "emulated" | "sidlite") {
+        return Err(format!("Engine '{target}' cannot replay a SID write log"));
+    }
+    eprintln!("[phosphor] Replaying SID write log {path} on '{target}'…");
+    let inner = create_engine(target, u64_address, u64_password, macos_usb_mode)?;
+    let dev = crate::sid_log::ReplayDevice::open(std::path::Path::new(&path), inner)?;
     Ok(Box::new(dev))
 }
@@ -18,10 +18,14 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::traits::StreamTrait;
 use resid::{ChipModel, SamplingMethod, Sid};
 
+use crate::audio_output::{OutputSettings, RingSizes};
+use crate::emu_tuning::{adjust_cutoff, EmuTuning};
 use crate::sid_device::SidDevice;
+use crate::stereo_mix::StereoMixer;
+use crate::voice_scope::{self, VoiceScope};
 
 // ─────────────────────────────────────────────────────────────────────────────
 //  Constants
@@ -35,13 +39,13 @@ const NTSC_CYCLES_PER_FRAME: u32 = 17_095; // 263 lines × 65 cycles
 /// Number of SID registers per chip (0x00-0x1F).
 const SID_REGS: u8 = 0x20;
 
-/// Max ring buffer capacity in stereo pairs.
-/// ~256ms at 48kHz - must be larger than the prefill to leave room for audio frames.
-const MAX_BUFFER_SAMPLES: usize = 12288;
-
 /// Scratch buffer for resid sample() output.
 const SCRATCH_SIZE: usize = 2048;
 
+/// External-input level for 8580 digi boost — libsidplayfp's reSID
+/// builder feeds the same value.
+const DIGI_BOOST_INPUT: i32 = -32768;
+
 //  Models the C64 mainboard two-stage RC network on the SID audio output line.
 //  Every C64 has this circuit, so its frequency response is part of the authentic
 //  sound — especially the LP roll-off that softens the harshness at the top end,
@@ -136,6 +140,122 @@ impl SendSid {
     }
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+//  Per-voice scope tap
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// reSID only reads back voice 3's oscillator and envelope (OSC3 /
+/// ENV3). Each voice is watched through its own shadow chip with the
+/// voices rotated so it sits in slot 3. Sync and ring modulation run in
+/// the cycle 1 ← 3 ← 2 ← 1, which a rotation keeps, so the shadow
+/// computes that voice exactly as the audible chip does.
+///
+/// The shadows only run while the scope is on screen. Opening it starts
+/// them from the current registers, so notes already sounding come in
+/// through a fresh attack.
+struct ResidScope {
+    scope: VoiceScope,
+    models: [ChipModel; 4],
+    /// Voice registers ($00–$14) per chip as written.
+    regs: [[u8; 21]; 4],
+    chips: usize,
+    /// Three shadow chips per active chip, voice 1 to 3.
+    shadows: Vec<[SendSid; 3]>,
+    points: Vec<i16>,
+}
+
+/// Register in the shadow chip that watches voice `target` for voice
+/// register `local` ($00–$14).
+fn rotated_reg(target: u8, local: u8) -> u8 {
+    let slot = (local / 7 + 5 - target) % 3;
+    slot * 7 + local % 7
+}
+
+impl ResidScope {
+    fn new(sample_rate: u32, model: ChipModel) -> Self {
+        Self {
+            scope: VoiceScope::new(sample_rate),
+            models: [model; 4],
+            regs: [[0; 21]; 4],
+            chips: 1,
+            shadows: Vec::new(),
+            points: Vec::with_capacity(12),
+        }
+    }
+
+    fn set_chips(&mut self, chips: usize) {
+        self.chips = chips.clamp(1, 4);
+        self.shadows.truncate(self.chips);
+    }
+
+    fn set_models(&mut self, models: [ChipModel; 4]) {
+        self.models = models;
+        self.shadows.clear();
+    }
+
+    fn reset(&mut self) {
+        self.regs = [[0; 21]; 4];
+        self.shadows.clear();
+        self.scope.reset();
+    }
+
+    /// Mirror a register write, `reg` as in `SidDevice::write`.
+    fn write(&mut self, reg: u8, val: u8) {
+        let chip = (reg / SID_REGS) as usize;
+        let local = reg % SID_REGS;
+        if chip >= 4 || local >= 21 {
+            return;
+        }
+        self.regs[chip][local as usize] = val;
+        if let Some(shadows) = self.shadows.get_mut(chip) {
+            for (target, sid) in shadows.iter_mut().enumerate() {
+                sid.inner().write(rotated_reg(target as u8, local), val);
+            }
+        }
+    }
+
+    /// Advance by `cycles`, spread evenly over the `points` samples the
+    /// audible chips produced for them, and publish each voice's OSC ×
+    /// ENV once per sample.
+    fn clock(&mut self, cycles: u32, points: usize) {
+        if !self.scope.wanted() {
+            self.shadows.clear();
+            return;
+        }
+        while self.shadows.len() < self.chips {
+            let chip = self.shadows.len();
+            let model = self.models[chip];
+            let regs = self.regs[chip];
+            self.shadows.push(std::array::from_fn(|target| {
+                let mut sid = SendSid::new(model);
+                for (local, &val) in regs.iter().enumerate() {
+                    sid.inner()
+                        .write(rotated_reg(target as u8, local as u8), val);
+                }
+                sid
+            }));
+        }
+        if points == 0 {
+            for sid in self.shadows.iter_mut().flatten() {
+                sid.inner().clock_delta(cycles);
+            }
+            return;
+        }
+        let mut done = 0u32;
+        for p in 0..points {
+            let upto = (cycles as u64 * (p as u64 + 1) / points as u64) as u32;
+            self.points.clear();
+            for sid in self.shadows.iter_mut().flatten() {
+                sid.inner().clock_delta(upto - done);
+                let (osc, env) = (sid.inner().read(0x1B), sid.inner().read(0x1C));
+                self.points.push(voice_scope::voice_point(osc, env));
+            }
+            done = upto;
+            self.scope.push(&self.points);
+        }
+    }
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 //  Shared audio ring buffer  (player pushes, cpal callback pops)
 // ─────────────────────────────────────────────────────────────────────────────
@@ -143,100 +263,82 @@ impl SendSid {
 type AudioBuffer = Arc<Mutex<VecDeque<(i16, i16)>>>;
 
 fn new_audio_buffer() -> AudioBuffer {
-    Arc::new(Mutex::new(VecDeque::with_capacity(MAX_BUFFER_SAMPLES)))
+    Arc::new(Mutex::new(VecDeque::new()))
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
 //  Audio thread  (owns the !Send cpal::Stream)
 // ─────────────────────────────────────────────────────────────────────────────
 
-/// Spawn a dedicated thread for cpal audio output.
-/// Returns the device's actual sample rate on success.
-fn spawn_audio_thread(audio_buf: AudioBuffer, shutdown: Arc<AtomicBool>) -> Result<u32, String> {
-    let (result_tx, result_rx) = std::sync::mpsc::sync_channel::<Result<u32, String>>(1);
+/// Spawn a dedicated thread for cpal audio output on the configured device
+/// (see `audio_output`). Returns the stream's sample rate and the ring
+/// sizes for it on success.
+fn spawn_audio_thread(
+    audio_buf: AudioBuffer,
+    shutdown: Arc<AtomicBool>,
+) -> Result<(u32, RingSizes), String> {
+    let (result_tx, result_rx) =
+        std::sync::mpsc::sync_channel::<Result<(u32, RingSizes), String>>(1);
 
     thread::Builder::new()
         .name("sid-audio".into())
         .spawn(move || {
-            let result = (|| -> Result<(cpal::Stream, u32), String> {
-                let host = cpal::default_host();
-                let device = host
-                    .default_output_device()
-                    .ok_or_else(|| "No audio output device found".to_string())?;
-
-                let dev_name = device.name().unwrap_or_else(|_| "unknown".into());
-
-                let default_config = device
-                    .default_output_config()
-                    .map_err(|e| format!("No default output config: {e}"))?;
-
-                let actual_rate = default_config.sample_rate().0;
-                eprintln!(
-                    "[emulated] Audio device: '{}', native rate: {}Hz",
-                    dev_name, actual_rate,
-                );
-
-                // Pre-fill the ring buffer with ~150ms of silence so the audio
-                // callback has samples to consume while the emulation ramps up.
+            let result = (|| -> Result<(cpal::Stream, u32, RingSizes), String> {
+                let mut output =
+                    crate::audio_output::open(&OutputSettings::load(), None, "emulated")?;
+                let actual_rate = output.config.sample_rate.0;
+                let rings = output.rings;
+
+                // Pre-fill the ring buffer with silence (~150ms unless a latency
+                // target is set) so the audio callback has samples to consume while the emulation ramps up.
                 // The player calls reset() and then sleeps for 50ms + runs setup
                 // code before the first ring_cycled() call. On Windows (WASAPI),
                 // underruns are immediately audible as crackle because WASAPI
                 // has stricter real-time requirements than CoreAudio/PulseAudio.
                 // 150ms > 50ms sleep + ~50ms setup overhead, so the buffer never
-                // runs dry before the first audio frame arrives.
+                // runs dry before the first audio frame arrives. A lower latency
+                // target trades that margin for less delay.
                 {
-                    let prefill = (actual_rate as usize * 150) / 1000; // ~150ms
                     let mut ring = audio_buf.lock().unwrap();
-                    for _ in 0..prefill {
+                    for _ in 0..rings.prefill {
                         ring.push_back((0, 0));
                     }
                 }
 
-                let config = cpal::StreamConfig {
-                    channels: 2,
-                    sample_rate: cpal::SampleRate(actual_rate),
-                    buffer_size: cpal::BufferSize::Default,
-                };
-
                 let buf = audio_buf;
 
-                let stream = device
-                    .build_output_stream(
-                        &config,
-                        move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
-                            let mut ring = buf.lock().unwrap();
-                            let vol = crate::audio_volume::scale();
-                            // data is interleaved [L, R, L, R, ...]
-                            let frames = data.len() / 2;
-                            for f in 0..frames {
-                                let idx = f * 2;
-                                if let Some((l, r)) = ring.pop_front() {
-                                    data[idx] = (l as f32 / 32768.0) * vol;
-                                    data[idx + 1] = (r as f32 / 32768.0) * vol;
-                                } else {
-                                    // Underrun: silence
-                                    data[idx] = 0.0;
-                                    data[idx + 1] = 0.0;
-                                }
+                let stream = output.build_stream(
+                    move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                        let mut ring = buf.lock().unwrap();
+                        let vol = crate::audio_volume::scale();
+                        // data is interleaved [L, R, L, R, ...]
+                        let frames = data.len() / 2;
+                        for f in 0..frames {
+                            let idx = f * 2;
+                            if let Some((l, r)) = ring.pop_front() {
+                                data[idx] = crate::audio_volume::limit((l as f32 / 32768.0) * vol);
+                                data[idx + 1] =
+                                    crate::audio_volume::limit((r as f32 / 32768.0) * vol);
+                            } else {
+                                // Underrun: silence
+                                data[idx] = 0.0;
+                                data[idx + 1] = 0.0;
                             }
-                        },
-                        move |err| {
-                            eprintln!("[emulated] Audio error: {err}");
-                        },
-                        None,
-                    )
-                    .map_err(|e| format!("build_output_stream failed: {e}"))?;
+                        }
+                    },
+                    "emulated",
+                )?;
 
                 stream
                     .play()
                     .map_err(|e| format!("stream.play() failed: {e}"))?;
 
-                Ok((stream, actual_rate))
+                Ok((stream, actual_rate, rings))
             })();
 
             match result {
-                Ok((stream, rate)) => {
-                    let _ = result_tx.send(Ok(rate));
+                Ok((stream, rate, rings)) => {
+                    let _ = result_tx.send(Ok((rate, rings)));
                     // Park this thread: it owns the stream.
                     while !shutdown.load(Ordering::Relaxed) {
                         thread::park_timeout(std::time::Duration::from_millis(100));
@@ -274,8 +376,22 @@ pub struct EmulatedDevice {
     ext4: ExternalFilter,
 
     clock_freq: u32,
+    /// SID clock in percent of `clock_freq`, for the tempo's "pitch
+    /// follows speed" mode. Back to 100 on every `set_clock_rate`.
+    clock_scale: u16,
     sample_rate: u32,
-    chip_model: ChipModel,
+    /// Model per chip slot (SID1–SID4). Chips created later by
+    /// `set_stereo` pick up their slot's model.
+    chip_models: [ChipModel; 4],
+    /// Digi boost / 6581 filter preset for the current tune.
+    tuning: EmuTuning,
+    /// Cutoff registers ($15, $16) per chip as the player wrote them,
+    /// before the 6581 filter preset shifts them.
+    fc_raw: [[u8; 2]; 4],
+    /// Per-chip pan and crossfeed, from the tune's stereo layout.
+    mixer: StereoMixer,
+    /// Per-voice waveforms for the visualiser. `None` when offline.
+    scope: Option<ResidScope>,
 
     cycles_per_frame: u32,
 
@@ -284,6 +400,11 @@ pub struct EmulatedDevice {
 
     audio_buf: AudioBuffer,
     audio_shutdown: Arc<AtomicBool>,
+    /// True when opened via `open_offline()` — no cpal consumer, so the
+    /// ring buffer is neither prefilled nor capped.
+    offline: bool,
+    /// Prefill and cap for the ring, from the output latency setting.
+    rings: RingSizes,
 
     /// Diagnostic frame counter.
     frame_counter: u64,
@@ -311,8 +432,42 @@ impl EmulatedDevice {
         let audio_shutdown = Arc::new(AtomicBool::new(false));
 
         // Spawn audio thread: returns the device's actual sample rate.
-        let sample_rate = spawn_audio_thread(audio_buf.clone(), audio_shutdown.clone())?;
+        let (sample_rate, rings) = spawn_audio_thread(audio_buf.clone(), audio_shutdown.clone())?;
 
+        // Lock the MP3 stream tap's sample rate now so if a browser
+        // subscribes before playback starts, the encoder builds at
+        // the correct rate from the first frame.
+        crate::audio_stream::set_sample_rate(sample_rate);
+
+        Ok(Self::build(
+            sample_rate,
+            audio_buf,
+            audio_shutdown,
+            false,
+            rings,
+        ))
+    }
+
+    /// Open without a cpal stream for offline rendering. Samples pile up
+    /// in the ring buffer (uncapped, no silence prefill) until the caller
+    /// drains them with `take_rendered()`.
+    pub fn open_offline(sample_rate: u32) -> Self {
+        Self::build(
+            sample_rate,
+            new_audio_buffer(),
+            Arc::new(AtomicBool::new(false)),
+            true,
+            RingSizes::new(sample_rate, 0),
+        )
+    }
+
+    fn build(
+        sample_rate: u32,
+        audio_buf: AudioBuffer,
+        audio_shutdown: Arc<AtomicBool>,
+        offline: bool,
+        rings: RingSizes,
+    ) -> Self {
         let chip_model = ChipModel::Mos6581;
         let clock_freq = PAL_CLOCK;
 
@@ -333,17 +488,14 @@ impl EmulatedDevice {
 
         eprintln!(
             "[emulated] SID opened: {}, clock={}Hz, output={}Hz, \
-             resampler=Resample, ExternalFilter=ON",
+             resampler=Resample, ExternalFilter=ON{}",
             chip_model_name(chip_model),
             clock_freq,
             sample_rate,
+            if offline { ", offline" } else { "" },
         );
-        // Lock the MP3 stream tap's sample rate now so if a browser
-        // subscribes before playback starts, the encoder builds at
-        // the correct rate from the first frame.
-        crate::audio_stream::set_sample_rate(sample_rate);
 
-        Ok(Self {
+        Self {
             sid1,
             sid2: None,
             sid3: None,
@@ -353,28 +505,38 @@ impl EmulatedDevice {
             ext3,
             ext4: ExternalFilter::new(),
             clock_freq,
+            clock_scale: 100,
             sample_rate,
-            chip_model,
+            chip_models: [chip_model; 4],
+            tuning: EmuTuning::default(),
+            fc_raw: [[0; 2]; 4],
+            mixer: StereoMixer::new(sample_rate),
+            scope: (!offline).then(|| ResidScope::new(sample_rate, chip_model)),
             cycles_per_frame: PAL_CYCLES_PER_FRAME,
             cycles_this_frame: 0,
             audio_buf,
             audio_shutdown,
+            offline,
+            rings,
             frame_counter: 0,
             carry1: Vec::new(),
             carry2: Vec::new(),
             carry3: Vec::new(),
             carry4: Vec::new(),
-        })
+        }
     }
 
     /// Pre-fill the audio ring buffer with silence to prevent underruns
     /// when the audio callback starts draining before emulation produces data.
-    /// Must be larger than the longest possible gap between reset() and the
-    /// first ring_cycled() call (sleep 50ms + setup overhead ≈ 100ms total).
+    /// At the default latency this is larger than the longest possible gap
+    /// between reset() and the first ring_cycled() call (sleep 50ms + setup
+    /// overhead ≈ 100ms total).
     fn prefill_silence(&self) {
-        let prefill = (self.sample_rate as usize * 150) / 1000; // ~150ms
+        if self.offline {
+            return;
+        }
         if let Ok(mut ring) = self.audio_buf.lock() {
-            for _ in 0..prefill {
+            for _ in 0..self.rings.prefill {
                 ring.push_back((0, 0));
             }
         }
@@ -382,15 +544,49 @@ impl EmulatedDevice {
 
     // ── Internal helpers ─────────────────────────────────────────────────
 
-    fn make_sid(&self) -> SendSid {
-        let mut sid = SendSid::new(self.chip_model);
+    fn make_sid(&self, chip: usize) -> SendSid {
+        let mut sid = SendSid::new(self.chip_models[chip]);
         sid.inner().set_sampling_parameters(
             SamplingMethod::Resample,
-            self.clock_freq,
+            self.sampling_clock(),
             self.sample_rate,
         );
         sid
     }
+
+    fn active_chips(&self) -> usize {
+        1 + self.sid2.is_some() as usize
+            + self.sid3.is_some() as usize
+            + self.sid4.is_some() as usize
+    }
+
+    /// Re-derive the stereo mix for the chips now active.
+    fn configure_mixer(&mut self) {
+        let chips = self.active_chips();
+        self.mixer.configure(&self.tuning, chips, self.sample_rate);
+    }
+
+    /// Clock handed to the resampler: a faster clock yields fewer
+    /// samples per cycle, so the tune plays faster and higher.
+    fn sampling_clock(&self) -> u32 {
+        (self.clock_freq as u64 * self.clock_scale as u64 / 100) as u32
+    }
+
+    /// Reconfigure all SIDs with the current clock-to-sample ratio.
+    fn apply_sampling(&mut self) {
+        let clock = self.sampling_clock();
+        let rate = self.sample_rate;
+        self.sid1
+            .inner()
+            .set_sampling_parameters(SamplingMethod::Resample, clock, rate);
+        for s in [&mut self.sid2, &mut self.sid3, &mut self.sid4]
+            .into_iter()
+            .flatten()
+        {
+            s.inner()
+                .set_sampling_parameters(SamplingMethod::Resample, clock, rate);
+        }
+    }
 }
 
 /// Map the PSID/RSID header's chip-model bits to a concrete `ChipModel`.
@@ -425,29 +621,69 @@ fn chip_models_eq(a: ChipModel, b: ChipModel) -> bool {
 }
 
 impl EmulatedDevice {
-    /// Rebuild every populated SID with a new chip model. Cheap
+    /// Rebuild every populated SID with its slot's chip model. Cheap
     /// (~microseconds — `resid::Sid::new` just resets internal state).
     fn rebuild_sids(&mut self) {
-        self.sid1 = self.make_sid();
+        self.sid1 = self.make_sid(0);
         if self.sid2.is_some() {
-            self.sid2 = Some(self.make_sid());
+            self.sid2 = Some(self.make_sid(1));
         }
         if self.sid3.is_some() {
-            self.sid3 = Some(self.make_sid());
+            self.sid3 = Some(self.make_sid(2));
         }
         if self.sid4.is_some() {
-            self.sid4 = Some(self.make_sid());
+            self.sid4 = Some(self.make_sid(3));
         }
         self.ext1.reset();
         self.ext2.reset();
         self.ext3.reset();
         self.ext4.reset();
+        if let Some(ref mut scope) = self.scope {
+            scope.set_models(self.chip_models);
+        }
         // Fresh resamplers → any queued carry belongs to the old
         // chip's state and would be a discontinuity if mixed in.
         self.carry1.clear();
         self.carry2.clear();
         self.carry3.clear();
         self.carry4.clear();
+        self.apply_digi_boost();
+    }
+
+    /// Feed the digi-boost DC into every 8580 chip (or clear it). The
+    /// external input is chip state, so this follows every rebuild,
+    /// reset and chip creation.
+    fn apply_digi_boost(&mut self) {
+        let boost = self.tuning.digi_boost_8580;
+        let models = self.chip_models;
+        let level = |chip: usize| {
+            if boost && matches!(models[chip], ChipModel::Mos8580) {
+                DIGI_BOOST_INPUT
+            } else {
+                0
+            }
+        };
+        self.sid1.inner().input(level(0));
+        if let Some(ref mut s) = self.sid2 {
+            s.inner().input(level(1));
+        }
+        if let Some(ref mut s) = self.sid3 {
+            s.inner().input(level(2));
+        }
+        if let Some(ref mut s) = self.sid4 {
+            s.inner().input(level(3));
+        }
+    }
+
+    /// Cutoff pair to send to `chip` with the 6581 filter preset
+    /// applied, or `None` when the chip takes the raw value.
+    fn biased_cutoff(&self, chip: usize) -> Option<(u8, u8)> {
+        let offset = self.tuning.filter_6581.cutoff_offset();
+        if offset == 0 || !matches!(self.chip_models[chip], ChipModel::Mos6581) {
+            return None;
+        }
+        let [lo, hi] = self.fc_raw[chip];
+        Some(adjust_cutoff(lo, hi, offset))
     }
 
     /// Clock one SID by `delta` C64 cycles, collect generated samples.
@@ -483,8 +719,23 @@ impl EmulatedDevice {
     /// Route a register write to the correct SID chip.
     ///   0x00-0x1F -> SID1, 0x20-0x3F -> SID2, 0x40-0x5F -> SID3, 0x60-0x7F -> SID4
     fn write_to_sid(&mut self, reg: u8, val: u8) {
+        if let Some(ref mut scope) = self.scope {
+            scope.write(reg, val);
+        }
         let chip = reg / SID_REGS;
         let local = reg % SID_REGS;
+        if chip < 4 && (local == 0x15 || local == 0x16) {
+            self.fc_raw[chip as usize][(local - 0x15) as usize] = val;
+            if let Some((lo, hi)) = self.biased_cutoff(chip as usize) {
+                self.write_chip(chip, 0x15, lo);
+                self.write_chip(chip, 0x16, hi);
+                return;
+            }
+        }
+        self.write_chip(chip, local, val);
+    }
+
+    fn write_chip(&mut self, chip: u8, local: u8, val: u8) {
         match chip {
             0 => self.sid1.inner().write(local, val),
             1 => {
@@ -532,6 +783,9 @@ impl EmulatedDevice {
         if let Some(ref mut sid) = self.sid4 {
             Self::clock_sid(sid, delta, &mut s4);
         }
+        if let Some(ref mut scope) = self.scope {
+            scope.clock(delta, s1.len());
+        }
 
         if s1.is_empty() {
             return;
@@ -604,32 +858,21 @@ impl EmulatedDevice {
         // both the local ring buffer (for cpal) and the global audio
         // stream tap (for the browser MP3 endpoint) in one pass.
         let mut buf = self.audio_buf.lock().unwrap();
-        let room = MAX_BUFFER_SAMPLES.saturating_sub(buf.len());
+        let room = if self.offline {
+            usize::MAX
+        } else {
+            self.rings.capacity.saturating_sub(buf.len())
+        };
         let mix_count = count.min(room);
         let mut mixed: Vec<(i16, i16)> = Vec::with_capacity(mix_count);
 
         for i in 0..mix_count {
-            let left = all1[i];
-            let right = if self.sid2.is_some() {
-                all2[i]
-            } else {
-                left // mono: mirror SID1 to right channel
-            };
-
-            // SID3/SID4 centre-mixed at half volume
-            let mut centre: i16 = 0;
-            if self.sid3.is_some() {
-                centre = centre.saturating_add(all3[i] / 2);
-            }
-            if self.sid4.is_some() {
-                centre = centre.saturating_add(all4[i] / 2);
-            }
-
-            let pair = if centre != 0 {
-                (left.saturating_add(centre), right.saturating_add(centre))
-            } else {
-                (left, right)
-            };
+            let pair = self.mixer.mix([
+                all1[i],
+                all2.get(i).copied().unwrap_or(0),
+                all3.get(i).copied().unwrap_or(0),
+                all4.get(i).copied().unwrap_or(0),
+            ]);
             buf.push_back(pair);
             mixed.push(pair);
         }
@@ -638,7 +881,7 @@ impl EmulatedDevice {
         // Fan the same samples out to the /api/stream.mp3 tap. Fast-path
         // bails out immediately when no browsers are listening — cost
         // is one atomic-bool load.
-        if !mixed.is_empty() {
+        if !mixed.is_empty() && !self.offline {
             crate::audio_stream::push_pairs(&mixed, self.sample_rate);
         }
 
@@ -689,33 +932,8 @@ impl SidDevice for EmulatedDevice {
             NTSC_CYCLES_PER_FRAME
         };
 
-        // Reconfigure all SIDs with the correct clock-to-sample ratio.
-        self.sid1.inner().set_sampling_parameters(
-            SamplingMethod::Resample,
-            self.clock_freq,
-            self.sample_rate,
-        );
-        if let Some(ref mut s) = self.sid2 {
-            s.inner().set_sampling_parameters(
-                SamplingMethod::Resample,
-                self.clock_freq,
-                self.sample_rate,
-            );
-        }
-        if let Some(ref mut s) = self.sid3 {
-            s.inner().set_sampling_parameters(
-                SamplingMethod::Resample,
-                self.clock_freq,
-                self.sample_rate,
-            );
-        }
-        if let Some(ref mut s) = self.sid4 {
-            s.inner().set_sampling_parameters(
-                SamplingMethod::Resample,
-                self.clock_freq,
-                self.sample_rate,
-            );
-        }
+        self.clock_scale = 100;
+        self.apply_sampling();
 
         // ExternalFilter runs at the audio rate, not the C64 clock rate.
         let rate = self.sample_rate as f64;
@@ -733,20 +951,53 @@ impl SidDevice for EmulatedDevice {
         );
     }
 
-    fn set_sid_model(&mut self, model: u8) {
-        let new = chip_model_from_header(model);
-        if chip_models_eq(new, self.chip_model) {
+    fn set_sid_models(&mut self, models: [u8; 4]) {
+        let new = models.map(chip_model_from_header);
+        if new
+            .iter()
+            .zip(&self.chip_models)
+            .all(|(&a, &b)| chip_models_eq(a, b))
+        {
             return;
         }
-        self.chip_model = new;
+        self.chip_models = new;
         self.rebuild_sids();
         eprintln!(
-            "[emulated] SID model → {} (header={})",
-            chip_model_name(new),
-            model
+            "[emulated] SID models → {} (header={:?})",
+            new.map(chip_model_name).join(" / "),
+            models
+        );
+    }
+
+    fn set_emu_tuning(&mut self, tuning: EmuTuning) {
+        if tuning == self.tuning {
+            return;
+        }
+        self.tuning = tuning;
+        self.configure_mixer();
+        self.apply_digi_boost();
+        // Cutoffs already on the chips were biased for the old tuning.
+        for chip in 0..4u8 {
+            let [lo, hi] = self.fc_raw[chip as usize];
+            let (lo, hi) = self.biased_cutoff(chip as usize).unwrap_or((lo, hi));
+            self.write_chip(chip, 0x15, lo);
+            self.write_chip(chip, 0x16, hi);
+        }
+        eprintln!(
+            "[emulated] Tuning: 8580 digi boost {}, 6581 filter {}",
+            if tuning.digi_boost_8580 { "on" } else { "off" },
+            tuning.filter_6581.name()
         );
     }
 
+    fn set_clock_scale(&mut self, pct: u16) -> bool {
+        if pct != self.clock_scale {
+            self.clock_scale = pct;
+            self.apply_sampling();
+        }
+        true
+    }
+
     fn set_cycles_per_frame(&mut self, cycles: u32) {
         if cycles != self.cycles_per_frame {
             eprintln!(
@@ -779,6 +1030,11 @@ impl SidDevice for EmulatedDevice {
         self.carry2.clear();
         self.carry3.clear();
         self.carry4.clear();
+        self.fc_raw = [[0; 2]; 4];
+        if let Some(ref mut scope) = self.scope {
+            scope.reset();
+        }
+        self.apply_digi_boost();
 
         self.cycles_this_frame = 0;
         if let Ok(mut buf) = self.audio_buf.lock() {
@@ -789,27 +1045,41 @@ impl SidDevice for EmulatedDevice {
 
     fn set_stereo(&mut self, mode: i32) {
         if mode >= 1 && self.sid2.is_none() {
-            self.sid2 = Some(self.make_sid());
+            self.sid2 = Some(self.make_sid(1));
             self.ext2.reset();
             eprintln!("[emulated] SID2 enabled");
         }
         if mode >= 2 && self.sid3.is_none() {
-            self.sid3 = Some(self.make_sid());
+            self.sid3 = Some(self.make_sid(2));
             self.ext3.reset();
             eprintln!("[emulated] SID3 enabled");
         }
         if mode >= 3 && self.sid4.is_none() {
-            self.sid4 = Some(self.make_sid());
+            self.sid4 = Some(self.make_sid(3));
             self.ext4.reset();
             eprintln!("[emulated] SID4 enabled");
         }
-        if mode == 0 {
-            self.sid2 = None;
-            self.sid3 = None;
-            self.sid4 = None;
+        if mode >= 1 {
+            self.apply_digi_boost();
+        }
+        // Drop chips above the new count, so a tune with fewer SIDs than
+        // the last one isn't mixed with idle extra chips.
+        if mode < 1 && self.sid2.take().is_some() {
             self.ext2.reset();
+            self.carry2.clear();
+        }
+        if mode < 2 && self.sid3.take().is_some() {
             self.ext3.reset();
+            self.carry3.clear();
+        }
+        if mode < 3 && self.sid4.take().is_some() {
             self.ext4.reset();
+            self.carry4.clear();
+        }
+        self.configure_mixer();
+        let chips = self.active_chips();
+        if let Some(ref mut scope) = self.scope {
+            scope.set_chips(chips);
         }
     }
 
@@ -896,6 +1166,17 @@ impl SidDevice for EmulatedDevice {
         self.close();
         self.audio_shutdown.store(true, Ordering::Relaxed);
     }
+
+    fn has_host_gain(&self) -> bool {
+        true
+    }
+
+    fn take_rendered(&mut self) -> Vec<(i16, i16)> {
+        match self.audio_buf.lock() {
+            Ok(mut buf) => buf.drain(..).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
 }
 
 impl Drop for EmulatedDevice {
@@ -911,6 +1192,18 @@ mod tests {
     use super::*;
 
     // ChipModel has no PartialEq, so match on the variant.
+    #[test]
+    fn scope_shadows_put_the_watched_voice_in_slot_3() {
+        for target in 0..3u8 {
+            // The watched voice's control register lands on voice 3's.
+            assert_eq!(rotated_reg(target, target * 7 + 4), 0x12);
+            // Its sync / ring source stays in the slot before it.
+            let source = (target + 2) % 3;
+            assert_eq!(rotated_reg(target, source * 7 + 4), 0x0B);
+        }
+        assert_eq!(rotated_reg(2, 0x14), 0x14);
+    }
+
     #[test]
     fn model_0_unknown_defaults_to_6581() {
         assert!(matches!(chip_model_from_header(0), ChipModel::Mos6581));
@@ -0,0 +1,788 @@
+// SID write log — capture the exact stream a device receives, replay it
+// on another engine.
+//
+// `RecordingDevice` sits between the player and every engine opened by
+// `ensure_hardware`. While a log is armed it copies each device call —
+// the cycle-stamped `ring_cycled` batches exactly as `send_sid_writes`
+// produced them, plain writes, frame flushes, and clock / stereo / model
+// / cycles-per-frame changes — into a compact binary file. Recording
+// starts at the next device reset (i.e. the next tune or subtune load)
+// and ends at the reset after that, so one log holds one playthrough.
+//
+// `ReplayDevice` is the "replay" engine: it wraps another engine and,
+// instead of the writes the player sends, feeds that engine one logged
+// frame per `flush()`. Frame pacing still comes from the player, so the
+// log only plays under the tune, song and clock it was recorded with —
+// anything else is refused — and the player skips seek and tempo while
+// replaying. The stream reaching the target engine is then identical to
+// the original, cycle stamps and all.
+//
+// File layout: "PSWL", version byte, the header (HVSC MD5 as 32 ASCII
+// hex digits, song as u16 LE, clock byte: 1 = PAL), then tagged events.
+// Counts and cycle deltas are LEB128 varints — a typical frame is a few
+// dozen bytes.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::sid_device::SidDevice;
+
+/// File extension used by the save / open dialogs.
+pub const EXTENSION: &str = "sidlog";
+
+const MAGIC: &[u8; 4] = b"PSWL";
+const VERSION: u8 = 2;
+/// MAGIC + VERSION + MD5 + song + clock.
+const HEADER_LEN: usize = 5 + 32 + 2 + 1;
+
+const TAG_CLOCK: u8 = 0x01;
+const TAG_RESET: u8 = 0x02;
+const TAG_STEREO: u8 = 0x03;
+const TAG_CYCLES: u8 = 0x05;
+const TAG_WRITE: u8 = 0x06;
+const TAG_RING: u8 = 0x07;
+const TAG_FLUSH: u8 = 0x08;
+/// Per-chip models, SID1..SID4.
+const TAG_MODELS: u8 = 0x09;
+
+/// The playthrough a log holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogHeader {
+    /// HVSC MD5 of the tune, lowercase hex.
+    pub md5: String,
+    /// 1-based song.
+    pub song: u16,
+    pub pal: bool,
+}
+
+impl LogHeader {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let mut md5 = [b'0'; 32];
+        for (d, s) in md5.iter_mut().zip(self.md5.bytes()) {
+            *d = s;
+        }
+        out.extend(md5);
+        out.extend(self.song.to_le_bytes());
+        out.push(self.pal as u8);
+    }
+
+    /// What differs between this header and the tune being played, if
+    /// anything.
+    fn mismatch(&self, md5: &str, song: u16, pal: bool) -> Option<String> {
+        let clock = |pal: bool| if pal { "PAL" } else { "NTSC" };
+        if !self.md5.eq_ignore_ascii_case(md5) {
+            Some(format!("log is of tune {}, not {md5}", self.md5))
+        } else if self.song != song {
+            Some(format!("log is of song {}, not {song}", self.song))
+        } else if self.pal != pal {
+            Some(format!(
+                "log was recorded at {}, tune plays at {}",
+                clock(self.pal),
+                clock(pal)
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// One recorded device call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogEvent {
+    Clock {
+        pal: bool,
+    },
+    Reset,
+    Stereo(i32),
+    SidModels([u8; 4]),
+    CyclesPerFrame(u32),
+    Write(u8, u8),
+    /// `(delta_cycles, reg, val)` batch as passed to `ring_cycled`.
+    Ring(Vec<(u16, u8, u8)>),
+    /// End of a frame.
+    Flush,
+}
+
+fn put_varint(out: &mut Vec<u8>, mut v: u32) {
+    loop {
+        let byte = (v & 0x7F) as u8;
+        v >>= 7;
+        if v == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn encode(ev: &LogEvent, out: &mut Vec<u8>) {
+    match ev {
+        LogEvent::Clock { pal } => out.extend([TAG_CLOCK, *pal as u8]),
+        LogEvent::Reset => out.push(TAG_RESET),
+        LogEvent::Stereo(mode) => {
+            out.push(TAG_STEREO);
+            out.extend(mode.to_le_bytes());
+        }
+        LogEvent::SidModels(m) => {
+            out.push(TAG_MODELS);
+            out.extend(m);
+        }
+        LogEvent::CyclesPerFrame(c) => {
+            out.push(TAG_CYCLES);
+            put_varint(out, *c);
+        }
+        LogEvent::Write(reg, val) => out.extend([TAG_WRITE, *reg, *val]),
+        LogEvent::Ring(writes) => {
+            out.push(TAG_RING);
+            put_varint(out, writes.len() as u32);
+            for &(delta, reg, val) in writes {
+                put_varint(out, delta as u32);
+                out.extend([reg, val]);
+            }
+        }
+        LogEvent::Flush => out.push(TAG_FLUSH),
+    }
+}
+
+/// Byte cursor over a log file.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Result<u8, String> {
+        let b = *self
+            .data
+            .get(self.pos)
+            .ok_or_else(|| format!("Truncated log at byte {}", self.pos))?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn varint(&mut self) -> Result<u32, String> {
+        let mut v: u32 = 0;
+        for shift in (0..35).step_by(7) {
+            let b = self.byte()?;
+            v |= ((b & 0x7F) as u32) << shift;
+            if b & 0x80 == 0 {
+                return Ok(v);
+            }
+        }
+        Err(format!("Bad varint at byte {}", self.pos))
+    }
+}
+
+/// Parse a complete log file.
+pub fn decode(data: &[u8]) -> Result<(LogHeader, Vec<LogEvent>), String> {
+    if data.len() < 5 || &data[..4] != MAGIC {
+        return Err("Not a SID write log".into());
+    }
+    if data[4] != VERSION {
+        return Err(format!("Unsupported SID write log version {}", data[4]));
+    }
+    if data.len() < HEADER_LEN {
+        return Err("Truncated SID write log header".into());
+    }
+    let md5 = std::str::from_utf8(&data[5..37])
+        .ok()
+        .filter(|m| m.bytes().all(|b| b.is_ascii_hexdigit()))
+        .ok_or("Bad tune MD5 in SID write log header")?;
+    let header = LogHeader {
+        md5: md5.to_ascii_lowercase(),
+        song: u16::from_le_bytes([data[37], data[38]]),
+        pal: data[39] != 0,
+    };
+    let mut r = Reader {
+        data,
+        pos: HEADER_LEN,
+    };
+    let mut events = Vec::new();
+    while r.pos < data.len() {
+        let ev = match r.byte()? {
+            TAG_CLOCK => LogEvent::Clock {
+                pal: r.byte()? != 0,
+            },
+            TAG_RESET => LogEvent::Reset,
+            TAG_STEREO => {
+                let b = [r.byte()?, r.byte()?, r.byte()?, r.byte()?];
+                LogEvent::Stereo(i32::from_le_bytes(b))
+            }
+            TAG_MODELS => LogEvent::SidModels([r.byte()?, r.byte()?, r.byte()?, r.byte()?]),
+            TAG_CYCLES => LogEvent::CyclesPerFrame(r.varint()?),
+            TAG_WRITE => LogEvent::Write(r.byte()?, r.byte()?),
+            TAG_RING => {
+                let n = r.varint()? as usize;
+                let mut writes = Vec::with_capacity(n.min(4096));
+                for _ in 0..n {
+                    let delta = r.varint()?.min(0xFFFF) as u16;
+                    writes.push((delta, r.byte()?, r.byte()?));
+                }
+                LogEvent::Ring(writes)
+            }
+            TAG_FLUSH => LogEvent::Flush,
+            tag => return Err(format!("Unknown event 0x{tag:02X} at byte {}", r.pos - 1)),
+        };
+        events.push(ev);
+    }
+    Ok((header, events))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Recorder
+// ─────────────────────────────────────────────────────────────────────────────
+
+struct Recorder {
+    path: PathBuf,
+    out: BufWriter<File>,
+    /// False until the first reset — the log starts at a tune load.
+    started: bool,
+    /// Clock and tune seen before the start; setup_playback sets both
+    /// just ahead of the reset, and they become the header.
+    pending_clock: Option<bool>,
+    pending_tune: Option<(String, u16)>,
+    frames: u64,
+    buf: Vec<u8>,
+}
+
+/// Fast-path flag so `RecordingDevice` skips the lock when idle.
+static ARMED: AtomicBool = AtomicBool::new(false);
+static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+/// Create `path` and start logging at the next tune load. Replaces any
+/// recording already in progress.
+pub fn arm(path: &Path) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("Cannot create {}: {e}", path.display()))?;
+    let mut out = BufWriter::new(file);
+    out.write_all(MAGIC)
+        .and_then(|_| out.write_all(&[VERSION]))
+        .map_err(|e| format!("Cannot write {}: {e}", path.display()))?;
+    stop();
+    *RECORDER.lock().unwrap_or_else(|e| e.into_inner()) = Some(Recorder {
+        path: path.to_path_buf(),
+        out,
+        started: false,
+        pending_clock: None,
+        pending_tune: None,
+        frames: 0,
+        buf: Vec::new(),
+    });
+    ARMED.store(true, Ordering::Relaxed);
+    eprintln!("[sid-log] Armed → {}", path.display());
+    Ok(())
+}
+
+/// Finish the current recording, if any. Returns the file and the
+/// number of frames it holds.
+pub fn stop() -> Option<(PathBuf, u64)> {
+    let mut guard = RECORDER.lock().unwrap_or_else(|e| e.into_inner());
+    ARMED.store(false, Ordering::Relaxed);
+    let mut rec = guard.take()?;
+    if let Err(e) = rec.out.flush() {
+        eprintln!("[sid-log] Flush failed: {e}");
+    }
+    eprintln!(
+        "[sid-log] Recorded {} frames → {}",
+        rec.frames,
+        rec.path.display()
+    );
+    Some((rec.path, rec.frames))
+}
+
+/// True while a log is armed or being written.
+pub fn is_recording() -> bool {
+    ARMED.load(Ordering::Relaxed)
+}
+
+/// Note the tune the next reset loads; it heads the log if recording
+/// starts there.
+fn record_tune(md5: &str, song: u16) {
+    let mut guard = RECORDER.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(rec) = guard.as_mut().filter(|r| !r.started) {
+        rec.pending_tune = Some((md5.to_string(), song));
+    }
+}
+
+fn record(ev: LogEvent) {
+    let mut guard = RECORDER.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(rec) = guard.as_mut() else {
+        return;
+    };
+    if !rec.started {
+        match ev {
+            LogEvent::Clock { pal } => rec.pending_clock = Some(pal),
+            LogEvent::Reset => {
+                rec.started = true;
+                let (md5, song) = rec.pending_tune.take().unwrap_or_default();
+                let header = LogHeader {
+                    md5,
+                    song,
+                    pal: rec.pending_clock.take().unwrap_or(true),
+                };
+                rec.buf.clear();
+                header.encode(&mut rec.buf);
+                if let Err(e) = rec.out.write_all(&rec.buf) {
+                    eprintln!("[sid-log] Write failed: {e}");
+                }
+                write_event(rec, &ev);
+            }
+            _ => {}
+        }
+        return;
+    }
+    if ev == LogEvent::Reset {
+        // Next tune (or stop) — this playthrough is complete.
+        drop(guard);
+        stop();
+        return;
+    }
+    if ev == LogEvent::Flush {
+        rec.frames += 1;
+    }
+    write_event(rec, &ev);
+}
+
+fn write_event(rec: &mut Recorder, ev: &LogEvent) {
+    rec.buf.clear();
+    encode(ev, &mut rec.buf);
+    if let Err(e) = rec.out.write_all(&rec.buf) {
+        eprintln!("[sid-log] Write failed: {e}");
+    }
+}
+
+/// Pass-through wrapper that feeds the recorder. Installed around every
+/// live engine; costs one atomic load per call while no log is armed.
+pub struct RecordingDevice {
+    inner: Box<dyn SidDevice>,
+}
+
+impl RecordingDevice {
+    pub fn wrap(inner: Box<dyn SidDevice>) -> Box<dyn SidDevice> {
+        Box::new(Self { inner })
+    }
+}
+
+macro_rules! log_if_armed {
+    ($ev:expr) => {
+        if ARMED.load(Ordering::Relaxed) {
+            record($ev);
+        }
+    };
+}
+
+impl SidDevice for RecordingDevice {
+    fn init(&mut self) -> Result<(), String> {
+        self.inner.init()
+    }
+    fn set_clock_rate(&mut self, is_pal: bool) {
+        log_if_armed!(LogEvent::Clock { pal: is_pal });
+        self.inner.set_clock_rate(is_pal);
+    }
+    fn reset(&mut self) {
+        log_if_armed!(LogEvent::Reset);
+        self.inner.reset();
+    }
+    fn set_tune(&mut self, md5: &str, song: u16) {
+        if ARMED.load(Ordering::Relaxed) {
+            record_tune(md5, song);
+        }
+        self.inner.set_tune(md5, song);
+    }
+    fn set_stereo(&mut self, mode: i32) {
+        log_if_armed!(LogEvent::Stereo(mode));
+        self.inner.set_stereo(mode);
+    }
+    fn write(&mut self, reg: u8, val: u8) {
+        log_if_armed!(LogEvent::Write(reg, val));
+        self.inner.write(reg, val);
+    }
+    fn ring_cycled(&mut self, writes: &[(u16, u8, u8)]) {
+        log_if_armed!(LogEvent::Ring(writes.to_vec()));
+        self.inner.ring_cycled(writes);
+    }
+    fn flush(&mut self) {
+        log_if_armed!(LogEvent::Flush);
+        self.inner.flush();
+    }
+    fn mute(&mut self) {
+        self.inner.mute();
+    }
+    fn close(&mut self) {
+        self.inner.close();
+    }
+    fn shutdown(&mut self) {
+        self.inner.shutdown();
+    }
+    fn reinit(&mut self) -> Result<(), String> {
+        self.inner.reinit()
+    }
+    fn set_cycles_per_frame(&mut self, cycles: u32) {
+        log_if_armed!(LogEvent::CyclesPerFrame(cycles));
+        self.inner.set_cycles_per_frame(cycles);
+    }
+    fn set_sid_models(&mut self, models: [u8; 4]) {
+        log_if_armed!(LogEvent::SidModels(models));
+        self.inner.set_sid_models(models);
+    }
+    fn set_emu_tuning(&mut self, tuning: crate::emu_tuning::EmuTuning) {
+        self.inner.set_emu_tuning(tuning);
+    }
+    fn set_clock_scale(&mut self, pct: u16) -> bool {
+        self.inner.set_clock_scale(pct)
+    }
+    fn has_host_gain(&self) -> bool {
+        self.inner.has_host_gain()
+    }
+    fn take_rendered(&mut self) -> Vec<(i16, i16)> {
+        self.inner.take_rendered()
+    }
+    fn play_sid_native(&mut self, data: &[u8], song: u16) -> Result<bool, String> {
+        self.inner.play_sid_native(data, song)
+    }
+    fn pause_machine(&mut self) -> Result<(), String> {
+        self.inner.pause_machine()
+    }
+    fn resume_machine(&mut self) -> Result<(), String> {
+        self.inner.resume_machine()
+    }
+    fn start_audio(&mut self, port: u16) -> Result<(), String> {
+        self.inner.start_audio(port)
+    }
+    fn stop_audio(&mut self) {
+        self.inner.stop_audio();
+    }
+    fn read_screen_elapsed(&mut self) -> Option<u32> {
+        self.inner.read_screen_elapsed()
+    }
+    fn read_screen_total(&mut self) -> Option<u32> {
+        self.inner.read_screen_total()
+    }
+    fn run_device_config(
+        &mut self,
+        op: &crate::player::DeviceConfigCmd,
+    ) -> Result<Option<crate::ui::DeviceConfigSnapshot>, String> {
+        self.inner.run_device_config(op)
+    }
+    fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Replay engine
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// The "replay" engine: plays a recorded log on `inner`, one frame per
+/// `flush()`, ignoring whatever the player itself writes.
+pub struct ReplayDevice {
+    inner: Box<dyn SidDevice>,
+    header: LogHeader,
+    events: Vec<LogEvent>,
+    pos: usize,
+    finished: bool,
+    /// Tune and clock the player is loading, checked against `header`
+    /// at each reset.
+    tune: Option<(String, u16)>,
+    clock: Option<bool>,
+}
+
+impl ReplayDevice {
+    pub fn open(path: &Path, inner: Box<dyn SidDevice>) -> Result<Self, String> {
+        let data =
+            std::fs::read(path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+        let (header, events) = decode(&data)?;
+        let frames = events.iter().filter(|e| **e == LogEvent::Flush).count();
+        eprintln!(
+            "[sid-log] Replaying {} ({frames} frames of {} song {})",
+            path.display(),
+            header.md5,
+            header.song
+        );
+        Ok(Self::from_events(header, events, inner))
+    }
+
+    fn from_events(header: LogHeader, events: Vec<LogEvent>, inner: Box<dyn SidDevice>) -> Self {
+        Self {
+            inner,
+            header,
+            events,
+            pos: 0,
+            finished: false,
+            tune: None,
+            clock: None,
+        }
+    }
+
+    /// Apply events through the next frame boundary. With `prelude`,
+    /// stop before the first frame's own events instead.
+    fn advance(&mut self, prelude: bool) {
+        while let Some(ev) = self.events.get(self.pos) {
+            let frame_event = matches!(
+                ev,
+                LogEvent::Ring(_) | LogEvent::CyclesPerFrame(_) | LogEvent::Flush
+            );
+            if prelude && frame_event {
+                return;
+            }
+            self.pos += 1;
+            match ev {
+                LogEvent::Clock { pal } => self.inner.set_clock_rate(*pal),
+                LogEvent::Reset => self.inner.reset(),
+                LogEvent::Stereo(mode) => self.inner.set_stereo(*mode),
+                LogEvent::SidModels(m) => self.inner.set_sid_models(*m),
+                LogEvent::CyclesPerFrame(c) => self.inner.set_cycles_per_frame(*c),
+                LogEvent::Write(reg, val) => self.inner.write(*reg, *val),
+                LogEvent::Ring(writes) => self.inner.ring_cycled(writes),
+                LogEvent::Flush => {
+                    self.inner.flush();
+                    return;
+                }
+            }
+        }
+        if !self.finished {
+            self.finished = true;
+            eprintln!("[sid-log] Replay finished");
+            self.inner.mute();
+        }
+        self.inner.flush();
+    }
+}
+
+impl SidDevice for ReplayDevice {
+    fn init(&mut self) -> Result<(), String> {
+        self.inner.init()
+    }
+    // Clock, stereo, model and writes come from the log, not the player;
+    // the player's clock and tune are only checked against the header.
+    fn set_clock_rate(&mut self, is_pal: bool) {
+        self.clock = Some(is_pal);
+    }
+    fn set_tune(&mut self, md5: &str, song: u16) {
+        self.tune = Some((md5.to_string(), song));
+    }
+    fn set_stereo(&mut self, _mode: i32) {}
+    fn write(&mut self, _reg: u8, _val: u8) {}
+    fn ring_cycled(&mut self, _writes: &[(u16, u8, u8)]) {}
+    fn set_cycles_per_frame(&mut self, _cycles: u32) {}
+    fn set_sid_models(&mut self, _models: [u8; 4]) {}
+    /// Tuning isn't part of the log, so the target engine follows the
+    /// loaded tune's settings.
+    fn set_emu_tuning(&mut self, tuning: crate::emu_tuning::EmuTuning) {
+        self.inner.set_emu_tuning(tuning);
+    }
+    fn set_clock_scale(&mut self, pct: u16) -> bool {
+        self.inner.set_clock_scale(pct)
+    }
+    /// A tune load rewinds the log and replays its prelude (clock,
+    /// reset, stereo, INIT writes) up to the first frame — unless the
+    /// player loaded a different tune, song or clock, in which case the
+    /// target stays silent.
+    fn reset(&mut self) {
+        self.pos = 0;
+        let wrong = match (&self.tune, self.clock) {
+            (Some((md5, song)), Some(pal)) => self.header.mismatch(md5, *song, pal),
+            _ => None,
+        };
+        if let Some(why) = wrong {
+            eprintln!("[sid-log] Not replaying: {why}");
+            self.pos = self.events.len();
+            self.finished = true;
+            self.inner.reset();
+            self.inner.mute();
+            return;
+        }
+        self.finished = false;
+        self.inner.set_clock_rate(self.header.pal);
+        self.advance(true);
+    }
+    fn flush(&mut self) {
+        self.advance(false);
+    }
+    fn mute(&mut self) {
+        self.inner.mute();
+    }
+    fn close(&mut self) {
+        self.inner.close();
+    }
+    fn shutdown(&mut self) {
+        self.inner.shutdown();
+    }
+    fn reinit(&mut self) -> Result<(), String> {
+        self.inner.reinit()
+    }
+    fn has_host_gain(&self) -> bool {
+        self.inner.has_host_gain()
+    }
+    fn take_rendered(&mut self) -> Vec<(i16, i16)> {
+        self.inner.take_rendered()
+    }
+    fn run_device_config(
+        &mut self,
+        op: &crate::player::DeviceConfigCmd,
+    ) -> Result<Option<crate::ui::DeviceConfigSnapshot>, String> {
+        self.inner.run_device_config(op)
+    }
+    fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex as StdMutex};
+
+    /// Device that records what reaches it as log events.
+    struct Probe(Arc<StdMutex<Vec<LogEvent>>>);
+
+    impl SidDevice for Probe {
+        fn init(&mut self) -> Result<(), String> {
+            Ok(())
+        }
+        fn set_clock_rate(&mut self, is_pal: bool) {
+            self.0.lock().unwrap().push(LogEvent::Clock { pal: is_pal });
+        }
+        fn reset(&mut self) {
+            self.0.lock().unwrap().push(LogEvent::Reset);
+        }
+        fn set_stereo(&mut self, mode: i32) {
+            self.0.lock().unwrap().push(LogEvent::Stereo(mode));
+        }
+        fn write(&mut self, reg: u8, val: u8) {
+            self.0.lock().unwrap().push(LogEvent::Write(reg, val));
+        }
+        fn ring_cycled(&mut self, writes: &[(u16, u8, u8)]) {
+            self.0.lock().unwrap().push(LogEvent::Ring(writes.to_vec()));
+        }
+        fn flush(&mut self) {
+            self.0.lock().unwrap().push(LogEvent::Flush);
+        }
+        fn mute(&mut self) {}
+        fn close(&mut self) {}
+        fn shutdown(&mut self) {}
+    }
+
+    fn sample_header() -> LogHeader {
+        LogHeader {
+            md5: "0123456789abcdef0123456789abcdef".into(),
+            song: 3,
+            pal: false,
+        }
+    }
+
+    fn sample_log() -> Vec<LogEvent> {
+        vec![
+            LogEvent::Reset,
+            LogEvent::SidModels([2, 1, 2, 2]),
+            LogEvent::Stereo(-1),
+            LogEvent::Write(0x18, 0x0F),
+            LogEvent::CyclesPerFrame(17_045),
+            LogEvent::Ring(vec![
+                (0, 0x04, 0x41),
+                (300, 0x0B, 0x11),
+                (0xFFFF, 0x18, 0x1F),
+            ]),
+            LogEvent::Flush,
+            LogEvent::Ring(vec![(12, 0x04, 0x40)]),
+            LogEvent::Flush,
+        ]
+    }
+
+    fn sample_data() -> Vec<u8> {
+        let mut data = MAGIC.to_vec();
+        data.push(VERSION);
+        sample_header().encode(&mut data);
+        for ev in &sample_log() {
+            encode(ev, &mut data);
+        }
+        data
+    }
+
+    #[test]
+    fn encode_decode_roundtrip() {
+        let data = sample_data();
+        assert_eq!(decode(&data).unwrap(), (sample_header(), sample_log()));
+        assert!(decode(b"RIFF\x01").is_err());
+        assert!(decode(&data[..data.len() - 2]).is_err());
+    }
+
+    #[test]
+    fn header_roundtrip_and_validation() {
+        let data = sample_data();
+        assert_eq!(&data[5..37], b"0123456789abcdef0123456789abcdef");
+        assert_eq!(&data[37..HEADER_LEN], &[3, 0, 0]);
+        assert_eq!(decode(&data[..HEADER_LEN]).unwrap().0, sample_header());
+        assert!(decode(&data[..HEADER_LEN - 1]).is_err());
+        let mut bad = data.clone();
+        bad[5] = b'z';
+        assert!(decode(&bad).is_err());
+        let mut v1 = data;
+        v1[4] = 1;
+        assert!(decode(&v1).is_err());
+    }
+
+    #[test]
+    fn replay_refuses_a_different_tune() {
+        let seen = Arc::new(StdMutex::new(Vec::new()));
+        let h = sample_header();
+        let mut dev =
+            ReplayDevice::from_events(h.clone(), sample_log(), Box::new(Probe(seen.clone())));
+        dev.set_clock_rate(false);
+        dev.set_tune(&h.md5, 1);
+        dev.reset();
+        dev.flush();
+        assert_eq!(
+            seen.lock().unwrap().as_slice(),
+            &[LogEvent::Reset, LogEvent::Flush]
+        );
+        assert!(h.mismatch(&h.md5.to_uppercase(), 3, false).is_none());
+        assert!(h.mismatch(&h.md5, 3, true).is_some());
+        assert!(h
+            .mismatch("ffffffffffffffffffffffffffffffff", 3, false)
+            .is_some());
+    }
+
+    #[test]
+    fn replay_steps_one_frame_per_flush() {
+        let seen = Arc::new(StdMutex::new(Vec::new()));
+        let h = sample_header();
+        let mut dev =
+            ReplayDevice::from_events(h.clone(), sample_log(), Box::new(Probe(seen.clone())));
+        dev.set_clock_rate(false);
+        dev.set_tune(&h.md5, h.song);
+        dev.reset();
+        // Player's own writes never reach the target.
+        dev.write(0x18, 0x00);
+        assert_eq!(
+            seen.lock().unwrap().as_slice(),
+            &[
+                LogEvent::Clock { pal: false },
+                LogEvent::Reset,
+                LogEvent::Stereo(-1),
+                LogEvent::Write(0x18, 0x0F),
+            ]
+        );
+        seen.lock().unwrap().clear();
+        dev.flush();
+        dev.flush();
+        assert_eq!(
+            seen.lock().unwrap().as_slice(),
+            &[
+                LogEvent::Ring(vec![
+                    (0, 0x04, 0x41),
+                    (300, 0x0B, 0x11),
+                    (0xFFFF, 0x18, 0x1F)
+                ]),
+                LogEvent::Flush,
+                LogEvent::Ring(vec![(12, 0x04, 0x40)]),
+                LogEvent::Flush,
+            ]
+        );
+        // Past the end: keeps flushing so the target stays paced.
+        dev.flush();
+        assert_eq!(seen.lock().unwrap().last(), Some(&LogEvent::Flush));
+    }
+}
@@ -0,0 +1,298 @@
+// A/B mirror — drive two engines from one write stream.
+//
+// `MirrorDevice` forwards every device call to a primary engine and a
+// secondary one (e.g. USBSID-Pico hardware and reSID), so both play the
+// tune in lock-step. Only one is audible: the other gets every $D418
+// write with the master-volume nibble cleared, which silences a real
+// chip and an emulated one alike while the filter-mode bits keep its
+// state identical. Switching sides (the `A` hotkey) re-sends the latest
+// $D418 of each chip to both, so the swap is instant and the tune keeps
+// playing.
+//
+// The audible side is a process-wide flag, like the write-log `ARMED`
+// flag in `sid_log`: the GUI flips it directly and the device picks it
+// up on its next call, and a reopened device starts on the same side.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::sid_device::SidDevice;
+
+/// Register offset of the volume / filter-mode register within a chip.
+const VOL_REG: u8 = 0x18;
+const SID_REGS: u8 = 0x20;
+
+static SECONDARY_AUDIBLE: AtomicBool = AtomicBool::new(false);
+/// Display names of the open mirror's (primary, secondary) engines.
+static ACTIVE: Mutex<Option<(String, String)>> = Mutex::new(None);
+
+/// Switch which engine of the mirror pair is heard.
+pub fn toggle_audible() {
+    let secondary = !SECONDARY_AUDIBLE.fetch_xor(true, Ordering::Relaxed);
+    eprintln!(
+        "[mirror] Audible: {}",
+        if secondary { "secondary" } else { "primary" }
+    );
+}
+
+/// Label for the track-info bar while a mirror is open, e.g.
+/// "A/B — hearing reSID (USB muted)".
+pub fn status_label() -> Option<String> {
+    let active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner());
+    let (primary, secondary) = active.as_ref()?;
+    let (heard, muted) = if SECONDARY_AUDIBLE.load(Ordering::Relaxed) {
+        (secondary, primary)
+    } else {
+        (primary, secondary)
+    };
+    Some(format!("A/B — hearing {heard} ({muted} muted)"))
+}
+
+/// Short engine name for `status_label`.
+pub fn engine_label(name: &str) -> &str {
+    match name {
+        "usb" => "USB",
+        "emulated" => "reSID",
+        "sidlite" => "SIDLite",
+        other => other,
+    }
+}
+
+pub struct MirrorDevice {
+    /// Primary, secondary.
+    devs: [Box<dyn SidDevice>; 2],
+    /// Index into `devs` of the engine currently heard.
+    audible: usize,
+    /// Last $D418 value per chip as the player wrote it.
+    vol_regs: [u8; 4],
+    /// Chips in use, from `set_stereo`.
+    chips: usize,
+}
+
+impl MirrorDevice {
+    pub fn new(
+        primary: Box<dyn SidDevice>,
+        secondary: Box<dyn SidDevice>,
+        names: (&str, &str),
+    ) -> Self {
+        *ACTIVE.lock().unwrap_or_else(|e| e.into_inner()) = Some((
+            engine_label(names.0).to_string(),
+            engine_label(names.1).to_string(),
+        ));
+        Self {
+            devs: [primary, secondary],
+            audible: SECONDARY_AUDIBLE.load(Ordering::Relaxed) as usize,
+            vol_regs: [0; 4],
+            chips: 1,
+        }
+    }
+
+    /// Value `dev` should receive for a write of `val` to `reg`.
+    fn value_for(&self, dev: usize, reg: u8, val: u8) -> u8 {
+        if dev != self.audible && reg % SID_REGS == VOL_REG {
+            val & 0xF0
+        } else {
+            val
+        }
+    }
+
+    fn note_write(&mut self, reg: u8, val: u8) {
+        let chip = (reg / SID_REGS) as usize;
+        if reg % SID_REGS == VOL_REG && chip < self.vol_regs.len() {
+            self.vol_regs[chip] = val;
+        }
+    }
+
+    /// Pick up a hotkey swap: re-send each chip's $D418 so the newly
+    /// audible side gets its volume back and the other drops to zero.
+    fn sync_audible(&mut self) {
+        let want = SECONDARY_AUDIBLE.load(Ordering::Relaxed) as usize;
+        if want == self.audible {
+            return;
+        }
+        self.audible = want;
+        for chip in 0..self.chips {
+            let reg = chip as u8 * SID_REGS + VOL_REG;
+            for dev in 0..2 {
+                let val = self.value_for(dev, reg, self.vol_regs[chip]);
+                self.devs[dev].write(reg, val);
+            }
+        }
+    }
+}
+
+impl Drop for MirrorDevice {
+    fn drop(&mut self) {
+        *ACTIVE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+impl SidDevice for MirrorDevice {
+    fn init(&mut self) -> Result<(), String> {
+        self.devs[0].init()?;
+        self.devs[1].init()
+    }
+    fn set_clock_rate(&mut self, is_pal: bool) {
+        for dev in &mut self.devs {
+            dev.set_clock_rate(is_pal);
+        }
+    }
+    fn reset(&mut self) {
+        self.vol_regs = [0; 4];
+        for dev in &mut self.devs {
+            dev.reset();
+        }
+    }
+    fn set_stereo(&mut self, mode: i32) {
+        self.chips = (mode + 1).clamp(1, 4) as usize;
+        for dev in &mut self.devs {
+            dev.set_stereo(mode);
+        }
+    }
+    fn write(&mut self, reg: u8, val: u8) {
+        self.sync_audible();
+        self.note_write(reg, val);
+        for dev in 0..2 {
+            let v = self.value_for(dev, reg, val);
+            self.devs[dev].write(reg, v);
+        }
+    }
+    fn ring_cycled(&mut self, writes: &[(u16, u8, u8)]) {
+        self.sync_audible();
+        for &(_, reg, val) in writes {
+            self.note_write(reg, val);
+        }
+        let silent = 1 - self.audible;
+        let masked: Vec<(u16, u8, u8)> = writes
+            .iter()
+            .map(|&(delta, reg, val)| (delta, reg, self.value_for(silent, reg, val)))
+            .collect();
+        self.devs[self.audible].ring_cycled(writes);
+        self.devs[silent].ring_cycled(&masked);
+    }
+    fn flush(&mut self) {
+        for dev in &mut self.devs {
+            dev.flush();
+        }
+    }
+    fn mute(&mut self) {
+        for dev in &mut self.devs {
+            dev.mute();
+        }
+    }
+    fn close(&mut self) {
+        for dev in &mut self.devs {
+            dev.close();
+        }
+    }
+    fn shutdown(&mut self) {
+        for dev in &mut self.devs {
+            dev.shutdown();
+        }
+    }
+    fn reinit(&mut self) -> Result<(), String> {
+        self.devs[0].reinit()?;
+        self.devs[1].reinit()
+    }
+    fn set_cycles_per_frame(&mut self, cycles: u32) {
+        for dev in &mut self.devs {
+            dev.set_cycles_per_frame(cycles);
+        }
+    }
+    fn set_sid_models(&mut self, models: [u8; 4]) {
+        for dev in &mut self.devs {
+            dev.set_sid_models(models);
+        }
+    }
+    fn set_emu_tuning(&mut self, tuning: crate::emu_tuning::EmuTuning) {
+        for dev in &mut self.devs {
+            dev.set_emu_tuning(tuning);
+        }
+    }
+    /// Both sides must follow, or they'd drift apart; a hardware side
+    /// keeps the pair on stretched frames.
+    fn set_clock_scale(&mut self, pct: u16) -> bool {
+        let both = self.devs.iter_mut().all(|d| d.set_clock_scale(pct));
+        if !both {
+            for dev in &mut self.devs {
+                dev.set_clock_scale(100);
+            }
+        }
+        both
+    }
+    /// Fades follow whichever side is heard: host gain for a software
+    /// engine, $D418 ramps (sent to both) for hardware.
+    fn has_host_gain(&self) -> bool {
+        self.devs[self.audible].has_host_gain()
+    }
+    /// Device config goes to whichever side is the USB engine.
+    fn run_device_config(
+        &mut self,
+        op: &crate::player::DeviceConfigCmd,
+    ) -> Result<Option<crate::ui::DeviceConfigSnapshot>, String> {
+        self.devs[0]
+            .run_device_config(op)
+            .or_else(|_| self.devs[1].run_device_config(op))
+    }
+    fn is_connected(&self) -> bool {
+        self.devs.iter().all(|d| d.is_connected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Device that keeps the last value written to each register.
+    struct Probe(Arc<Mutex<[u8; 0x80]>>);
+
+    impl SidDevice for Probe {
+        fn init(&mut self) -> Result<(), String> {
+            Ok(())
+        }
+        fn set_clock_rate(&mut self, _is_pal: bool) {}
+        fn reset(&mut self) {}
+        fn set_stereo(&mut self, _mode: i32) {}
+        fn write(&mut self, reg: u8, val: u8) {
+            self.0.lock().unwrap()[reg as usize] = val;
+        }
+        fn ring_cycled(&mut self, writes: &[(u16, u8, u8)]) {
+            for &(_, reg, val) in writes {
+                self.write(reg, val);
+            }
+        }
+        fn flush(&mut self) {}
+        fn mute(&mut self) {}
+        fn close(&mut self) {}
+        fn shutdown(&mut self) {}
+    }
+
+    #[test]
+    fn only_audible_side_gets_volume_and_swap_restores_it() {
+        let a = Arc::new(Mutex::new([0u8; 0x80]));
+        let b = Arc::new(Mutex::new([0u8; 0x80]));
+        let mut dev = MirrorDevice::new(
+            Box::new(Probe(a.clone())),
+            Box::new(Probe(b.clone())),
+            ("usb", "emulated"),
+        );
+        dev.audible = 0;
+        dev.set_stereo(1);
+        dev.ring_cycled(&[(0, 0x04, 0x41), (8, 0x18, 0x1F), (8, 0x38, 0x2C)]);
+        assert_eq!(a.lock().unwrap()[0x18], 0x1F);
+        assert_eq!(b.lock().unwrap()[0x18], 0x10);
+        assert_eq!(b.lock().unwrap()[0x38], 0x20);
+        assert_eq!(b.lock().unwrap()[0x04], 0x41);
+
+        // Swap to the secondary; the next call picks it up. Flip the
+        // process-wide flag back afterwards.
+        toggle_audible();
+        dev.write(0x05, 0x09);
+        toggle_audible();
+        assert_eq!(a.lock().unwrap()[0x18], 0x10);
+        assert_eq!(a.lock().unwrap()[0x38], 0x20);
+        assert_eq!(b.lock().unwrap()[0x18], 0x1F);
+        assert_eq!(b.lock().unwrap()[0x38], 0x2C);
+    }
+}
@@ -8,10 +8,13 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::traits::StreamTrait;
 use sidlite_sys::{ChipModel, Sid};
 
+use crate::audio_output::{OutputSettings, RingSizes};
+use crate::emu_tuning::{adjust_cutoff, EmuTuning};
 use crate::sid_device::SidDevice;
+use crate::stereo_mix::StereoMixer;
 
 // ─────────────────────────────────────────────────────────────────────────────
 //  Constants
@@ -23,8 +26,12 @@ const PAL_CYCLES_PER_FRAME: u32 = 19_656;
 const NTSC_CYCLES_PER_FRAME: u32 = 17_095;
 
 const SID_REGS: u8 = 0x20;
-const MAX_BUFFER_SAMPLES: usize = 12288;
+/// Highest output rate SIDLite's resampler accepts.
+pub const MAX_SAMPLE_RATE: u32 = 48_000;
 const SCRATCH_SIZE: usize = 2048;
+/// 8580 digi boost: DC added per step of the $D418 volume nibble.
+/// SIDLite has no external input, so the step is mixed in here.
+const DIGI_BOOST_STEP: i16 = -640;
 
 // ─────────────────────────────────────────────────────────────────────────────
 //  ExternalFilter — C64 mainboard RC output stage (same as sid_emulated.rs)
@@ -81,90 +88,77 @@ impl ExternalFilter {
 type AudioBuffer = Arc<Mutex<VecDeque<(i16, i16)>>>;
 
 fn new_audio_buffer() -> AudioBuffer {
-    Arc::new(Mutex::new(VecDeque::with_capacity(MAX_BUFFER_SAMPLES)))
+    Arc::new(Mutex::new(VecDeque::new()))
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
 //  Audio thread (owns the !Send cpal::Stream)
 // ─────────────────────────────────────────────────────────────────────────────
 
-fn spawn_audio_thread(audio_buf: AudioBuffer, shutdown: Arc<AtomicBool>) -> Result<u32, String> {
-    let (result_tx, result_rx) = std::sync::mpsc::sync_channel::<Result<u32, String>>(1);
+/// Open the configured output (see `audio_output`), capped at
+/// `MAX_SAMPLE_RATE` so the stream runs at the rate SIDLite renders.
+fn spawn_audio_thread(
+    audio_buf: AudioBuffer,
+    shutdown: Arc<AtomicBool>,
+) -> Result<(u32, RingSizes), String> {
+    let (result_tx, result_rx) =
+        std::sync::mpsc::sync_channel::<Result<(u32, RingSizes), String>>(1);
 
     thread::Builder::new()
         .name("sidlite-audio".into())
         .spawn(move || {
-            let result = (|| -> Result<(cpal::Stream, u32), String> {
-                let host = cpal::default_host();
-                let device = host
-                    .default_output_device()
-                    .ok_or_else(|| "No audio output device found".to_string())?;
-
-                let dev_name = device.name().unwrap_or_else(|_| "unknown".into());
-                let default_config = device
-                    .default_output_config()
-                    .map_err(|e| format!("No default output config: {e}"))?;
-
-                let actual_rate = default_config.sample_rate().0;
-                eprintln!(
-                    "[sidlite] Audio device: '{}', native rate: {}Hz",
-                    dev_name, actual_rate,
-                );
-
-                // Pre-fill ring buffer with ~150ms silence to avoid startup crackle.
+            let result = (|| -> Result<(cpal::Stream, u32, RingSizes), String> {
+                let mut output = crate::audio_output::open(
+                    &OutputSettings::load(),
+                    Some(MAX_SAMPLE_RATE),
+                    "sidlite",
+                )?;
+                let actual_rate = output.config.sample_rate.0;
+                let rings = output.rings;
+
+                // Pre-fill ring buffer with silence (~150ms by default) to avoid
+                // startup crackle.
                 // The player sleeps 50ms after reset() plus runs setup code before
                 // the first ring_cycled() call. Windows WASAPI makes underruns
                 // immediately audible; 150ms covers the full startup window.
                 {
-                    let prefill = (actual_rate as usize * 150) / 1000;
                     let mut ring = audio_buf.lock().unwrap();
-                    for _ in 0..prefill {
+                    for _ in 0..rings.prefill {
                         ring.push_back((0, 0));
                     }
                 }
 
-                let config = cpal::StreamConfig {
-                    channels: 2,
-                    sample_rate: cpal::SampleRate(actual_rate),
-                    buffer_size: cpal::BufferSize::Default,
-                };
-
                 let buf = audio_buf;
-                let stream = device
-                    .build_output_stream(
-                        &config,
-                        move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
-                            let mut ring = buf.lock().unwrap();
-                            let vol = crate::audio_volume::scale();
-                            let frames = data.len() / 2;
-                            for f in 0..frames {
-                                let idx = f * 2;
-                                if let Some((l, r)) = ring.pop_front() {
-                                    data[idx] = (l as f32 / 32768.0) * vol;
-                                    data[idx + 1] = (r as f32 / 32768.0) * vol;
-                                } else {
-                                    data[idx] = 0.0;
-                                    data[idx + 1] = 0.0;
-                                }
+                let stream = output.build_stream(
+                    move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                        let mut ring = buf.lock().unwrap();
+                        let vol = crate::audio_volume::scale();
+                        let frames = data.len() / 2;
+                        for f in 0..frames {
+                            let idx = f * 2;
+                            if let Some((l, r)) = ring.pop_front() {
+                                data[idx] = crate::audio_volume::limit((l as f32 / 32768.0) * vol);
+                                data[idx + 1] =
+                                    crate::audio_volume::limit((r as f32 / 32768.0) * vol);
+                            } else {
+                                data[idx] = 0.0;
+                                data[idx + 1] = 0.0;
                             }
-                        },
-                        move |err| {
-                            eprintln!("[sidlite] Audio error: {err}");
-                        },
-                        None,
-                    )
-                    .map_err(|e| format!("build_output_stream failed: {e}"))?;
+                        }
+                    },
+                    "sidlite",
+                )?;
 
                 stream
                     .play()
                     .map_err(|e| format!("stream.play() failed: {e}"))?;
 
-                Ok((stream, actual_rate))
+                Ok((stream, actual_rate, rings))
             })();
 
             match result {
-                Ok((stream, rate)) => {
-                    let _ = result_tx.send(Ok(rate));
+                Ok((stream, rate, rings)) => {
+                    let _ = result_tx.send(Ok((rate, rings)));
                     while !shutdown.load(Ordering::Relaxed) {
                         thread::park_timeout(std::time::Duration::from_millis(100));
                     }
@@ -199,14 +193,29 @@ pub struct SidLiteDevice {
     ext4: ExternalFilter,
 
     clock_freq: u32,
+    /// SID clock in percent of `clock_freq` ("pitch follows speed").
+    clock_scale: u16,
     sample_rate: u32,
-    chip_model: ChipModel,
+    /// Model per chip slot (SID1–SID4), set from the tune header.
+    chip_models: [ChipModel; 4],
+    /// Digi boost / 6581 filter preset for the current tune.
+    tuning: EmuTuning,
+    /// Cutoff registers ($15, $16) per chip as the player wrote them.
+    fc_raw: [[u8; 2]; 4],
+    /// Last $D418 volume nibble per chip, for the digi-boost DC.
+    volumes: [u8; 4],
+    /// Per-chip pan and crossfeed (see `EmulatedDevice::mixer`).
+    mixer: StereoMixer,
 
     cycles_per_frame: u32,
     cycles_this_frame: u32,
 
     audio_buf: AudioBuffer,
     audio_shutdown: Arc<AtomicBool>,
+    /// Opened via `open_offline()`: no prefill, no ring-buffer cap.
+    offline: bool,
+    /// Prefill and cap for the ring, from the output latency setting.
+    rings: RingSizes,
 
     frame_counter: u64,
 }
@@ -216,11 +225,38 @@ impl SidLiteDevice {
         let audio_buf = new_audio_buffer();
         let audio_shutdown = Arc::new(AtomicBool::new(false));
 
-        let sample_rate = spawn_audio_thread(audio_buf.clone(), audio_shutdown.clone())?;
+        let (sample_rate, rings) = spawn_audio_thread(audio_buf.clone(), audio_shutdown.clone())?;
+
+        let dev = Self::build(sample_rate, audio_buf, audio_shutdown, false, rings);
+        // Lock the MP3 stream tap's rate now so a browser subscribing
+        // before playback begins gets the encoder built at the actual
+        // cpal rate (not the 48kHz default).
+        crate::audio_stream::set_sample_rate(dev.sample_rate);
+        Ok(dev)
+    }
+
+    /// Open without a cpal stream for offline rendering (see
+    /// `EmulatedDevice::open_offline`). Rates above 48 kHz are clamped.
+    pub fn open_offline(sample_rate: u32) -> Self {
+        Self::build(
+            sample_rate,
+            new_audio_buffer(),
+            Arc::new(AtomicBool::new(false)),
+            true,
+            RingSizes::new(sample_rate, 0),
+        )
+    }
 
+    fn build(
+        sample_rate: u32,
+        audio_buf: AudioBuffer,
+        audio_shutdown: Arc<AtomicBool>,
+        offline: bool,
+        rings: RingSizes,
+    ) -> Self {
         // SIDLite supports sample rates up to 48000.
         // If the device rate exceeds that, clamp to 48000.
-        let effective_rate = sample_rate.min(48000) as u16;
+        let effective_rate = sample_rate.min(MAX_SAMPLE_RATE) as u16;
 
         let chip_model = ChipModel::Mos6581;
         let clock_freq = PAL_CLOCK;
@@ -232,15 +268,14 @@ impl SidLiteDevice {
         ext1.set_clock_frequency(sample_rate as f64);
 
         eprintln!(
-            "[sidlite] SID opened: MOS6581, clock={}Hz, output={}Hz (device={}Hz), ExternalFilter=ON",
-            clock_freq, effective_rate, sample_rate,
+            "[sidlite] SID opened: MOS6581, clock={}Hz, output={}Hz (device={}Hz), ExternalFilter=ON{}",
+            clock_freq,
+            effective_rate,
+            sample_rate,
+            if offline { ", offline" } else { "" },
         );
-        // Lock the MP3 stream tap's rate now so a browser subscribing
-        // before playback begins gets the encoder built at the actual
-        // cpal rate (not the 48kHz default).
-        crate::audio_stream::set_sample_rate(effective_rate as u32);
 
-        Ok(Self {
+        Self {
             sid1,
             sid2: None,
             sid3: None,
@@ -250,31 +285,87 @@ impl SidLiteDevice {
             ext3: ExternalFilter::new(),
             ext4: ExternalFilter::new(),
             clock_freq,
+            clock_scale: 100,
             sample_rate: effective_rate as u32,
-            chip_model,
+            chip_models: [chip_model; 4],
+            tuning: EmuTuning::default(),
+            fc_raw: [[0; 2]; 4],
+            volumes: [0; 4],
+            mixer: StereoMixer::new(effective_rate as u32),
             cycles_per_frame: PAL_CYCLES_PER_FRAME,
             cycles_this_frame: 0,
             audio_buf,
             audio_shutdown,
+            offline,
+            rings,
             frame_counter: 0,
-        })
+        }
     }
 
     fn prefill_silence(&self) {
-        let prefill = (self.sample_rate as usize * 150) / 1000; // ~150ms
+        if self.offline {
+            return;
+        }
         if let Ok(mut ring) = self.audio_buf.lock() {
-            for _ in 0..prefill {
+            for _ in 0..self.rings.prefill {
                 ring.push_back((0, 0));
             }
         }
     }
 
-    fn make_sid(&self) -> Sid {
-        let mut sid = Sid::new(self.chip_model);
-        sid.set_sampling_parameters(self.clock_freq, self.sample_rate as u16);
+    fn make_sid(&self, chip: usize) -> Sid {
+        let mut sid = Sid::new(self.chip_models[chip]);
+        sid.set_sampling_parameters(self.sampling_clock(), self.sample_rate as u16);
         sid
     }
 
+    fn active_chips(&self) -> usize {
+        1 + self.sid2.is_some() as usize
+            + self.sid3.is_some() as usize
+            + self.sid4.is_some() as usize
+    }
+
+    /// See `EmulatedDevice::configure_mixer`.
+    fn configure_mixer(&mut self) {
+        let chips = self.active_chips();
+        self.mixer.configure(&self.tuning, chips, self.sample_rate);
+    }
+
+    /// See `EmulatedDevice::sampling_clock`.
+    fn sampling_clock(&self) -> u32 {
+        (self.clock_freq as u64 * self.clock_scale as u64 / 100) as u32
+    }
+
+    fn apply_sampling(&mut self) {
+        let clock = self.sampling_clock();
+        let rate = self.sample_rate as u16;
+        self.sid1.set_sampling_parameters(clock, rate);
+        for s in [&mut self.sid2, &mut self.sid3, &mut self.sid4]
+            .into_iter()
+            .flatten()
+        {
+            s.set_sampling_parameters(clock, rate);
+        }
+    }
+
+    /// Rebuild every populated SID with its slot's chip model.
+    fn rebuild_sids(&mut self) {
+        self.sid1 = self.make_sid(0);
+        if self.sid2.is_some() {
+            self.sid2 = Some(self.make_sid(1));
+        }
+        if self.sid3.is_some() {
+            self.sid3 = Some(self.make_sid(2));
+        }
+        if self.sid4.is_some() {
+            self.sid4 = Some(self.make_sid(3));
+        }
+        self.ext1.reset();
+        self.ext2.reset();
+        self.ext3.reset();
+        self.ext4.reset();
+    }
+
     fn clock_sid(sid: &mut Sid, delta: u32, out: &mut Vec<i16>) {
         if delta == 0 {
             return;
@@ -286,9 +377,47 @@ impl SidLiteDevice {
         }
     }
 
+    /// Cutoff pair to send to `chip` with the 6581 filter preset
+    /// applied, or `None` when the chip takes the raw value.
+    fn biased_cutoff(&self, chip: usize) -> Option<(u8, u8)> {
+        let offset = self.tuning.filter_6581.cutoff_offset();
+        if offset == 0 || !matches!(self.chip_models[chip], ChipModel::Mos6581) {
+            return None;
+        }
+        let [lo, hi] = self.fc_raw[chip];
+        Some(adjust_cutoff(lo, hi, offset))
+    }
+
+    /// Digi-boost DC for `chip` at its current volume (0 when off).
+    fn boost_dc(&self, chip: usize) -> i16 {
+        if self.tuning.digi_boost_8580 && matches!(self.chip_models[chip], ChipModel::Mos8580) {
+            self.volumes[chip] as i16 * DIGI_BOOST_STEP
+        } else {
+            0
+        }
+    }
+
     fn write_to_sid(&mut self, reg: u8, val: u8) {
         let chip = reg / SID_REGS;
         let local = reg % SID_REGS;
+        if chip < 4 {
+            match local {
+                0x15 | 0x16 => {
+                    self.fc_raw[chip as usize][(local - 0x15) as usize] = val;
+                    if let Some((lo, hi)) = self.biased_cutoff(chip as usize) {
+                        self.write_chip(chip, 0x15, lo);
+                        self.write_chip(chip, 0x16, hi);
+                        return;
+                    }
+                }
+                0x18 => self.volumes[chip as usize] = val & 0x0F,
+                _ => {}
+            }
+        }
+        self.write_chip(chip, local, val);
+    }
+
+    fn write_chip(&mut self, chip: u8, local: u8, val: u8) {
         match chip {
             0 => self.sid1.write(local, val),
             1 => {
@@ -330,42 +459,46 @@ impl SidLiteDevice {
         if let Some(ref mut sid) = self.sid4 {
             Self::clock_sid(sid, delta, &mut s4);
         }
-
         if s1.is_empty() {
             return;
         }
 
-        let filtered1: Vec<i16> = s1.iter().map(|&s| self.ext1.clock(s)).collect();
-        let filtered2: Vec<i16> = s2.iter().map(|&s| self.ext2.clock(s)).collect();
-        let filtered3: Vec<i16> = s3.iter().map(|&s| self.ext3.clock(s)).collect();
-        let filtered4: Vec<i16> = s4.iter().map(|&s| self.ext4.clock(s)).collect();
+        // Digi-boost DC goes in ahead of the RC stage, whose high-pass
+        // turns the volume steps back into the sample waveform.
+        let dc = [0, 1, 2, 3].map(|chip| self.boost_dc(chip));
+        let filtered1: Vec<i16> = s1
+            .iter()
+            .map(|&s| self.ext1.clock(s.saturating_add(dc[0])))
+            .collect();
+        let filtered2: Vec<i16> = s2
+            .iter()
+            .map(|&s| self.ext2.clock(s.saturating_add(dc[1])))
+            .collect();
+        let filtered3: Vec<i16> = s3
+            .iter()
+            .map(|&s| self.ext3.clock(s.saturating_add(dc[2])))
+            .collect();
+        let filtered4: Vec<i16> = s4
+            .iter()
+            .map(|&s| self.ext4.clock(s.saturating_add(dc[3])))
+            .collect();
 
         let mut buf = self.audio_buf.lock().unwrap();
-        let room = MAX_BUFFER_SAMPLES.saturating_sub(buf.len());
+        let room = if self.offline {
+            usize::MAX
+        } else {
+            self.rings.capacity.saturating_sub(buf.len())
+        };
         let count = filtered1.len().min(room);
         let mut mixed: Vec<(i16, i16)> = Vec::with_capacity(count);
 
         for i in 0..count {
-            let left = filtered1[i];
-            let right = if !filtered2.is_empty() {
-                *filtered2.get(i).unwrap_or(&0)
-            } else {
-                left
-            };
-
-            let mut centre: i16 = 0;
-            if !filtered3.is_empty() {
-                centre = centre.saturating_add(*filtered3.get(i).unwrap_or(&0) / 2);
-            }
-            if !filtered4.is_empty() {
-                centre = centre.saturating_add(*filtered4.get(i).unwrap_or(&0) / 2);
-            }
-
-            let pair = if centre != 0 {
-                (left.saturating_add(centre), right.saturating_add(centre))
-            } else {
-                (left, right)
-            };
+            let pair = self.mixer.mix([
+                filtered1[i],
+                filtered2.get(i).copied().unwrap_or(0),
+                filtered3.get(i).copied().unwrap_or(0),
+                filtered4.get(i).copied().unwrap_or(0),
+            ]);
             buf.push_back(pair);
             mixed.push(pair);
         }
@@ -373,12 +506,21 @@ impl SidLiteDevice {
 
         // Also fan out to the /api/stream.mp3 tap. Fast-path bails when
         // no browsers are listening.
-        if !mixed.is_empty() {
+        if !mixed.is_empty() && !self.offline {
             crate::audio_stream::push_pairs(&mixed, self.sample_rate);
         }
     }
 }
 
+/// Header model bits → SIDLite chip. Same mapping as the reSID engine's
+/// `chip_model_from_header`: only an explicit 8580 gets one.
+fn chip_model_from_header(model_bits: u8) -> ChipModel {
+    match model_bits {
+        2 => ChipModel::Mos8580,
+        _ => ChipModel::Mos6581,
+    }
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 //  SidDevice trait implementation
 // ─────────────────────────────────────────────────────────────────────────────
@@ -396,17 +538,8 @@ impl SidDevice for SidLiteDevice {
             NTSC_CYCLES_PER_FRAME
         };
 
-        let rate = self.sample_rate as u16;
-        self.sid1.set_sampling_parameters(self.clock_freq, rate);
-        if let Some(ref mut s) = self.sid2 {
-            s.set_sampling_parameters(self.clock_freq, rate);
-        }
-        if let Some(ref mut s) = self.sid3 {
-            s.set_sampling_parameters(self.clock_freq, rate);
-        }
-        if let Some(ref mut s) = self.sid4 {
-            s.set_sampling_parameters(self.clock_freq, rate);
-        }
+        self.clock_scale = 100;
+        self.apply_sampling();
 
         let rate = self.sample_rate as f64;
         self.ext1.set_clock_frequency(rate);
@@ -423,6 +556,54 @@ impl SidDevice for SidLiteDevice {
         );
     }
 
+    fn set_sid_models(&mut self, models: [u8; 4]) {
+        let is_8580 = |m: &ChipModel| matches!(m, ChipModel::Mos8580);
+        let new = models.map(chip_model_from_header);
+        if new
+            .iter()
+            .map(is_8580)
+            .eq(self.chip_models.iter().map(is_8580))
+        {
+            return;
+        }
+        self.chip_models = new;
+        self.rebuild_sids();
+        eprintln!(
+            "[sidlite] SID models → {} (header={:?})",
+            new.map(|m| if is_8580(&m) { "MOS8580" } else { "MOS6581" })
+                .join(" / "),
+            models
+        );
+    }
+
+    fn set_emu_tuning(&mut self, tuning: EmuTuning) {
+        if tuning == self.tuning {
+            return;
+        }
+        self.tuning = tuning;
+        self.configure_mixer();
+        // Cutoffs already on the chips were biased for the old tuning.
+        for chip in 0..4u8 {
+            let [lo, hi] = self.fc_raw[chip as usize];
+            let (lo, hi) = self.biased_cutoff(chip as usize).unwrap_or((lo, hi));
+            self.write_chip(chip, 0x15, lo);
+            self.write_chip(chip, 0x16, hi);
+        }
+        eprintln!(
+            "[sidlite] Tuning: 8580 digi boost {}, 6581 filter {}",
+            if tuning.digi_boost_8580 { "on" } else { "off" },
+            tuning.filter_6581.name()
+        );
+    }
+
+    fn set_clock_scale(&mut self, pct: u16) -> bool {
+        if pct != self.clock_scale {
+            self.clock_scale = pct;
+            self.apply_sampling();
+        }
+        true
+    }
+
     fn set_cycles_per_frame(&mut self, cycles: u32) {
         if cycles != self.cycles_per_frame {
             eprintln!(
@@ -448,6 +629,8 @@ impl SidDevice for SidLiteDevice {
         self.ext2.reset();
         self.ext3.reset();
         self.ext4.reset();
+        self.fc_raw = [[0; 2]; 4];
+        self.volumes = [0; 4];
 
         self.cycles_this_frame = 0;
         if let Ok(mut buf) = self.audio_buf.lock() {
@@ -458,28 +641,31 @@ impl SidDevice for SidLiteDevice {
 
     fn set_stereo(&mut self, mode: i32) {
         if mode >= 1 && self.sid2.is_none() {
-            self.sid2 = Some(self.make_sid());
+            self.sid2 = Some(self.make_sid(1));
             self.ext2.reset();
             eprintln!("[sidlite] SID2 enabled");
         }
         if mode >= 2 && self.sid3.is_none() {
-            self.sid3 = Some(self.make_sid());
+            self.sid3 = Some(self.make_sid(2));
             self.ext3.reset();
             eprintln!("[sidlite] SID3 enabled");
         }
         if mode >= 3 && self.sid4.is_none() {
-            self.sid4 = Some(self.make_sid());
+            self.sid4 = Some(self.make_sid(3));
             self.ext4.reset();
             eprintln!("[sidlite] SID4 enabled");
         }
-        if mode == 0 {
-            self.sid2 = None;
-            self.sid3 = None;
-            self.sid4 = None;
+        // Drop chips above the new count (see `EmulatedDevice::set_stereo`).
+        if mode < 1 && self.sid2.take().is_some() {
             self.ext2.reset();
+        }
+        if mode < 2 && self.sid3.take().is_some() {
             self.ext3.reset();
+        }
+        if mode < 3 && self.sid4.take().is_some() {
             self.ext4.reset();
         }
+        self.configure_mixer();
     }
 
     fn write(&mut self, reg: u8, val: u8) {
@@ -533,6 +719,7 @@ impl SidDevice for SidLiteDevice {
         if let Some(ref mut s) = self.sid4 {
             s.write(0x18, 0x00);
         }
+        self.volumes = [0; 4];
         self.ext1.reset();
         self.ext2.reset();
         self.ext3.reset();
@@ -554,6 +741,17 @@ impl SidDevice for SidLiteDevice {
         self.close();
         self.audio_shutdown.store(true, Ordering::Relaxed);
     }
+
+    fn has_host_gain(&self) -> bool {
+        true
+    }
+
+    fn take_rendered(&mut self) -> Vec<(i16, i16)> {
+        match self.audio_buf.lock() {
+            Ok(mut buf) => buf.drain(..).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
 }
 
 impl Drop for SidLiteDevice {
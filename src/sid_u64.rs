@@ -9,13 +9,15 @@
 // When enabled via config (u64_audio_enabled), Phosphor asks the U64 to stream
 // its audio output as UDP packets to our local port.  Two threads handle this:
 //
+//   cpal output   — drains the ring buffer into the configured audio device
+//                   (see `audio_output`).  Owns the !Send cpal::Stream so it
+//                   lives in its own thread.  Started first: its rate and
+//                   latency setting size the jitter buffer.
+//
 //   UDP receiver  — binds the port, receives packets, resamples PAL ~47983 Hz
 //                   → host device rate, pushes f32 stereo into a shared ring
 //                   buffer with jitter management.
 //
-//   cpal output   — drains the ring buffer into the system audio device.  Owns
-//                   the !Send cpal::Stream so it lives in its own thread.
-//
 // Packet format (from Ultimate 64 firmware):
 //   bytes 0-1  : sequence number (u16 LE) — used for gap detection only
 //   bytes 2..  : i16 LE stereo samples interleaved (L R L R …)
@@ -29,10 +31,11 @@ use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::traits::StreamTrait;
 use ultimate64::Rest;
 use url::Host;
 
+use crate::audio_output::OutputSettings;
 use crate::sid_device::SidDevice;
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -45,17 +48,16 @@ const U64_SAMPLE_RATE_PAL: f64 = 47_982.886_904_761_9;
 /// Audio packet header size (sequence number only).
 const AUDIO_HEADER: usize = 2;
 
-/// Jitter buffer: don't start playback until this many samples are buffered.
-/// ~100 ms at 48 kHz stereo.
-const JITTER_MIN: usize = 9_600;
+/// Jitter buffer: don't start playback until this much is buffered, unless
+/// a latency target is set. Overflow trims back to twice the start level.
+const JITTER_MIN_MS: u32 = 100;
 
-/// Jitter buffer: target fill level — trim back to this when overflowing.
-/// ~200 ms at 48 kHz stereo.
-const JITTER_TARGET: usize = 19_200;
+/// Floor for the latency target on the network path — UDP arrival jitter
+/// needs more slack than a local engine.
+const JITTER_FLOOR_MS: u32 = 40;
 
 /// Jitter buffer: hard maximum before we start dropping.
-/// ~1 s at 48 kHz stereo.
-const JITTER_MAX: usize = 96_000;
+const JITTER_MAX_MS: u32 = 1_000;
 
 // ─────────────────────────────────────────────────────────────────────────────
 //  Linear resampler  (U64 PAL ~47983 Hz → host device rate)
@@ -104,19 +106,41 @@ impl Resampler {
 
 struct AudioRing {
     samples: VecDeque<f32>,
-    ready: bool, // true once we've buffered JITTER_MIN samples
+    ready: bool, // true once we've buffered jitter_min samples
     last_seq: Option<u16>,
     gaps: u64,
+    /// Jitter thresholds in interleaved f32 samples, sized by `set_output`.
+    jitter_min: usize,
+    jitter_target: usize,
+    jitter_max: usize,
 }
 
 impl AudioRing {
     fn new() -> Self {
-        Self {
-            samples: VecDeque::with_capacity(JITTER_MAX),
+        let mut ring = Self {
+            samples: VecDeque::new(),
             ready: false,
             last_seq: None,
             gaps: 0,
-        }
+            jitter_min: 0,
+            jitter_target: 0,
+            jitter_max: 0,
+        };
+        ring.set_output(48_000, 0);
+        ring
+    }
+
+    /// Size the jitter thresholds for the output rate and latency target.
+    fn set_output(&mut self, rate: u32, latency_ms: u32) {
+        let ms = if latency_ms == 0 {
+            JITTER_MIN_MS
+        } else {
+            latency_ms.max(JITTER_FLOOR_MS)
+        };
+        let samples = |ms: u32| (rate as u64 * ms as u64 / 1000) as usize * 2;
+        self.jitter_min = samples(ms);
+        self.jitter_target = samples(ms * 2);
+        self.jitter_max = samples(JITTER_MAX_MS).max(self.jitter_target * 2);
     }
 }
 
@@ -138,6 +162,103 @@ impl AudioStream {
         let stop = Arc::new(AtomicBool::new(false));
         let ring: SharedRing = Arc::new(Mutex::new(AudioRing::new()));
 
+        // ── cpal output thread ────────────────────────────────────────────────
+        let audio_stop = stop.clone();
+        let audio_ring = ring.clone();
+        let (init_tx, init_rx) = std::sync::mpsc::sync_channel::<Result<u32, String>>(1);
+
+        let audio_handle = thread::Builder::new()
+            .name("u64-audio-out".into())
+            .spawn(move || {
+                let result = (|| -> Result<(cpal::Stream, u32), String> {
+                    let settings = OutputSettings::load();
+                    let mut output = crate::audio_output::open(&settings, None, "u64-audio")?;
+                    let rate = output.config.sample_rate.0;
+                    let channels = output.config.channels as usize;
+
+                    // Size the jitter buffer for this rate; the net thread
+                    // resamples to it as well.
+                    if let Ok(mut r) = audio_ring.lock() {
+                        r.set_output(rate, settings.latency_ms);
+                    }
+
+                    let ring_cb = audio_ring.clone();
+
+                    let stream = output.build_stream(
+                        move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                            if let Ok(mut r) = ring_cb.lock() {
+                                // Jitter buffer: output silence until buffered enough.
+                                if !r.ready {
+                                    if r.samples.len() >= r.jitter_min {
+                                        r.ready = true;
+                                        eprintln!(
+                                            "[u64-audio] Jitter buffer ready ({} samples)",
+                                            r.samples.len()
+                                        );
+                                    } else {
+                                        data.fill(0.0);
+                                        return;
+                                    }
+                                }
+                                let vol = crate::audio_volume::scale();
+                                // Stereo pairs; upmix to N channels if needed.
+                                let frames = data.len() / channels;
+                                for f in 0..frames {
+                                    let l = crate::audio_volume::limit(
+                                        r.samples.pop_front().unwrap_or(0.0) * vol,
+                                    );
+                                    let r_samp = crate::audio_volume::limit(
+                                        r.samples.pop_front().unwrap_or(0.0) * vol,
+                                    );
+                                    let base = f * channels;
+                                    data[base] = l;
+                                    if channels > 1 {
+                                        data[base + 1] = r_samp;
+                                    }
+                                    for ch in 2..channels {
+                                        data[base + ch] = 0.0;
+                                    }
+                                }
+                            } else {
+                                data.fill(0.0);
+                            }
+                        },
+                        "u64-audio",
+                    )?;
+
+                    stream.play().map_err(|e| format!("stream.play(): {e}"))?;
+                    Ok((stream, rate))
+                })();
+
+                match result {
+                    Ok((stream, rate)) => {
+                        let _ = init_tx.send(Ok(rate));
+                        // Park here; the stream keeps playing as long as this
+                        // thread is alive and owns the stream.
+                        while !audio_stop.load(Ordering::Relaxed) {
+                            thread::park_timeout(Duration::from_millis(100));
+                        }
+                        drop(stream);
+                        eprintln!("[u64-audio] cpal thread stopped");
+                    }
+                    Err(e) => {
+                        eprintln!("[u64-audio] cpal init failed: {e}");
+                        let _ = init_tx.send(Err(e));
+                    }
+                }
+            })
+            .map_err(|e| format!("spawn audio thread: {e}"))?;
+
+        // Wait for cpal to initialise (or fail) before asking the U64 to
+        // stream — the resampler needs the output rate.
+        let out_rate = match init_rx.recv_timeout(Duration::from_secs(5)) {
+            Ok(result) => result?,
+            Err(_) => {
+                stop.store(true, Ordering::Relaxed);
+                return Err("Audio thread did not respond in time".into());
+            }
+        };
+
         // ── UDP receiver thread ───────────────────────────────────────────────
         let net_stop = stop.clone();
         let net_ring = ring.clone();
@@ -209,7 +330,7 @@ impl AudioStream {
                 }
 
                 let mut buf        = [0u8; 2048];
-                let mut resampler  = Resampler::new(U64_SAMPLE_RATE_PAL, 48_000.0);
+                let mut resampler  = Resampler::new(U64_SAMPLE_RATE_PAL, out_rate as f64);
                 let mut first      = true;
 
                 loop {
@@ -253,8 +374,8 @@ impl AudioStream {
                                 resampler.push(&samples, &mut ring.samples);
 
                                 // Overflow protection: trim to target
-                                if ring.samples.len() > JITTER_MAX {
-                                    let drop = ring.samples.len() - JITTER_TARGET;
+                                if ring.samples.len() > ring.jitter_max {
+                                    let drop = ring.samples.len() - ring.jitter_target;
                                     ring.samples.drain(..drop);
                                 }
                             }
@@ -276,117 +397,6 @@ impl AudioStream {
             })
             .map_err(|e| format!("spawn net thread: {e}"))?;
 
-        // ── cpal output thread ────────────────────────────────────────────────
-        let audio_stop = stop.clone();
-        let audio_ring = ring.clone();
-        let (init_tx, init_rx) = std::sync::mpsc::sync_channel::<Result<(), String>>(1);
-
-        let audio_handle = thread::Builder::new()
-            .name("u64-audio-out".into())
-            .spawn(move || {
-                let result = (|| -> Result<cpal::Stream, String> {
-                    let host = cpal::default_host();
-                    let device = host
-                        .default_output_device()
-                        .ok_or_else(|| "No audio output device".to_string())?;
-
-                    let dev_cfg = device
-                        .default_output_config()
-                        .map_err(|e| format!("No default output config: {e}"))?;
-
-                    let rate = dev_cfg.sample_rate().0;
-                    let channels = dev_cfg.channels() as usize;
-
-                    eprintln!(
-                        "[u64-audio] cpal device: '{}', {}Hz, {}ch",
-                        device.name().unwrap_or_default(),
-                        rate,
-                        channels
-                    );
-
-                    // If the device rate differs from 48 kHz the resampler in the
-                    // net thread used 48 kHz as its target — we accept minor pitch
-                    // drift rather than adding a second resampler stage.  For the
-                    // vast majority of systems the device is 48 kHz anyway.
-
-                    let config = cpal::StreamConfig {
-                        channels: 2,
-                        sample_rate: cpal::SampleRate(rate),
-                        buffer_size: cpal::BufferSize::Default,
-                    };
-
-                    let ring_cb = audio_ring.clone();
-
-                    let stream = device
-                        .build_output_stream(
-                            &config,
-                            move |data: &mut [f32], _| {
-                                if let Ok(mut r) = ring_cb.lock() {
-                                    // Jitter buffer: output silence until buffered enough.
-                                    if !r.ready {
-                                        if r.samples.len() >= JITTER_MIN {
-                                            r.ready = true;
-                                            eprintln!(
-                                                "[u64-audio] Jitter buffer ready ({} samples)",
-                                                r.samples.len()
-                                            );
-                                        } else {
-                                            data.fill(0.0);
-                                            return;
-                                        }
-                                    }
-                                    let vol = crate::audio_volume::scale();
-                                    // Stereo pairs; upmix to N channels if needed.
-                                    let frames = data.len() / channels;
-                                    for f in 0..frames {
-                                        let l = r.samples.pop_front().unwrap_or(0.0) * vol;
-                                        let r_samp = r.samples.pop_front().unwrap_or(0.0) * vol;
-                                        let base = f * channels;
-                                        data[base] = l;
-                                        if channels > 1 {
-                                            data[base + 1] = r_samp;
-                                        }
-                                        for ch in 2..channels {
-                                            data[base + ch] = 0.0;
-                                        }
-                                    }
-                                } else {
-                                    data.fill(0.0);
-                                }
-                            },
-                            |err| eprintln!("[u64-audio] cpal error: {err}"),
-                            None,
-                        )
-                        .map_err(|e| format!("build_output_stream: {e}"))?;
-
-                    stream.play().map_err(|e| format!("stream.play(): {e}"))?;
-                    Ok(stream)
-                })();
-
-                match result {
-                    Ok(stream) => {
-                        let _ = init_tx.send(Ok(()));
-                        // Park here; the stream keeps playing as long as this
-                        // thread is alive and owns the stream.
-                        while !audio_stop.load(Ordering::Relaxed) {
-                            thread::park_timeout(Duration::from_millis(100));
-                        }
-                        drop(stream);
-                        eprintln!("[u64-audio] cpal thread stopped");
-                    }
-                    Err(e) => {
-                        eprintln!("[u64-audio] cpal init failed: {e}");
-                        let _ = init_tx.send(Err(e));
-                    }
-                }
-            })
-            .map_err(|e| format!("spawn audio thread: {e}"))?;
-
-        // Wait for cpal to initialise (or fail) before returning.
-        init_rx
-            .recv_timeout(Duration::from_secs(5))
-            .map_err(|_| "Audio thread did not respond in time".to_string())??;
-
         Ok(Self {
             stop,
             net: Some(net_handle),
@@ -448,6 +458,8 @@ pub struct U64Device {
     /// trying to read the on-screen timer until the next play_sid_native()
     /// (the firmware's UI must be different than what we expect).
     screen_layout_ok: bool,
+    /// Start tunes as a standalone PRG via run-PRG instead of `sid_play`.
+    run_as_prg: bool,
 }
 
 impl U64Device {
@@ -495,9 +507,40 @@ impl U64Device {
             screen_base: None,
             screen_layout_ok: false,
             connected,
+            run_as_prg: false,
         })
     }
 
+    pub fn set_run_as_prg(&mut self, on: bool) {
+        self.run_as_prg = on;
+    }
+
+    /// Wrap the tune in a PRG (`prg_export`) and start it with run-PRG.
+    /// `Ok(false)` when the tune can't be exported, so the caller can fall
+    /// back to the firmware SID player.
+    fn run_sid_as_prg(&mut self, data: &[u8], song: u16) -> Result<bool, String> {
+        let prg = crate::player::sid_file::load_sid(data).and_then(|sid| {
+            let song = if song > 0 {
+                song
+            } else {
+                sid.header.start_song
+            };
+            crate::prg_export::build(&sid, song)
+        });
+        let prg = match prg {
+            Ok(prg) => prg,
+            Err(e) => {
+                eprintln!("[u64] Cannot start as PRG ({e}) — using the SID player");
+                return Ok(false);
+            }
+        };
+        let result = self.rest.run_prg(&prg);
+        self.note_call("run_prg", &result);
+        result.map_err(|e| format!("U64 run_prg failed: {e}"))?;
+        eprintln!("[u64] PRG sent ({} bytes, song {})", prg.len(), song);
+        Ok(true)
+    }
+
     /// Update the connection flag based on a fresh REST call result. The
     /// transition is logged so the user-visible "Disconnected" pill has a
     /// matching breadcrumb in stderr.
@@ -650,6 +693,13 @@ impl SidDevice for U64Device {
     /// Returns `Ok(true)` on success, meaning the host should skip CPU
     /// emulation and let the real hardware handle everything.
     fn play_sid_native(&mut self, data: &[u8], song: u16) -> Result<bool, String> {
+        if self.run_as_prg && self.run_sid_as_prg(data, song)? {
+            // Our PRG has no on-screen timer; the layout check below
+            // fails and elapsed time falls back to the host clock.
+            self.invalidate_screen_layout();
+            return Ok(true);
+        }
+
         let song_num = if song > 0 { Some(song as u8) } else { None };
 
         let result = self.rest.sid_play(data, song_num);
@@ -0,0 +1,185 @@
+// SIDdump-compatible register dump.
+//
+// Text layout follows the classic `siddump` tool: one row per frame with,
+// per voice, frequency / note+absolute note / waveform / ADSR / pulse
+// width, then the filter cutoff, resonance+routing, filter type and
+// master volume. As in siddump, only values that changed since the
+// previous row are printed and the rest are shown as dots, so note
+// starts and parameter sweeps stand out when transcribing.
+//
+// Multi-SID tunes get one voices+filter block per chip on the same row.
+//
+// Fed either from a headless run (`player::regdump`, every play call) or
+// from the live `TrackerHistory` ring (one row per GUI tick).
+
+use crate::ui::sid_panel::{freq_to_hz, hz_to_note};
+
+const SID_REG_SIZE: usize = 0x20;
+const VOICE_HEADER: &str = "Freq Note/Abs WF ADSR Pul";
+const FILTER_HEADER: &str = "FCut RC Typ V";
+const FILTER_TYPES: [&str; 8] = ["Off", "Low", "Bnd", "L+B", "Hi ", "L+H", "B+H", "LBH"];
+
+/// Incremental dump writer. `push` one register snapshot per frame,
+/// then `finish` for the text.
+pub struct SidDump {
+    num_sids: usize,
+    is_pal: bool,
+    prev: Option<Vec<u8>>,
+    out: String,
+}
+
+impl SidDump {
+    pub fn new(num_sids: usize, is_pal: bool) -> Self {
+        let num_sids = num_sids.clamp(1, 4);
+        let mut segs: Vec<&str> = Vec::new();
+        for _ in 0..num_sids {
+            segs.extend([VOICE_HEADER; 3]);
+            segs.push(FILTER_HEADER);
+        }
+        let mut out = format!("| Frame | {} |\n", segs.join(" | "));
+        out.push_str("+-------+");
+        for s in &segs {
+            out.push_str(&"-".repeat(s.len() + 2));
+            out.push('+');
+        }
+        out.push('\n');
+        Self {
+            num_sids,
+            is_pal,
+            prev: None,
+            out,
+        }
+    }
+
+    /// Append the row for frame number `frame`. `regs` is the player's
+    /// flat register shadow (chip n at n*0x20).
+    pub fn push(&mut self, frame: u64, regs: &[u8]) {
+        let len = self.num_sids * SID_REG_SIZE;
+        let cur: Vec<u8> = (0..len)
+            .map(|i| regs.get(i).copied().unwrap_or(0))
+            .collect();
+        let prev = self.prev.as_deref();
+        // Every field is "changed" on the first row.
+        let changed = |regs: &[usize]| prev.is_none_or(|p| regs.iter().any(|&r| p[r] != cur[r]));
+
+        let mut segs: Vec<String> = Vec::with_capacity(self.num_sids * 4);
+        for sid in 0..self.num_sids {
+            let b = sid * SID_REG_SIZE;
+            for voice in 0..3 {
+                let v = b + voice * 7;
+                let freq = u16::from_le_bytes([cur[v], cur[v + 1]]);
+                let (freq_s, note_s) = if changed(&[v, v + 1]) {
+                    let hz = freq_to_hz(cur[v], cur[v + 1], self.is_pal);
+                    let note = match abs_note(hz) {
+                        Some(abs) => format!("{} {:02X}", hz_to_note(hz), abs | 0x80),
+                        None => "... ..".to_string(),
+                    };
+                    (format!("{freq:04X}"), note)
+                } else {
+                    ("....".to_string(), "... ..".to_string())
+                };
+                let wf = if changed(&[v + 4]) {
+                    format!("{:02X}", cur[v + 4])
+                } else {
+                    "..".to_string()
+                };
+                let adsr = if changed(&[v + 5, v + 6]) {
+                    format!("{:02X}{:02X}", cur[v + 5], cur[v + 6])
+                } else {
+                    "....".to_string()
+                };
+                let pulse = if changed(&[v + 2, v + 3]) {
+                    format!(
+                        "{:03X}",
+                        u16::from_le_bytes([cur[v + 2], cur[v + 3] & 0x0F])
+                    )
+                } else {
+                    "...".to_string()
+                };
+                segs.push(format!("{freq_s}  {note_s}  {wf} {adsr} {pulse}"));
+            }
+
+            let cutoff = ((cur[b + 0x16] as u16) << 8) | (((cur[b + 0x15] & 0x07) as u16) << 5);
+            let cut = if changed(&[b + 0x15, b + 0x16]) {
+                format!("{cutoff:04X}")
+            } else {
+                "....".to_string()
+            };
+            let rc = if changed(&[b + 0x17]) {
+                format!("{:02X}", cur[b + 0x17])
+            } else {
+                "..".to_string()
+            };
+            let mode = cur[b + 0x18];
+            let prev_mode = prev.map(|p| p[b + 0x18]);
+            let typ = if prev_mode.is_none_or(|p| (p ^ mode) & 0x70 != 0) {
+                FILTER_TYPES[((mode >> 4) & 0x07) as usize]
+            } else {
+                "..."
+            };
+            let vol = if prev_mode.is_none_or(|p| (p ^ mode) & 0x0F != 0) {
+                format!("{:01X}", mode & 0x0F)
+            } else {
+                ".".to_string()
+            };
+            segs.push(format!("{cut} {rc} {typ} {vol}"));
+        }
+
+        self.out
+            .push_str(&format!("| {frame:5} | {} |\n", segs.join(" | ")));
+        self.prev = Some(cur);
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
+
+/// Note number counted from C-0, matching the octave in `hz_to_note`.
+/// `None` below the range `hz_to_note` names.
+fn abs_note(hz: f64) -> Option<u8> {
+    if hz < 16.0 {
+        return None;
+    }
+    let midi = (12.0 * (hz / 440.0).log2() + 69.0).round() as i32;
+    Some((midi - 12).clamp(0, 0x7F) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_line_up_with_header() {
+        let mut d = SidDump::new(1, true);
+        let mut regs = [0u8; 0x20];
+        regs[0] = 0xD6;
+        regs[1] = 0x1C; // A-4 on PAL
+        regs[4] = 0x41;
+        regs[0x18] = 0x1F;
+        d.push(0, &regs);
+        d.push(1, &regs);
+        let text = d.finish();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines.iter().all(|l| l.len() == lines[0].len()));
+        assert!(lines[2].contains("1CD6  A-4 B9  41"));
+        assert!(lines[2].ends_with("0000 00 Low F |"));
+    }
+
+    #[test]
+    fn unchanged_values_are_dots() {
+        let mut d = SidDump::new(1, true);
+        let mut regs = [0u8; 0x20];
+        d.push(0, &regs);
+        regs[11] = 0x21; // voice 2 waveform only
+        d.push(1, &regs);
+        let text = d.finish();
+        let row = text.lines().nth(3).unwrap();
+        assert_eq!(
+            row,
+            "|     1 | ....  ... ..  .. .... ... | ....  ... ..  21 .... ... \
+             | ....  ... ..  .. .... ... | .... .. ... . |"
+        );
+    }
+}
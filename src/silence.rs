@@ -0,0 +1,117 @@
+// Silence detector for auto-advance on tunes with no known length.
+//
+// Fed from the GUI tick with the `voice_levels` and `sid_regs` shadow the
+// player already ships in `PlayerStatus`. A snapshot counts as silent when
+// every voice level is zero AND no voice control register or $D418 changed
+// since the previous snapshot — the register check keeps percussive tunes
+// (gated notes with sustain 0, which report level 0) and $D418 digis from
+// being mistaken for the end of the song.
+//
+// Time is measured on the player's elapsed clock rather than wall-clock so
+// pausing doesn't count towards the threshold.
+
+use std::time::Duration;
+
+const SID_REG_SIZE: usize = 0x20;
+/// Registers compared between snapshots: the three voice control
+/// registers and the mode/volume register, per chip.
+const WATCHED_REGS: [usize; 4] = [0x04, 0x0B, 0x12, 0x18];
+/// Levels at or below this are treated as silence.
+const LEVEL_EPSILON: f32 = 0.001;
+
+#[derive(Debug, Default)]
+pub struct SilenceDetector {
+    /// Watched registers from the previous snapshot.
+    last_regs: Vec<u8>,
+    /// Playback position at which the current silent stretch began.
+    silent_since: Option<Duration>,
+    /// Length of the current silent stretch as of the last update.
+    silent_for: Duration,
+}
+
+impl SilenceDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget everything — call on track/subtune change and seek.
+    pub fn reset(&mut self) {
+        self.last_regs.clear();
+        self.silent_since = None;
+        self.silent_for = Duration::ZERO;
+    }
+
+    /// Feed one status snapshot taken at playback position `elapsed`.
+    pub fn update(&mut self, elapsed: Duration, levels: &[f32], sid_regs: &[u8], num_sids: usize) {
+        let regs: Vec<u8> = (0..num_sids.clamp(1, 4))
+            .flat_map(|sid| {
+                WATCHED_REGS
+                    .iter()
+                    .map(move |&r| sid_regs.get(sid * SID_REG_SIZE + r).copied().unwrap_or(0))
+            })
+            .collect();
+        let quiet = levels.iter().all(|&l| l <= LEVEL_EPSILON);
+        let is_static = regs == self.last_regs;
+        self.last_regs = regs;
+
+        if quiet && is_static {
+            let since = *self.silent_since.get_or_insert(elapsed);
+            self.silent_for = elapsed.saturating_sub(since);
+        } else {
+            self.silent_since = None;
+            self.silent_for = Duration::ZERO;
+        }
+    }
+
+    /// How long the tune has been continuously silent.
+    pub fn silent_for(&self) -> Duration {
+        self.silent_for
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(s: f32) -> Duration {
+        Duration::from_secs_f32(s)
+    }
+
+    #[test]
+    fn static_silence_accumulates() {
+        let mut d = SilenceDetector::new();
+        let regs = vec![0u8; 0x20];
+        d.update(secs(1.0), &[0.0; 3], &regs, 1);
+        d.update(secs(2.0), &[0.0; 3], &regs, 1);
+        d.update(secs(4.5), &[0.0; 3], &regs, 1);
+        assert_eq!(d.silent_for(), secs(2.5));
+    }
+
+    #[test]
+    fn audible_voice_resets() {
+        let mut d = SilenceDetector::new();
+        let regs = vec![0u8; 0x20];
+        d.update(secs(1.0), &[0.0; 3], &regs, 1);
+        d.update(secs(3.0), &[0.0; 3], &regs, 1);
+        d.update(secs(3.1), &[0.0, 0.4, 0.0], &regs, 1);
+        assert_eq!(d.silent_for(), Duration::ZERO);
+    }
+
+    #[test]
+    fn register_activity_is_not_silence() {
+        // Gated notes with sustain 0 read as level 0, but the control
+        // register keeps changing; $D418 digis likewise.
+        let mut d = SilenceDetector::new();
+        let mut regs = vec![0u8; 0x40];
+        d.update(secs(1.0), &[0.0; 6], &regs, 2);
+        regs[0x20 + 0x0B] = 0x41;
+        d.update(secs(2.0), &[0.0; 6], &regs, 2);
+        assert_eq!(d.silent_for(), Duration::ZERO);
+        regs[0x18] = 0x0F;
+        d.update(secs(3.0), &[0.0; 6], &regs, 2);
+        assert_eq!(d.silent_for(), Duration::ZERO);
+        d.update(secs(4.0), &[0.0; 6], &regs, 2);
+        d.update(secs(6.0), &[0.0; 6], &regs, 2);
+        assert_eq!(d.silent_for(), secs(2.0));
+    }
+}
@@ -0,0 +1,84 @@
+// Playback tempo, shared by the GUI, the remote API and the player thread.
+//
+// At `pct` percent the player paces frames `100 / pct` times as long in
+// wall-clock time, so PLAY runs fewer (or more) times a second. By default
+// the SID clock is left alone: each frame is stretched on the device side
+// too — the writes' cycle stamps and the frame length scale together — so
+// notes keep their pitch. With "pitch follows speed" the software engines
+// run their SID clock at `pct` percent instead, like a tape played slower
+// or faster. Hardware clocks are fixed, so there it falls back to the
+// stretched frames.
+//
+// Writes timed within a frame (digis, hard-restart gaps) are stretched
+// with it, so sample playback shifts pitch either way.
+//
+// Stored in atomics like `audio_volume` and `voice_mask`, so a change
+// needs no round trip through the player command channel. Native U64
+// playback runs on the Ultimate's own clock and ignores the tempo.
+
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+use std::time::Duration;
+
+pub const MIN_PCT: u16 = 50;
+pub const MAX_PCT: u16 = 200;
+/// Step for the toolbar buttons and the `[` / `]` hotkeys.
+pub const STEP_PCT: u16 = 10;
+
+static PCT: AtomicU16 = AtomicU16::new(100);
+static PITCH_FOLLOWS: AtomicBool = AtomicBool::new(false);
+
+/// Set the tempo in percent, clamped to `MIN_PCT..=MAX_PCT`. Takes
+/// effect on the player's next frame.
+pub fn set(pct: u16) -> u16 {
+    let pct = pct.clamp(MIN_PCT, MAX_PCT);
+    PCT.store(pct, Ordering::Relaxed);
+    pct
+}
+
+/// Current tempo in percent. Read once per frame by the player thread.
+#[inline]
+pub fn current() -> u16 {
+    PCT.load(Ordering::Relaxed)
+}
+
+pub fn set_pitch_follows(on: bool) {
+    PITCH_FOLLOWS.store(on, Ordering::Relaxed);
+}
+
+pub fn pitch_follows() -> bool {
+    PITCH_FOLLOWS.load(Ordering::Relaxed)
+}
+
+/// Cycle count `cycles` lasts as long at `pct` percent as it did at 100.
+pub fn stretch(cycles: u32, pct: u16) -> u32 {
+    (cycles as u64 * 100 / pct.max(1) as u64) as u32
+}
+
+/// Wall-clock length of a frame of nominal length `frame` at `pct`.
+pub fn stretch_duration(frame: Duration, pct: u16) -> Duration {
+    frame * 100 / pct.max(1) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stretch_scales_inversely_with_tempo() {
+        assert_eq!(stretch(19_656, 100), 19_656);
+        assert_eq!(stretch(19_656, 50), 39_312);
+        assert_eq!(stretch(19_656, 200), 9_828);
+        assert_eq!(
+            stretch_duration(Duration::from_micros(19_951), 50),
+            Duration::from_micros(39_902)
+        );
+    }
+
+    #[test]
+    fn set_clamps_to_range() {
+        assert_eq!(set(20), MIN_PCT);
+        assert_eq!(set(500), MAX_PCT);
+        assert_eq!(set(100), 100);
+        assert_eq!(current(), 100);
+    }
+}
@@ -0,0 +1,779 @@
+// Terminal front-end — `phosphor --tui [files, folders or playlists...]`.
+//
+// For headless boxes (a Raspberry Pi driving a USBSID-Pico) where the iced
+// window can't open. Drives the same pieces the GUI does — the
+// `player::spawn_player` thread, the session `Playlist`, `FavoritesDb`,
+// the Songlength and STIL databases and `HvscBrowser` — and draws them
+// with crossterm on the alternate screen: now-playing with STIL text, a
+// text VU meter per voice, and the playlist or the HVSC browser below.
+//
+// The player thread and the loaders report on stderr, which would tear
+// through the screen, so on Unix stderr goes to
+// `<config_dir>/phosphor-tui.log` while the TUI runs.
+
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{Receiver, Sender};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::{cursor, execute, queue, terminal};
+
+use crate::config::{Config, FavoritesDb};
+use crate::hvsc_browser::{HvscBrowser, HvscCategory};
+use crate::player::{self, PlayState, PlayerCmd, PlayerStatus};
+use crate::playlist::{self, Playlist, SonglengthDb};
+use crate::stil::{self, StilDb};
+
+/// Screen refresh and key poll interval.
+const TICK: Duration = Duration::from_millis(50);
+/// Cells per voice in the VU meter.
+const VU_WIDTH: usize = 12;
+/// Most STIL lines shown under the now-playing block.
+const STIL_LINES: usize = 6;
+/// Volume step for `+` / `-`.
+const VOLUME_STEP: f32 = 0.05;
+
+const HELP: &str = "Enter play  Space pause  n/p next/prev  ←/→ subtune  s stop  \
+                    f ♥  r repeat  z shuffle  +/- volume  b browse  q quit";
+const BROWSE_HELP: &str = "Enter open/play  a add all  Bksp back  c category  b playlist  q quit";
+
+#[derive(PartialEq)]
+enum View {
+    Playlist,
+    Browse,
+}
+
+struct Tui {
+    config: Config,
+    cmd_tx: Sender<PlayerCmd>,
+    status_rx: Receiver<PlayerStatus>,
+    status: PlayerStatus,
+    playlist: Playlist,
+    favorites: FavoritesDb,
+    songlength_db: Option<SonglengthDb>,
+    stil_db: Option<StilDb>,
+    /// STIL text for the playing tune and subtune.
+    stil_text: String,
+    browser: HvscBrowser,
+    view: View,
+    /// Highlighted playlist row.
+    selected: usize,
+    /// Highlighted browser row: an author, or a tune once one is open.
+    browse_selected: usize,
+    browse_in_author: bool,
+    /// Debounce for auto-advance, so one song end fires once.
+    last_advance_at: Option<Instant>,
+    /// One-line notice in place of the key help, until the next key.
+    message: String,
+    quit: bool,
+}
+
+/// Run the TUI until the user quits. `paths` are the non-flag arguments,
+/// loaded like the GUI's; with none, the last session is restored.
+/// Returns the process exit code.
+pub fn run(paths: Vec<PathBuf>) -> i32 {
+    let log = redirect_stderr();
+
+    let config = Config::load();
+    crate::audio_volume::set(config.master_volume);
+    crate::tempo::set_pitch_follows(config.tempo_pitch_follows);
+    let (cmd_tx, status_rx, _device_cfg_rx) = player::spawn_player(
+        config.output_engine(),
+        config.u64_address.clone(),
+        config.u64_password.clone(),
+        config.macos_usb_mode.clone(),
+    );
+
+    let mut playlist = Playlist::new();
+    playlist.add_entries(playlist::parse_startup(paths, Default::default()));
+    let hvsc_root = config.hvsc_root.as_deref().map(PathBuf::from);
+    let songlength_db = crate::config::songlength_db_path()
+        .filter(|p| p.exists())
+        .and_then(|p| SonglengthDb::load(&p).ok())
+        .or_else(|| {
+            let db = SonglengthDb::overlay_only();
+            (!db.overlay.is_empty()).then_some(db)
+        });
+    if let Some(db) = &songlength_db {
+        db.apply_to_playlist(&mut playlist, hvsc_root.as_deref());
+    }
+    let stil_db = config
+        .last_stil_file
+        .as_ref()
+        .map(PathBuf::from)
+        .filter(|p| p.exists())
+        .or_else(|| stil::stil_db_path().filter(|p| p.exists()))
+        .and_then(|p| StilDb::load(&p).ok());
+
+    let mut tui = Tui {
+        cmd_tx,
+        status_rx,
+        status: PlayerStatus::default(),
+        playlist,
+        favorites: FavoritesDb::load(),
+        songlength_db,
+        stil_db,
+        stil_text: String::new(),
+        browser: HvscBrowser::new(hvsc_root),
+        view: View::Playlist,
+        selected: 0,
+        browse_selected: 0,
+        browse_in_author: false,
+        last_advance_at: None,
+        message: log
+            .as_ref()
+            .map(|l| format!("Log: {}", l.path.display()))
+            .unwrap_or_default(),
+        quit: false,
+        config,
+    };
+
+    let result = tui.run_loop();
+    tui.playlist.save_session();
+    let _ = tui.cmd_tx.try_send(PlayerCmd::Stop);
+    std::thread::sleep(Duration::from_millis(100));
+    let _ = tui.cmd_tx.try_send(PlayerCmd::Quit);
+    std::thread::sleep(Duration::from_millis(200));
+    // Back on the terminal, so a failure is seen rather than logged.
+    drop(log);
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("[tui] {e}");
+            1
+        }
+    }
+}
+
+impl Tui {
+    fn run_loop(&mut self) -> io::Result<()> {
+        let _screen = Screen::enter()?;
+        let mut out = io::stdout();
+        while !self.quit {
+            while let Ok(status) = self.status_rx.try_recv() {
+                let song_changed = status
+                    .track_info
+                    .as_ref()
+                    .map(|t| (&t.path, t.current_song))
+                    != self
+                        .status
+                        .track_info
+                        .as_ref()
+                        .map(|t| (&t.path, t.current_song));
+                self.status = status;
+                if song_changed {
+                    self.refresh_stil();
+                }
+            }
+            self.check_song_end();
+            self.draw(&mut out)?;
+            if event::poll(TICK)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        self.message.clear();
+                        self.handle_key(key);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn send(&self, cmd: PlayerCmd) {
+        if let Err(e) = self.cmd_tx.try_send(cmd) {
+            eprintln!("[tui] Player command dropped: {e:?}");
+        }
+    }
+
+    // ── Keys ────────────────────────────────────────────────────────────
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+        // Transport keys work in both views.
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Char(' ') => self.send(PlayerCmd::TogglePause),
+            KeyCode::Char('s') => self.send(PlayerCmd::Stop),
+            KeyCode::Char('n') => self.next(),
+            KeyCode::Char('p') => self.prev(),
+            KeyCode::Left => self.step_subtune(-1),
+            KeyCode::Right => self.step_subtune(1),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.nudge_volume(VOLUME_STEP),
+            KeyCode::Char('-') => self.nudge_volume(-VOLUME_STEP),
+            KeyCode::Char('r') => {
+                self.playlist.cycle_repeat();
+                self.message = format!("Repeat: {}", self.playlist.repeat.label());
+            }
+            KeyCode::Char('z') => {
+                self.playlist.toggle_shuffle();
+                self.message = format!(
+                    "Shuffle {}",
+                    if self.playlist.shuffle { "on" } else { "off" }
+                );
+            }
+            KeyCode::Char('b') | KeyCode::Tab => self.toggle_view(),
+            _ => match self.view {
+                View::Playlist => self.handle_playlist_key(key.code),
+                View::Browse => self.handle_browse_key(key.code),
+            },
+        }
+    }
+
+    fn handle_playlist_key(&mut self, code: KeyCode) {
+        let len = self.playlist.len();
+        match code {
+            KeyCode::Enter if self.selected < len => self.play(self.selected),
+            KeyCode::Char('f') => {
+                if let Some(entry) = self.playlist.entries.get(self.selected) {
+                    let on = self.favorites.toggle(entry);
+                    self.favorites.save();
+                    self.message =
+                        format!("{} {}", if on { "♥ Added" } else { "Removed" }, entry.title);
+                }
+            }
+            code => self.selected = move_selection(self.selected, len, code),
+        }
+    }
+
+    fn handle_browse_key(&mut self, code: KeyCode) {
+        let len = if self.browse_in_author {
+            self.browser.tunes().len()
+        } else {
+            self.browser.authors().len()
+        };
+        match code {
+            KeyCode::Enter if self.browse_selected < len => {
+                if self.browse_in_author {
+                    let entry = self.browser.tunes()[self.browse_selected].entry.clone();
+                    self.playlist.add_entries(vec![entry]);
+                    self.play(self.playlist.len() - 1);
+                } else {
+                    self.open_author(self.browse_selected);
+                }
+            }
+            KeyCode::Char('a') if self.browse_selected < len => {
+                if !self.browse_in_author {
+                    self.open_author(self.browse_selected);
+                }
+                let entries: Vec<_> = self
+                    .browser
+                    .tunes()
+                    .iter()
+                    .map(|t| t.entry.clone())
+                    .collect();
+                self.message = format!("Added {} tunes", entries.len());
+                self.playlist.add_entries(entries);
+            }
+            KeyCode::Backspace | KeyCode::Esc if self.browse_in_author => {
+                self.browse_in_author = false;
+                self.browse_selected = self.browser.selected_author_idx().unwrap_or(0);
+            }
+            KeyCode::Char('c') => {
+                let next = match self.browser.category() {
+                    HvscCategory::Musicians => HvscCategory::Demos,
+                    HvscCategory::Demos => HvscCategory::Games,
+                    HvscCategory::Games => HvscCategory::Musicians,
+                };
+                self.browser.set_category(next);
+                self.browse_in_author = false;
+                self.browse_selected = 0;
+                self.load_authors();
+            }
+            code => self.browse_selected = move_selection(self.browse_selected, len, code),
+        }
+    }
+
+    fn toggle_view(&mut self) {
+        self.view = match self.view {
+            View::Playlist => {
+                self.load_authors();
+                View::Browse
+            }
+            View::Browse => View::Playlist,
+        };
+    }
+
+    fn load_authors(&mut self) {
+        if self.browser.root().is_none() {
+            self.message = "No HVSC folder set — pick one in the GUI's Settings".into();
+        } else if let Err(e) = self.browser.load_authors_if_needed() {
+            self.message = e;
+        }
+    }
+
+    fn open_author(&mut self, idx: usize) {
+        self.browser
+            .select_author(idx, self.stil_db.as_ref(), self.songlength_db.as_ref());
+        self.browse_in_author = true;
+        self.browse_selected = 0;
+    }
+
+    fn nudge_volume(&mut self, delta: f32) {
+        let v = (self.config.master_volume + delta).clamp(0.0, 1.0);
+        self.config.master_volume = v;
+        crate::audio_volume::set(v);
+        self.config.save();
+        self.message = format!("Volume {:.0}%", v * 100.0);
+    }
+
+    // ── Playback ────────────────────────────────────────────────────────
+
+    fn play(&mut self, idx: usize) {
+        let Some(entry) = self.playlist.entries.get(idx) else {
+            return;
+        };
+        let force_stereo =
+            self.config.force_stereo_2sid || std::env::args().any(|a| a == "--stereo");
+        let audio_port = if self.config.u64_audio_enabled {
+            Some(self.config.u64_audio_port)
+        } else {
+            None
+        };
+        self.send(PlayerCmd::Play {
+            path: entry.path.clone(),
+            song: entry.selected_song,
+            force_stereo,
+            audio_port,
+            restart_usb_on_load: self.config.restart_usb_on_load,
+            force_pal: entry.force_pal,
+        });
+        self.playlist.current = Some(idx);
+        self.selected = idx;
+        self.last_advance_at = None;
+    }
+
+    fn next(&mut self) {
+        match self.playlist.next() {
+            Some(i) => self.play(i),
+            None => self.send(PlayerCmd::Stop),
+        }
+    }
+
+    fn prev(&mut self) {
+        if let Some(i) = self.playlist.prev() {
+            self.play(i);
+        }
+    }
+
+    fn step_subtune(&mut self, delta: i32) {
+        let Some(info) = &self.status.track_info else {
+            return;
+        };
+        let song = (info.current_song as i32 + delta).clamp(1, info.songs.max(1) as i32) as u16;
+        if song == info.current_song {
+            return;
+        }
+        self.send(PlayerCmd::SetSubtune(song));
+        let default = self.config.default_song_length_secs;
+        let hvsc_root = self.config.hvsc_root.as_deref().map(std::path::Path::new);
+        if let Some(e) = self
+            .playlist
+            .current
+            .and_then(|i| self.playlist.entries.get_mut(i))
+        {
+            e.selected_song = song;
+            let known = self.songlength_db.as_ref().and_then(|db| {
+                db.lookup_with_path_fallback(
+                    e.md5.as_deref(),
+                    &e.path,
+                    hvsc_root,
+                    (song - 1) as usize,
+                )
+            });
+            e.set_duration(known, default);
+        }
+        self.last_advance_at = None;
+    }
+
+    /// Move on once the playing entry has run its length.
+    fn check_song_end(&mut self) {
+        if self.status.state != PlayState::Playing {
+            return;
+        }
+        let Some(entry) = self.playlist.current_entry() else {
+            return;
+        };
+        // Wait for the player to report the entry we asked for.
+        if self.status.track_info.as_ref().map(|t| &t.path) != Some(&entry.path) {
+            return;
+        }
+        let default = self.config.default_song_length_secs;
+        let Some(secs) = entry.duration_secs.or((default > 0).then_some(default)) else {
+            return;
+        };
+        if self.status.elapsed < Duration::from_secs(secs as u64)
+            || self
+                .last_advance_at
+                .is_some_and(|t| t.elapsed() < Duration::from_secs(2))
+        {
+            return;
+        }
+        self.last_advance_at = Some(Instant::now());
+        self.next();
+    }
+
+    fn refresh_stil(&mut self) {
+        self.stil_text.clear();
+        let (Some(db), Some(info)) = (&self.stil_db, &self.status.track_info) else {
+            return;
+        };
+        let hvsc_root = self.config.hvsc_root.as_deref().map(std::path::Path::new);
+        if let Some(entry) = db.lookup(&info.path, hvsc_root) {
+            self.stil_text = entry.format_for_display(info.current_song);
+        }
+    }
+
+    // ── Drawing ─────────────────────────────────────────────────────────
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        let (w, h) = terminal::size()?;
+        let (width, height) = (w as usize, h as usize);
+        let mut lines: Vec<String> = Vec::with_capacity(height);
+
+        // Now playing.
+        let state = match self.status.state {
+            PlayState::Playing => "▶",
+            PlayState::Paused => "⏸",
+            PlayState::Stopped => "■",
+        };
+        let length = self
+            .playlist
+            .current_entry()
+            .and_then(|e| e.duration_secs)
+            .map(|s| format!(" / {}", fmt_time(s as u64)))
+            .unwrap_or_default();
+        lines.push(split_line(
+            &format!("Phosphor — {}", self.config.output_engine),
+            &format!(
+                "{state} {}{length}",
+                fmt_time(self.status.elapsed.as_secs())
+            ),
+            width,
+        ));
+        match &self.status.track_info {
+            Some(info) => {
+                lines.push(fit(
+                    &format!("{} — {} ({})", info.name, info.author, info.released),
+                    width,
+                ));
+                lines.push(fit(
+                    &format!(
+                        "Song {}/{} · {} · {} · {} · vol {:.0}% · {}{}",
+                        info.current_song,
+                        info.songs,
+                        if info.is_pal { "PAL" } else { "NTSC" },
+                        if info.is_rsid { "RSID" } else { "PSID" },
+                        info.sid_type,
+                        self.config.master_volume * 100.0,
+                        self.playlist.repeat.label(),
+                        if self.playlist.shuffle {
+                            " · shuffle"
+                        } else {
+                            ""
+                        },
+                    ),
+                    width,
+                ));
+            }
+            None => {
+                lines.push(fit("Nothing playing", width));
+                lines.push(String::new());
+            }
+        }
+        if let Some(e) = &self.status.error {
+            lines.push(fit(&format!("⚠ {e}"), width));
+        }
+
+        // VU meter: one row per SID, three voices each.
+        for (sid, voices) in self.status.voice_levels.chunks(3).enumerate() {
+            let bars: Vec<String> = voices.iter().map(|&l| vu_bar(l, VU_WIDTH)).collect();
+            lines.push(fit(&format!("SID{} ▕{}▏", sid + 1, bars.join("▏▕")), width));
+        }
+
+        // STIL.
+        let stil = wrap(&self.stil_text, width);
+        if !stil.is_empty() {
+            lines.push(String::new());
+            lines.extend(stil.into_iter().take(STIL_LINES));
+        }
+
+        // List pane fills the rest, minus a title and the help line.
+        let (title, rows, selected, current) = self.list_rows(width);
+        let rule = format!("── {title} ");
+        let fill = width.saturating_sub(rule.chars().count());
+        lines.push(format!("{rule}{}", "─".repeat(fill)));
+        let list_height = height.saturating_sub(lines.len() + 1);
+        let start = window_start(selected, rows.len(), list_height);
+
+        for (y, line) in lines.iter().enumerate() {
+            queue!(out, cursor::MoveTo(0, y as u16), Print(fit(line, width)))?;
+        }
+        let top = lines.len();
+        for row in 0..list_height {
+            let i = start + row;
+            let text = rows.get(i).map(String::as_str).unwrap_or("");
+            let marker = if Some(i) == current { "▶ " } else { "  " };
+            queue!(out, cursor::MoveTo(0, (top + row) as u16))?;
+            if i == selected && i < rows.len() {
+                queue!(out, SetAttribute(Attribute::Reverse))?;
+            }
+            queue!(
+                out,
+                Print(fit(&format!("{marker}{text}"), width)),
+                SetAttribute(Attribute::Reset)
+            )?;
+        }
+        let help = if !self.message.is_empty() {
+            self.message.as_str()
+        } else if self.view == View::Browse {
+            BROWSE_HELP
+        } else {
+            HELP
+        };
+        queue!(
+            out,
+            cursor::MoveTo(0, height.saturating_sub(1) as u16),
+            SetAttribute(Attribute::Dim),
+            Print(fit(help, width)),
+            SetAttribute(Attribute::Reset)
+        )?;
+        out.flush()
+    }
+
+    /// Title, rows, selected row and playing row of the list pane.
+    fn list_rows(&self, width: usize) -> (String, Vec<String>, usize, Option<usize>) {
+        let cols = width.saturating_sub(2);
+        match self.view {
+            View::Playlist => {
+                let rows = self
+                    .playlist
+                    .entries
+                    .iter()
+                    .map(|e| {
+                        let fav = e
+                            .md5
+                            .as_deref()
+                            .is_some_and(|m| self.favorites.is_favorite(m));
+                        split_line(
+                            &format!("{} {} — {}", if fav { "♥" } else { " " }, e.title, e.author),
+                            &e.format_duration(),
+                            cols,
+                        )
+                    })
+                    .collect();
+                (
+                    format!("Playlist ({})", self.playlist.len()),
+                    rows,
+                    self.selected,
+                    self.playlist.current,
+                )
+            }
+            View::Browse if self.browse_in_author => {
+                let author = self
+                    .browser
+                    .selected_author()
+                    .map(|a| a.display_name.clone())
+                    .unwrap_or_default();
+                let rows = self
+                    .browser
+                    .tunes()
+                    .iter()
+                    .map(|t| {
+                        split_line(
+                            &format!("{} {}", if t.has_stil { "✓" } else { " " }, t.entry.title),
+                            &format!("{}  {}", t.entry.released, t.entry.format_duration()),
+                            cols,
+                        )
+                    })
+                    .collect();
+                (
+                    format!("HVSC {} / {author}", self.browser.category().label()),
+                    rows,
+                    self.browse_selected,
+                    None,
+                )
+            }
+            View::Browse => (
+                format!("HVSC {}", self.browser.category().label()),
+                self.browser
+                    .authors()
+                    .iter()
+                    .map(|a| a.display_name.clone())
+                    .collect(),
+                self.browse_selected,
+                None,
+            ),
+        }
+    }
+}
+
+/// Alternate screen + raw mode for the lifetime of the value.
+struct Screen;
+
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// stderr pointed at the TUI log file; the original stderr is put back
+/// on drop.
+#[cfg_attr(not(unix), allow(dead_code))]
+struct StderrRedirect {
+    path: PathBuf,
+    /// Duplicate of the original stderr descriptor.
+    #[cfg(unix)]
+    saved: libc::c_int,
+}
+
+#[cfg(unix)]
+impl Drop for StderrRedirect {
+    fn drop(&mut self) {
+        // SAFETY: `saved` is our own duplicate of the original stderr,
+        // closed exactly once here.
+        unsafe {
+            libc::dup2(self.saved, libc::STDERR_FILENO);
+            libc::close(self.saved);
+        }
+    }
+}
+
+/// Point stderr at a log file so player output doesn't scribble over the
+/// screen.
+#[cfg(unix)]
+fn redirect_stderr() -> Option<StderrRedirect> {
+    use std::os::unix::io::AsRawFd;
+
+    let path = crate::config::config_dir()?.join("phosphor-tui.log");
+    let file = std::fs::File::create(&path).ok()?;
+    // SAFETY: dup / dup2 on valid descriptors; the duplicate keeps the
+    // file open after `file` is dropped.
+    let saved = unsafe { libc::dup(libc::STDERR_FILENO) };
+    if saved < 0 {
+        return None;
+    }
+    if unsafe { libc::dup2(file.as_raw_fd(), libc::STDERR_FILENO) } < 0 {
+        unsafe { libc::close(saved) };
+        return None;
+    }
+    Some(StderrRedirect { path, saved })
+}
+
+#[cfg(not(unix))]
+fn redirect_stderr() -> Option<StderrRedirect> {
+    None
+}
+
+/// Apply an arrow / paging key to a list selection.
+fn move_selection(selected: usize, len: usize, code: KeyCode) -> usize {
+    let last = len.saturating_sub(1);
+    match code {
+        KeyCode::Up | KeyCode::Char('k') => selected.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => (selected + 1).min(last),
+        KeyCode::PageUp => selected.saturating_sub(10),
+        KeyCode::PageDown => (selected + 10).min(last),
+        KeyCode::Home => 0,
+        KeyCode::End => last,
+        _ => selected,
+    }
+}
+
+/// First visible row of a `rows`-high window that keeps `selected` in
+/// view, roughly centred.
+fn window_start(selected: usize, len: usize, rows: usize) -> usize {
+    if len <= rows {
+        0
+    } else {
+        selected.saturating_sub(rows / 2).min(len - rows)
+    }
+}
+
+/// `level` in [0, 1] as a bar `width` cells wide.
+fn vu_bar(level: f32, width: usize) -> String {
+    let filled = ((level.clamp(0.0, 1.0) * width as f32).round() as usize).min(width);
+    format!("{}{}", "█".repeat(filled), " ".repeat(width - filled))
+}
+
+fn fmt_time(secs: u64) -> String {
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// Truncate or pad `s` to exactly `width` characters.
+fn fit(s: &str, width: usize) -> String {
+    let mut out: String = s.chars().take(width).collect();
+    let n = out.chars().count();
+    out.extend(std::iter::repeat_n(' ', width - n));
+    out
+}
+
+/// `left` and `right` on one `width`-wide line, `right` flush right.
+fn split_line(left: &str, right: &str, width: usize) -> String {
+    let right: String = right.chars().take(width).collect();
+    let room = width - right.chars().count();
+    format!("{}{right}", fit(left, room))
+}
+
+/// Greedy word wrap, keeping the text's own line breaks.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    for para in text.lines() {
+        let mut line = String::new();
+        for word in para.split_whitespace() {
+            let len = line.chars().count();
+            if len > 0 && len + 1 + word.chars().count() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_window_keeps_selection_visible() {
+        assert_eq!(window_start(3, 5, 10), 0);
+        assert_eq!(window_start(0, 100, 10), 0);
+        assert_eq!(window_start(50, 100, 10), 45);
+        assert_eq!(window_start(99, 100, 10), 90);
+        assert_eq!(move_selection(0, 0, KeyCode::Down), 0);
+        assert_eq!(move_selection(8, 10, KeyCode::PageDown), 9);
+    }
+
+    #[test]
+    fn text_helpers_fit_the_width() {
+        assert_eq!(vu_bar(0.5, 4), "██  ");
+        assert_eq!(vu_bar(2.0, 3), "███");
+        assert_eq!(fit("Commando", 4), "Comm");
+        assert_eq!(split_line("Title", "3:00", 12), "Title   3:00");
+        assert_eq!(
+            wrap("Rob Hubbard wrote this\n\nin one night", 11),
+            ["Rob Hubbard", "wrote this", "", "in one", "night"]
+        );
+    }
+}
@@ -165,6 +165,8 @@ pub enum Message {
     // Sub-tunes
     NextSubtune,
     PrevSubtune,
+    /// Jump to this many seconds into the current subtune (progress-bar click).
+    SeekTo(u32),
 
     // Songlength
     LoadSonglength,
@@ -198,6 +200,16 @@ pub enum Message {
     ContextMenuMoveToTop,
     ContextMenuToggleFavorite,
     ContextMenuCopyTitle,
+    /// Step the clicked entry's clock: from tune → PAL → NTSC → from tune.
+    ContextMenuCycleClock,
+    /// SIDdump export of the clicked tune from a headless run.
+    ContextMenuExportDump,
+    /// SIDdump export of the live tracker history (current tune only).
+    ContextMenuExportLiveDump,
+    SidDumpSaved(Result<PathBuf, String>),
+    /// Save the clicked tune as a standalone C64 PRG.
+    ContextMenuExportPrg,
+    PrgSaved(Result<PathBuf, String>),
 
     // Recently played
     ShowRecentlyPlayed,
@@ -246,10 +258,27 @@ pub enum Message {
     SettingsTabChanged(SettingsTab),
     ToggleSkipRsid,
     ToggleForceStereo2sid,
+    /// Run PSIDs on the cycle-exact c64_emu core (`Config::psid_cycle_exact`).
+    TogglePsidCycleExact,
     /// macOS-only: switch USB transport between root bridge daemon and
     /// in-process libusb. Payload is "bridge" or "direct".
     SetMacosUsbMode(String),
     DefaultSongLengthChanged(String),
+    /// Fade-out length before song-end / sleep-timer stops (0 = off).
+    SetFadeOutSecs(u32),
+    /// Silence length that triggers a skip to the next track (0 = off).
+    SetSilenceSkipSecs(u32),
+    /// Loudness normalisation target in LUFS (0 = off).
+    SetLoudnessTarget(i32),
+    /// Output device for the cpal engines by name (`None` = system default).
+    SetAudioDevice(Option<String>),
+    /// Output sample rate in Hz (0 = device default).
+    SetAudioSampleRate(u32),
+    /// Output latency target in ms (0 = driver default).
+    SetAudioLatency(u32),
+    /// Re-enumerate the host's output devices on a worker thread.
+    ScanAudioDevices,
+    AudioDevicesScanned(Vec<String>),
     BaseFontSizeChanged(String),
     /// Live draft of the proxy URL text input.
     ProxyUrlChanged(String),
@@ -261,12 +290,43 @@ pub enum Message {
     /// Nudge master volume by a signed delta (e.g. -0.05 / +0.05 from the
     /// , / . keyboard shortcuts). Clamped to [0, 1] in the handler.
     VolumeNudge(f32),
+    /// Step the tempo by `n` × `tempo::STEP_PCT` (the `[` / `]` hotkeys).
+    TempoNudge(i16),
+    TempoReset,
+    TogglePitchFollows,
     DownloadSonglength,
     SonglengthDownloaded(Result<PathBuf, String>),
     SetOutputEngine(String),
     SetU64Address(String),
     SetU64Password(String),
 
+    // SID write log
+    /// Arm a write-log recording (asks for a file) or stop the running one.
+    ToggleWriteLog,
+    WriteLogFileChosen(Option<PathBuf>),
+    PickReplayFile,
+    ReplayFileChosen(Option<PathBuf>),
+    /// Engine the "replay" engine plays the log on.
+    SetReplayEngine(String),
+    /// Force a chip slot (0 = SID1) to a model: 0 = tune header,
+    /// 1 = 6581, 2 = 8580.
+    SetSidModelOverride(usize, u8),
+    ToggleDigiBoost8580,
+    /// Second engine for A/B listening ("none" to turn it off).
+    SetMirrorEngine(String),
+    /// Hotkey A — switch which engine of the A/B mirror is heard.
+    SwapMirror,
+    SetFilter6581(crate::emu_tuning::FilterPreset),
+    SetStereoLayout(crate::emu_tuning::StereoLayout),
+    /// Pan of one chip slot (0 = SID1) for the custom layout, -100…100.
+    SetStereoPan(usize, i8),
+    /// Headphone crossfeed in percent (0 = off).
+    SetCrossfeed(u32),
+    /// Per-tune override for the playing tune; `None` = use the default.
+    SetTuneDigiBoost(Option<bool>),
+    SetTuneFilter6581(Option<crate::emu_tuning::FilterPreset>),
+    SetTuneStereo(Option<crate::emu_tuning::StereoLayout>),
+
     // Remote control
     ToggleHttpRemote,
     /// Open a URL in the user's default browser (via the `open` crate).
@@ -312,6 +372,12 @@ pub enum Message {
     // Visualiser
     /// Toggle between Bar and Scope display modes.
     ToggleVisMode,
+    /// Mute/solo toggles: `(sid, voice)` are 0-based.
+    ToggleVoiceMute(usize, usize),
+    ToggleVoiceSolo(usize, usize),
+    /// Mute/solo all three voices of one SID chip.
+    ToggleSidMute(usize),
+    ToggleSidSolo(usize),
     ToggleFavoriteCurrent, // keyboard shortcut H — fav current track
     ShowHelp,
     DismissHelp,
@@ -418,6 +484,15 @@ pub enum Message {
     /// We compare source_file against current session_mode before
     /// applying — if the user switched playlists mid-flight, drop it.
     PublishedPlaylistsEnrichDone(String, Vec<crate::playlist::PlaylistEntry>),
+    /// Background song-length estimate finished for the tune with this
+    /// MD5 (see `player::length_estimate`).
+    LengthEstimated(
+        String,
+        Result<crate::player::length_estimate::LengthEstimate, String>,
+    ),
+    /// Background loudness measurement finished for the tune with this
+    /// MD5 (see `player::loudness_scan`).
+    LoudnessMeasured(String, Result<crate::loudness::Measurement, String>),
     /// User clicked "↺ Restore my playlist" while a published playlist is active.
     PublishedPlaylistsRestoreDefault,
     PublishedPlaylistsRestoreDone(Vec<crate::playlist::PlaylistEntry>),
@@ -428,6 +503,8 @@ pub enum Message {
 
     // U64 audio streaming
     ToggleU64Audio,
+    /// Start tunes on the U64 via run-PRG instead of its SID player.
+    ToggleU64RunAsPrg,
     U64AudioPortChanged(String),
 
     // STIL info overlay
@@ -694,7 +771,48 @@ pub fn track_info_bar<'a>(
         .into()
 }
 
+const PROGRESS_FILLED: Color = Color::from_rgb(0.30, 0.70, 0.50);
+const PROGRESS_REMAINING: Color = Color::from_rgb(0.18, 0.19, 0.22);
+
+/// Number of click targets the seekable progress bar is split into.
+const SEEK_SEGMENTS: u32 = 100;
+
+/// Progress bar for tunes with a known length: a row of thin segments,
+/// each a click target that seeks to the start of its slice of the tune.
+/// The hit area is taller than the 4 px bar so it's easy to grab.
+fn seek_bar<'a>(fraction: f32, total_secs: u64) -> Element<'a, Message> {
+    let filled_segments = (fraction * SEEK_SEGMENTS as f32) as u32;
+    let segments = (0..SEEK_SEGMENTS).map(|i| {
+        let colour = if i < filled_segments {
+            PROGRESS_FILLED
+        } else {
+            PROGRESS_REMAINING
+        };
+        let secs = (total_secs * i as u64 / SEEK_SEGMENTS as u64) as u32;
+        let line = container(Space::new().height(Length::Fixed(4.0)))
+            .width(Length::Fill)
+            .style(move |_theme: &Theme| container::Style {
+                background: Some(iced::Background::Color(colour)),
+                ..Default::default()
+            });
+        mouse_area(
+            container(line)
+                .height(Length::Fixed(12.0))
+                .width(Length::FillPortion(1))
+                .align_y(Alignment::Center),
+        )
+        .on_press(Message::SeekTo(secs))
+        .interaction(mouse::Interaction::Pointer)
+        .into()
+    });
+    Row::with_children(segments)
+        .spacing(0)
+        .width(Length::Fill)
+        .into()
+}
+
 /// Build the thin progress bar showing elapsed / total time below the track info.
+/// Clicking it seeks when the tune's length is known.
 pub fn progress_bar<'a>(
     status: &PlayerStatus,
     current_duration: Option<u32>,
@@ -716,44 +834,44 @@ pub fn progress_bar<'a>(
     let time_label = text(format!("  {elapsed_str} / {total_str}"))
         .size(font::sized(11.0))
         .color(Color::from_rgb(0.6, 0.65, 0.7));
-    let bar_pct = (fraction * 100.0) as u16;
-
-    let filled = container(Space::new().height(Length::Fixed(4.0)))
-        .width(Length::FillPortion(bar_pct.max(1)))
-        .style(|_theme: &Theme| container::Style {
-            background: Some(iced::Background::Color(Color::from_rgb(0.30, 0.70, 0.50))),
-            border: iced::Border {
-                radius: 2.0.into(),
+    let bar: Element<'a, Message> = if total_secs > 0 {
+        seek_bar(fraction, total_secs)
+    } else {
+        let bar_pct = (fraction * 100.0) as u16;
+        let filled = container(Space::new().height(Length::Fixed(4.0)))
+            .width(Length::FillPortion(bar_pct.max(1)))
+            .style(|_theme: &Theme| container::Style {
+                background: Some(iced::Background::Color(PROGRESS_FILLED)),
+                border: iced::Border {
+                    radius: 2.0.into(),
+                    ..Default::default()
+                },
                 ..Default::default()
-            },
-            ..Default::default()
-        });
-    let remaining = container(Space::new().height(Length::Fixed(4.0)))
-        .width(Length::FillPortion(100u16.saturating_sub(bar_pct).max(1)))
-        .style(|_theme: &Theme| container::Style {
-            background: Some(iced::Background::Color(Color::from_rgb(0.18, 0.19, 0.22))),
-            border: iced::Border {
-                radius: 2.0.into(),
+            });
+        let remaining = container(Space::new().height(Length::Fixed(4.0)))
+            .width(Length::FillPortion(100u16.saturating_sub(bar_pct).max(1)))
+            .style(|_theme: &Theme| container::Style {
+                background: Some(iced::Background::Color(PROGRESS_REMAINING)),
+                border: iced::Border {
+                    radius: 2.0.into(),
+                    ..Default::default()
+                },
                 ..Default::default()
-            },
-            ..Default::default()
-        });
+            });
+        row![filled, remaining]
+            .spacing(0)
+            .width(Length::Fill)
+            .into()
+    };
 
-    container(
-        row![
-            row![filled, remaining].spacing(0).width(Length::Fill),
-            time_label
-        ]
-        .spacing(8)
-        .align_y(Alignment::Center),
-    )
-    .padding(Padding::from([4, 16]))
-    .width(Length::Fill)
-    .style(|_theme: &Theme| container::Style {
-        background: Some(iced::Background::Color(Color::from_rgb(0.09, 0.10, 0.12))),
-        ..Default::default()
-    })
-    .into()
+    container(row![bar, time_label].spacing(8).align_y(Alignment::Center))
+        .padding(Padding::from([4, 16]))
+        .width(Length::Fill)
+        .style(|_theme: &Theme| container::Style {
+            background: Some(iced::Background::Color(Color::from_rgb(0.09, 0.10, 0.12))),
+            ..Default::default()
+        })
+        .into()
 }
 
 /// Build the transport controls bar (play/pause, prev/next, shuffle, repeat,
@@ -783,27 +901,28 @@ pub fn controls_bar<'a>(
         _ => "▶",
     };
 
+    let small_style = |_theme: &Theme, st: button::Status| {
+        let bg = match st {
+            button::Status::Hovered => Color::from_rgb(0.25, 0.27, 0.32),
+            button::Status::Pressed => Color::from_rgb(0.18, 0.20, 0.24),
+            _ => Color::from_rgb(0.18, 0.19, 0.22),
+        };
+        button::Style {
+            background: Some(iced::Background::Color(bg)),
+            text_color: Color::from_rgb(0.8, 0.82, 0.88),
+            border: iced::Border {
+                radius: 3.0.into(),
+                width: 1.0,
+                color: Color::from_rgb(0.25, 0.27, 0.30),
+            },
+            ..Default::default()
+        }
+    };
     let small_button = |label: &'a str, msg: Message| -> Element<'a, Message> {
         button(text(label).size(font::sized(btn_size)))
             .on_press(msg)
             .padding(Padding::from([btn_pad, if compact { 6 } else { 10 }]))
-            .style(|_theme: &Theme, st| {
-                let bg = match st {
-                    button::Status::Hovered => Color::from_rgb(0.25, 0.27, 0.32),
-                    button::Status::Pressed => Color::from_rgb(0.18, 0.20, 0.24),
-                    _ => Color::from_rgb(0.18, 0.19, 0.22),
-                };
-                button::Style {
-                    background: Some(iced::Background::Color(bg)),
-                    text_color: Color::from_rgb(0.8, 0.82, 0.88),
-                    border: iced::Border {
-                        radius: 3.0.into(),
-                        width: 1.0,
-                        color: Color::from_rgb(0.25, 0.27, 0.30),
-                    },
-                    ..Default::default()
-                }
-            })
+            .style(small_style)
             .into()
     };
 
@@ -876,6 +995,32 @@ pub fn controls_bar<'a>(
     ]
     .spacing(4);
 
+    // Tempo: −/+ in 10% steps, the percentage itself resets to 100%.
+    let pitch_follows = crate::tempo::pitch_follows();
+    let tempo_btn: Element<'a, Message> =
+        button(text(format!("{}%", crate::tempo::current())).size(font::sized(btn_size)))
+            .on_press(Message::TempoReset)
+            .padding(Padding::from([btn_pad, if compact { 6 } else { 10 }]))
+            .style(small_style)
+            .into();
+    let tempo_controls = row![
+        with_tip(small_button("−", Message::TempoNudge(-1)), "Slower ([)"),
+        with_tip(tempo_btn, "Tempo — click to reset to 100%"),
+        with_tip(small_button("+", Message::TempoNudge(1)), "Faster (])"),
+        with_tip(
+            small_button(
+                if pitch_follows { "♪ On" } else { "♪ Off" },
+                Message::TogglePitchFollows,
+            ),
+            if pitch_follows {
+                "Pitch follows speed: on — software engines retune with the tempo"
+            } else {
+                "Pitch follows speed: off — notes keep their pitch"
+            },
+        ),
+    ]
+    .spacing(4);
+
     let shuffle_tip = if playlist.shuffle {
         "Shuffle: on — click to disable (Shift+H)"
     } else {
@@ -1116,9 +1261,17 @@ pub fn controls_bar<'a>(
     //   Row 1: PLAYBACK (transport ┃ subtune ┃ mode)
     //   Row 2: LIBRARY  (file ops ┃ panel toggles)  + optional update badge
     let row_spacing = if compact { 6 } else { 8 };
-    let top_row = row![transport, sep(), subtune_controls, sep(), mode_controls]
-        .spacing(row_spacing)
-        .align_y(Alignment::Center);
+    let top_row = row![
+        transport,
+        sep(),
+        subtune_controls,
+        sep(),
+        tempo_controls,
+        sep(),
+        mode_controls
+    ]
+    .spacing(row_spacing)
+    .align_y(Alignment::Center);
 
     let mut bottom_row = row![
         file_ops,
@@ -1581,9 +1734,18 @@ pub fn context_menu_overlay<'a>(
         "♡  Add to favorites"
     };
 
+    let clock_label = match playlist.entries.get(track_idx).and_then(|e| e.force_pal) {
+        None => "◷   Clock: from tune",
+        Some(true) => "◷   Clock: forced PAL",
+        Some(false) => "◷   Clock: forced NTSC",
+    };
+
     let menu_width = 210.0_f32;
     let item_height = 32.0_f32;
-    let menu_height = item_height * 5.0 + 8.0;
+    // The live tracker history only exists for the tune that's playing.
+    let live_dump = playlist.current == Some(track_idx);
+    let item_count = if live_dump { 9.0 } else { 8.0 };
+    let menu_height = item_height * item_count + 8.0;
 
     // Flip so menu never goes off-screen
     let menu_x = if x + menu_width > window_width {
@@ -1615,32 +1777,39 @@ pub fn context_menu_overlay<'a>(
             .into()
     };
 
-    let menu_box = container(
-        column![
-            item("▶   Play", Message::ContextMenuPlay),
-            item("⤒   Move to top", Message::ContextMenuMoveToTop),
-            item(fav_label, Message::ContextMenuToggleFavorite),
-            item("⧉   Copy title", Message::ContextMenuCopyTitle),
-            item("✕   Remove from playlist", Message::ContextMenuRemove),
-        ]
-        .spacing(0)
-        .width(Length::Fixed(menu_width)),
-    )
-    .padding(Padding::from([4, 0]))
-    .style(|_theme: &Theme| container::Style {
-        background: Some(iced::Background::Color(Color::from_rgb(0.15, 0.16, 0.20))),
-        border: iced::Border {
-            radius: 5.0.into(),
-            width: 1.0,
-            color: Color::from_rgb(0.28, 0.30, 0.36),
-        },
-        shadow: iced::Shadow {
-            color: Color::from_rgba(0.0, 0.0, 0.0, 0.5),
-            offset: iced::Vector::new(2.0, 4.0),
-            blur_radius: 8.0,
-        },
-        ..Default::default()
-    });
+    let mut items = column![
+        item("▶   Play", Message::ContextMenuPlay),
+        item("⤒   Move to top", Message::ContextMenuMoveToTop),
+        item(fav_label, Message::ContextMenuToggleFavorite),
+        item("⧉   Copy title", Message::ContextMenuCopyTitle),
+        item(clock_label, Message::ContextMenuCycleClock),
+        item("⇩   Export SIDdump…", Message::ContextMenuExportDump),
+        item("⇩   Export as PRG…", Message::ContextMenuExportPrg),
+    ];
+    if live_dump {
+        items = items.push(item(
+            "⇩   Export recent frames…",
+            Message::ContextMenuExportLiveDump,
+        ));
+    }
+    items = items.push(item("✕   Remove from playlist", Message::ContextMenuRemove));
+
+    let menu_box = container(items.spacing(0).width(Length::Fixed(menu_width)))
+        .padding(Padding::from([4, 0]))
+        .style(|_theme: &Theme| container::Style {
+            background: Some(iced::Background::Color(Color::from_rgb(0.15, 0.16, 0.20))),
+            border: iced::Border {
+                radius: 5.0.into(),
+                width: 1.0,
+                color: Color::from_rgb(0.28, 0.30, 0.36),
+            },
+            shadow: iced::Shadow {
+                color: Color::from_rgba(0.0, 0.0, 0.0, 0.5),
+                offset: iced::Vector::new(2.0, 4.0),
+                blur_radius: 8.0,
+            },
+            ..Default::default()
+        });
 
     // Transparent full-screen dismiss area — captures clicks but NOT scroll events,
     // so the playlist underneath can still be scrolled while the menu is open.
@@ -3193,6 +3362,10 @@ pub fn settings_panel<'a>(
     // Which tab is currently in view — selects which section subset is
     // composed into the scrollable content column at the end.
     active_tab: SettingsTab,
+    // Output devices from the last scan; `None` while not yet scanned.
+    audio_devices: Option<&'a [String]>,
+    // Title and emulation-tuning override of the playing tune, if any.
+    current_tune: Option<(&'a str, crate::emu_tuning::TuneOverride)>,
 ) -> Element<'a, Message> {
     let header = row![
         text("Settings")
@@ -3234,6 +3407,7 @@ pub fn settings_panel<'a>(
             "emulated" => "🎵 Software Emulation (reSID)",
             "sidlite" => "🎶 SIDLite Emulation (libsidplayfp)",
             "u64" => "🌐 Ultimate 64 (Network)",
+            "replay" => "⏵ Replay SID write log",
             other => other,
         };
         let is_active = current_engine == name;
@@ -3282,6 +3456,19 @@ pub fn settings_panel<'a>(
                 .size(font::sized(11.0))
                 .color(Color::from_rgb(0.45, 0.47, 0.52)),
         )
+        .push(tool_button(
+            if config.u64_run_as_prg {
+                "✓ Start tunes as PRG (no SID player)"
+            } else {
+                "✗ Start tunes with the U64 SID player"
+            },
+            Message::ToggleU64RunAsPrg,
+        ))
+        .push(
+            text("PRG mode runs each tune as a standalone program. The U64's on-screen timer is not available then, so elapsed time comes from this machine.")
+                .size(font::sized(11.0))
+                .color(Color::from_rgb(0.45, 0.47, 0.52)),
+        )
         .push(rule::horizontal(1))
         .push(
             text("U64 audio streaming:")
@@ -3316,6 +3503,307 @@ pub fn settings_panel<'a>(
                 .color(Color::from_rgb(0.45, 0.47, 0.52)),
         );
 
+    // ── Per-chip SID model (software engines) ────────────────────
+    let mut model_rows = column![].spacing(6);
+    for (chip, &forced) in config.sid_models.iter().enumerate() {
+        let mut model_row = iced::widget::row![text(format!("SID{}:", chip + 1))
+            .size(font::sized(11.0))
+            .color(Color::from_rgb(0.65, 0.67, 0.72))
+            .width(Length::Fixed(40.0))]
+        .spacing(8)
+        .align_y(Alignment::Center);
+        for (model, label) in [(0u8, "From tune"), (1, "6581"), (2, "8580")] {
+            let is_active = forced == model;
+            model_row = model_row.push(
+                button(text(label).size(font::sized(12.0)))
+                    .on_press(Message::SetSidModelOverride(chip, model))
+                    .padding(Padding::from([4, 10]))
+                    .style(move |_theme: &Theme, st| engine_btn_style(is_active, st)),
+            );
+        }
+        model_rows = model_rows.push(model_row);
+    }
+    let sid_model_section = column![
+        text("SID chip models:")
+            .size(font::sized(14.0))
+            .color(Color::from_rgb(0.75, 0.77, 0.82)),
+        model_rows,
+        text(
+            "reSID and SIDLite emulate each chip of a multi-SID tune with the model its \
+             header asks for. Force a model per chip here; applies from the next tune load."
+        )
+        .size(font::sized(11.0))
+        .color(Color::from_rgb(0.45, 0.47, 0.52)),
+    ]
+    .spacing(6);
+
+    // ── Emulation tuning (digi boost / 6581 filter / stereo) ─────
+    use crate::emu_tuning::{FilterPreset, StereoLayout};
+    let choice_button = |label: &'a str, is_active: bool, msg: Message| {
+        button(text(label).size(font::sized(12.0)))
+            .on_press(msg)
+            .padding(Padding::from([4, 10]))
+            .style(move |_theme: &Theme, st| engine_btn_style(is_active, st))
+    };
+    let row_label = |label: &'a str| {
+        text(label)
+            .size(font::sized(11.0))
+            .color(Color::from_rgb(0.65, 0.67, 0.72))
+            .width(Length::Fixed(90.0))
+    };
+    let global_filter = FilterPreset::from_name(&config.filter_6581).unwrap_or_default();
+    let mut filter_row = iced::widget::row![row_label("6581 filter:")]
+        .spacing(8)
+        .align_y(Alignment::Center);
+    for preset in FilterPreset::ALL {
+        filter_row = filter_row.push(choice_button(
+            preset.label(),
+            global_filter == preset,
+            Message::SetFilter6581(preset),
+        ));
+    }
+    let global_stereo = StereoLayout::from_name(&config.stereo_layout).unwrap_or_default();
+    let mut stereo_row = iced::widget::row![row_label("Stereo:")]
+        .spacing(8)
+        .align_y(Alignment::Center);
+    for layout in StereoLayout::ALL {
+        stereo_row = stereo_row.push(choice_button(
+            layout.label(),
+            global_stereo == layout,
+            Message::SetStereoLayout(layout),
+        ));
+    }
+    let mut pan_rows = column![].spacing(6);
+    if global_stereo == StereoLayout::Custom {
+        for (chip, &pan) in config.stereo_pan.iter().enumerate() {
+            let mut pan_row = iced::widget::row![text(format!("SID{}:", chip + 1))
+                .size(font::sized(11.0))
+                .color(Color::from_rgb(0.65, 0.67, 0.72))
+                .width(Length::Fixed(90.0))]
+            .spacing(8)
+            .align_y(Alignment::Center);
+            for (value, label) in [(-100i8, "L"), (-50, "L½"), (0, "C"), (50, "R½"), (100, "R")] {
+                pan_row = pan_row.push(choice_button(
+                    label,
+                    pan == value,
+                    Message::SetStereoPan(chip, value),
+                ));
+            }
+            pan_rows = pan_rows.push(pan_row);
+        }
+    }
+    let mut crossfeed_row = iced::widget::row![row_label("Crossfeed:")]
+        .spacing(8)
+        .align_y(Alignment::Center);
+    for (pct, label) in [(0u32, "Off"), (30, "Light"), (60, "Strong")] {
+        crossfeed_row = crossfeed_row.push(choice_button(
+            label,
+            config.crossfeed_pct == pct,
+            Message::SetCrossfeed(pct),
+        ));
+    }
+    let mut tuning_section = column![
+        text("Emulation tuning (reSID / SIDLite):")
+            .size(font::sized(14.0))
+            .color(Color::from_rgb(0.75, 0.77, 0.82)),
+        tool_button(
+            if config.digi_boost_8580 {
+                "✓ 8580 digi boost on"
+            } else {
+                "✗ 8580 digi boost off"
+            },
+            Message::ToggleDigiBoost8580,
+        ),
+        filter_row,
+        stereo_row,
+        pan_rows,
+        crossfeed_row,
+        text(
+            "Digi boost makes $D418 sample playback audible on 8580 chips. The 6581 filter \
+             preset shifts the cutoff to match darker or brighter chips. Stereo places the \
+             chips of a multi-SID tune: Classic is SID1 left, SID2 right, SID3/SID4 centred; \
+             Spread fans them out evenly. Crossfeed softens hard-panned tunes on headphones."
+        )
+        .size(font::sized(11.0))
+        .color(Color::from_rgb(0.45, 0.47, 0.52)),
+    ]
+    .spacing(6);
+    if let Some((title, ov)) = current_tune {
+        let mut digi_row = iced::widget::row![row_label("Digi boost:")]
+            .spacing(8)
+            .align_y(Alignment::Center);
+        for (label, value) in [("Default", None), ("On", Some(true)), ("Off", Some(false))] {
+            digi_row = digi_row.push(choice_button(
+                label,
+                ov.digi_boost_8580 == value,
+                Message::SetTuneDigiBoost(value),
+            ));
+        }
+        let mut tune_filter_row = iced::widget::row![
+            row_label("6581 filter:"),
+            choice_button(
+                "Default",
+                ov.filter_6581.is_none(),
+                Message::SetTuneFilter6581(None),
+            ),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+        for preset in FilterPreset::ALL {
+            tune_filter_row = tune_filter_row.push(choice_button(
+                preset.label(),
+                ov.filter_6581 == Some(preset),
+                Message::SetTuneFilter6581(Some(preset)),
+            ));
+        }
+        let mut tune_stereo_row = iced::widget::row![
+            row_label("Stereo:"),
+            choice_button("Default", ov.stereo.is_none(), Message::SetTuneStereo(None)),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+        for layout in StereoLayout::ALL {
+            tune_stereo_row = tune_stereo_row.push(choice_button(
+                layout.label(),
+                ov.stereo == Some(layout),
+                Message::SetTuneStereo(Some(layout)),
+            ));
+        }
+        tuning_section = tuning_section
+            .push(
+                text(format!("This tune only — {title}:"))
+                    .size(font::sized(12.0))
+                    .color(Color::from_rgb(0.65, 0.67, 0.72)),
+            )
+            .push(digi_row)
+            .push(tune_filter_row)
+            .push(tune_stereo_row);
+    }
+    tuning_section = tuning_section.push(
+        text("Changes apply from the next tune load.")
+            .size(font::sized(11.0))
+            .color(Color::from_rgb(0.45, 0.47, 0.52)),
+    );
+
+    // ── PSID CPU core ────────────────────────────────────────────
+    let psid_core_section = column![
+        text("PSID CPU:")
+            .size(font::sized(14.0))
+            .color(Color::from_rgb(0.75, 0.77, 0.82)),
+        tool_button(
+            if config.psid_cycle_exact {
+                "✓ Cycle-exact C64 core"
+            } else {
+                "✗ libsidplayfp (default)"
+            },
+            Message::TogglePsidCycleExact
+        ),
+        text(
+            "Runs PSID tunes on the built-in cycle-exact C64 core — VIC badlines, real \
+             CIA timing, PLAY called from a raster or timer IRQ — so mid-frame writes reach \
+             the device at the cycle they happen. Applies from the next tune load."
+        )
+        .size(font::sized(11.0))
+        .color(Color::from_rgb(0.45, 0.47, 0.52)),
+    ]
+    .spacing(6);
+
+    // ── A/B mirror ───────────────────────────────────────────────
+    let mut mirror_row = iced::widget::row![text("Mirror to:")
+        .size(font::sized(11.0))
+        .color(Color::from_rgb(0.65, 0.67, 0.72))]
+    .spacing(8)
+    .align_y(Alignment::Center);
+    for (name, label) in [
+        ("none", "Off"),
+        ("usb", "USB"),
+        ("emulated", "reSID"),
+        ("sidlite", "SIDLite"),
+    ] {
+        let is_active = config.mirror_engine == name;
+        mirror_row = mirror_row.push(
+            button(text(label).size(font::sized(12.0)))
+                .on_press(Message::SetMirrorEngine(name.to_string()))
+                .padding(Padding::from([4, 10]))
+                .style(move |_theme: &Theme, st| engine_btn_style(is_active, st)),
+        );
+    }
+    let mirror_section = column![
+        text("A/B mirror:")
+            .size(font::sized(14.0))
+            .color(Color::from_rgb(0.75, 0.77, 0.82)),
+        mirror_row,
+        text(
+            "Feeds the same writes to a second engine alongside the USB, reSID or SIDLite \
+             output engine. Only one is heard — press A to switch sides mid-tune."
+        )
+        .size(font::sized(11.0))
+        .color(Color::from_rgb(0.45, 0.47, 0.52)),
+    ]
+    .spacing(6);
+
+    // ── SID write log (record / replay) ──────────────────────────
+    let recording = crate::sid_log::is_recording();
+    let replay_name = config
+        .replay_file
+        .as_deref()
+        .and_then(|p| std::path::Path::new(p).file_name())
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "(none)".to_string());
+    let mut replay_row = iced::widget::row![text("Replay on:")
+        .size(font::sized(11.0))
+        .color(Color::from_rgb(0.65, 0.67, 0.72))]
+    .spacing(8)
+    .align_y(Alignment::Center);
+    for (name, label) in [
+        ("usb", "USB"),
+        ("emulated", "reSID"),
+        ("sidlite", "SIDLite"),
+    ] {
+        let is_active = config.replay_engine == name;
+        replay_row = replay_row.push(
+            button(text(label).size(font::sized(12.0)))
+                .on_press(Message::SetReplayEngine(name.to_string()))
+                .padding(Padding::from([4, 10]))
+                .style(move |_theme: &Theme, st| engine_btn_style(is_active, st)),
+        );
+    }
+    let write_log_section = column![
+        text("SID write log:")
+            .size(font::sized(14.0))
+            .color(Color::from_rgb(0.75, 0.77, 0.82)),
+        tool_button(
+            if recording {
+                "⏹ Stop recording"
+            } else {
+                "⏺ Record write log…"
+            },
+            Message::ToggleWriteLog,
+        ),
+        text(
+            "Records every register write the current engine receives, cycle stamps \
+             included, from the next tune load until the one after."
+        )
+        .size(font::sized(11.0))
+        .color(Color::from_rgb(0.45, 0.47, 0.52)),
+        iced::widget::row![
+            tool_button("📂 Replay file…", Message::PickReplayFile),
+            text(replay_name)
+                .size(font::sized(12.0))
+                .color(Color::from_rgb(0.65, 0.67, 0.72)),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        replay_row,
+        text(
+            "The Replay engine plays the log instead of the tune's own writes. \
+             Load the same tune that was recorded — it sets the frame pacing."
+        )
+        .size(font::sized(11.0))
+        .color(Color::from_rgb(0.45, 0.47, 0.52)),
+    ]
+    .spacing(6);
+
     // ── Surprise Me source ───────────────────────────────────────
     let surprise_playlist = config.surprise_source == "playlist";
     let surprise_section = column![
@@ -3604,6 +4092,188 @@ pub fn settings_panel<'a>(
     ]
     .spacing(6);
 
+    // ── Fade-out ─────────────────────────────────────────────────
+    let fade_button = |secs: u32, label: &'a str| -> Element<'a, Message> {
+        let is_selected = config.fade_out_secs == secs;
+        button(text(label).size(font::sized(12.0)))
+            .on_press(Message::SetFadeOutSecs(secs))
+            .padding(Padding::from([6, 12]))
+            .style(move |_t: &Theme, st| engine_btn_style(is_selected, st))
+            .into()
+    };
+    let fade_section = column![
+        text("Fade-out at song end:")
+            .size(font::sized(14.0))
+            .color(Color::from_rgb(0.75, 0.77, 0.82)),
+        row![
+            fade_button(0, "Off"),
+            fade_button(2, "2 s"),
+            fade_button(3, "3 s"),
+            fade_button(5, "5 s"),
+            fade_button(8, "8 s"),
+        ]
+        .spacing(8),
+        text(
+            "Fades the tune out before the song length runs out and before the sleep \
+             timer stops playback. Off = hard cut."
+        )
+        .size(font::sized(11.0))
+        .color(Color::from_rgb(0.45, 0.47, 0.52)),
+    ]
+    .spacing(6);
+
+    // ── Loudness normalisation ───────────────────────────────────
+    let loudness_button = |lufs: i32, label: &'a str| -> Element<'a, Message> {
+        let is_selected = config.loudness_target_lufs == lufs;
+        button(text(label).size(font::sized(12.0)))
+            .on_press(Message::SetLoudnessTarget(lufs))
+            .padding(Padding::from([6, 12]))
+            .style(move |_t: &Theme, st| engine_btn_style(is_selected, st))
+            .into()
+    };
+    let loudness_section = column![
+        text("Loudness normalisation:")
+            .size(font::sized(14.0))
+            .color(Color::from_rgb(0.75, 0.77, 0.82)),
+        row![
+            loudness_button(0, "Off"),
+            loudness_button(-23, "−23 LUFS"),
+            loudness_button(-18, "−18 LUFS"),
+            loudness_button(-14, "−14 LUFS"),
+        ]
+        .spacing(8),
+        text(
+            "Evens out volume between tunes on reSID, SIDLite and the U64 audio stream. \
+             Each tune is measured once in the background, from a short offline render; \
+             its level settles a moment after the first play."
+        )
+        .size(font::sized(11.0))
+        .color(Color::from_rgb(0.45, 0.47, 0.52)),
+    ]
+    .spacing(6);
+
+    // ── Audio output ─────────────────────────────────────────────
+    let device_button = |device: Option<&str>, label: String| -> Element<'a, Message> {
+        let is_selected = config.audio_device.as_deref() == device;
+        button(text(label).size(font::sized(12.0)))
+            .on_press(Message::SetAudioDevice(device.map(str::to_string)))
+            .padding(Padding::from([4, 10]))
+            .width(Length::Fill)
+            .style(move |_t: &Theme, st| engine_btn_style(is_selected, st))
+            .into()
+    };
+    let mut device_col = column![device_button(None, "System default".to_string())].spacing(4);
+    match audio_devices {
+        Some(devices) => {
+            for name in devices {
+                device_col = device_col.push(device_button(Some(name.as_str()), name.clone()));
+            }
+            // Keep an unplugged choice visible so it can be switched off.
+            if let Some(ref wanted) = config.audio_device {
+                if !devices.contains(wanted) {
+                    device_col = device_col.push(device_button(
+                        Some(wanted.as_str()),
+                        format!("{wanted} (not found)"),
+                    ));
+                }
+            }
+        }
+        None => {
+            device_col = device_col.push(
+                text("Scanning…")
+                    .size(font::sized(11.0))
+                    .color(Color::from_rgb(0.45, 0.47, 0.52)),
+            );
+        }
+    }
+    let rate_button = |rate: u32, label: &'a str| -> Element<'a, Message> {
+        let is_selected = config.audio_sample_rate == rate;
+        button(text(label).size(font::sized(12.0)))
+            .on_press(Message::SetAudioSampleRate(rate))
+            .padding(Padding::from([6, 12]))
+            .style(move |_t: &Theme, st| engine_btn_style(is_selected, st))
+            .into()
+    };
+    let latency_button = |ms: u32, label: &'a str| -> Element<'a, Message> {
+        let is_selected = config.audio_latency_ms == ms;
+        button(text(label).size(font::sized(12.0)))
+            .on_press(Message::SetAudioLatency(ms))
+            .padding(Padding::from([6, 12]))
+            .style(move |_t: &Theme, st| engine_btn_style(is_selected, st))
+            .into()
+    };
+    let output_section = column![
+        row![
+            text("Output device:")
+                .size(font::sized(14.0))
+                .color(Color::from_rgb(0.75, 0.77, 0.82)),
+            Space::new().width(Length::Fill),
+            tool_button("↻ Rescan", Message::ScanAudioDevices),
+        ]
+        .align_y(Alignment::Center),
+        device_col,
+        text("Sample rate:")
+            .size(font::sized(11.0))
+            .color(Color::from_rgb(0.65, 0.67, 0.72)),
+        row![
+            rate_button(0, "Device default"),
+            rate_button(44_100, "44.1 kHz"),
+            rate_button(48_000, "48 kHz"),
+            rate_button(96_000, "96 kHz"),
+        ]
+        .spacing(8),
+        text("Latency:")
+            .size(font::sized(11.0))
+            .color(Color::from_rgb(0.65, 0.67, 0.72)),
+        row![
+            latency_button(0, "Default"),
+            latency_button(20, "20 ms"),
+            latency_button(50, "50 ms"),
+            latency_button(100, "100 ms"),
+            latency_button(200, "200 ms"),
+        ]
+        .spacing(8),
+        text(
+            "Used by reSID, SIDLite and the U64 audio stream; the engine reopens on change. \
+             A missing device or unsupported rate falls back to the default, and SIDLite \
+             runs at 48 kHz at most. Lower latency reacts faster but may crackle on a busy \
+             machine."
+        )
+        .size(font::sized(11.0))
+        .color(Color::from_rgb(0.45, 0.47, 0.52)),
+    ]
+    .spacing(6);
+
+    // ── Silence skip ─────────────────────────────────────────────
+    let silence_button = |secs: u32, label: &'a str| -> Element<'a, Message> {
+        let is_selected = config.silence_skip_secs == secs;
+        button(text(label).size(font::sized(12.0)))
+            .on_press(Message::SetSilenceSkipSecs(secs))
+            .padding(Padding::from([6, 12]))
+            .style(move |_t: &Theme, st| engine_btn_style(is_selected, st))
+            .into()
+    };
+    let silence_section = column![
+        text("Skip after silence:")
+            .size(font::sized(14.0))
+            .color(Color::from_rgb(0.75, 0.77, 0.82)),
+        row![
+            silence_button(0, "Off"),
+            silence_button(3, "3 s"),
+            silence_button(5, "5 s"),
+            silence_button(10, "10 s"),
+            silence_button(20, "20 s"),
+        ]
+        .spacing(8),
+        text(
+            "Tunes with no Songlength entry move on once every voice has been quiet \
+             this long — catches tunes that stop playing but never end."
+        )
+        .size(font::sized(11.0))
+        .color(Color::from_rgb(0.45, 0.47, 0.52)),
+    ]
+    .spacing(6);
+
     // ── Songlength DB ────────────────────────────────────────────
     let dl_color = if download_status.contains("Error") || download_status.contains("fail") {
         Color::from_rgb(1.0, 0.4, 0.4)
@@ -3860,11 +4530,13 @@ pub fn settings_panel<'a>(
         ("H", "Toggle favourite for current track"),
         ("Shift+H", "Toggle shuffle"),
         (", / .", "Nudge master volume −5% / +5%"),
+        ("[ / ]", "Tempo −10% / +10%"),
         ("V", "Cycle visualiser (Bars / Scope / Tracker / Karaoke)"),
         ("F", "Toggle full-screen visualiser"),
         ("K", "Toggle karaoke lyrics (MUS files)"),
         ("M", "Toggle mini player"),
         ("L", "Toggle 📚 Library panel"),
+        ("A", "Switch A/B mirror engine"),
         ("Ctrl+F", "Focus search"),
         ("?", "Show / hide help overlay"),
         ("Escape", "Close overlay / context menu"),
@@ -3958,6 +4630,10 @@ pub fn settings_panel<'a>(
                 .push(rule::horizontal(1))
                 .push(length_section)
                 .push(rule::horizontal(1))
+                .push(fade_section)
+                .push(rule::horizontal(1))
+                .push(silence_section)
+                .push(rule::horizontal(1))
                 .push(sleep_section)
                 .push(rule::horizontal(1))
                 .push(font_size_section);
@@ -3966,6 +4642,20 @@ pub fn settings_panel<'a>(
             tab_content = tab_content
                 .push(engine_col)
                 .push(rule::horizontal(1))
+                .push(output_section)
+                .push(rule::horizontal(1))
+                .push(loudness_section)
+                .push(rule::horizontal(1))
+                .push(sid_model_section)
+                .push(rule::horizontal(1))
+                .push(tuning_section)
+                .push(rule::horizontal(1))
+                .push(psid_core_section)
+                .push(rule::horizontal(1))
+                .push(mirror_section)
+                .push(rule::horizontal(1))
+                .push(write_log_section)
+                .push(rule::horizontal(1))
                 .push(macos_usb_section);
         }
         SettingsTab::Library => {
@@ -4290,6 +4980,7 @@ pub fn filter_playlist(
 /// Thin right-aligned footer bar showing HVSC completion stats.
 /// Mimics the foobar2000 status bar style.
 pub fn status_bar<'a>(
+    advance_notice: &'a str,
     heard_text: &'a str,
     hvsc_version_text: &'a str,
     hvsc_update_available: bool,
@@ -4324,10 +5015,23 @@ pub fn status_bar<'a>(
             .into()
     };
 
+    // Transient auto-advance note ("Silence — skipping"), left-aligned
+    // next to the help button so it doesn't shift the HVSC indicators.
+    let notice_element: Element<'a, Message> = if advance_notice.is_empty() {
+        Space::new().into()
+    } else {
+        text(advance_notice)
+            .size(font::sized(11.0))
+            .color(Color::from_rgb(0.95, 0.70, 0.30))
+            .into()
+    };
+
     container(
         row![
             Space::new().width(Length::Fixed(4.0)),
             help_btn,
+            Space::new().width(Length::Fixed(8.0)),
+            notice_element,
             Space::new().width(Length::Fill),
             hvsc_element,
             Space::new().width(Length::Fixed(16.0)),
@@ -4366,8 +5070,10 @@ pub fn help_overlay<'a>() -> Element<'a, Message> {
         ("H", "Toggle favourite for current track"),
         ("Shift+H", "Toggle shuffle"),
         (", / .", "Nudge master volume −5% / +5%"),
+        ("[ / ]", "Tempo −10% / +10%"),
         ("M", "Toggle mini player"),
         ("L", "Toggle 📚 Library panel"),
+        ("A", "Switch A/B mirror engine"),
         ("Ctrl+F", "Focus search"),
         ("Delete", "Remove selected track"),
         ("Escape / ?", "Close this overlay"),
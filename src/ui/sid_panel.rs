@@ -25,13 +25,14 @@
 use std::collections::VecDeque;
 
 use iced::widget::canvas::{self, Cache, Canvas, Frame, Geometry, Text};
-use iced::widget::{column, container, row, rule, text, Column, Space};
+use iced::widget::{button, column, container, row, rule, text, Column, Space};
 use iced::{
     mouse, Alignment, Color, Element, Font, Length, Padding, Point, Rectangle, Size, Theme,
 };
 
 use super::font;
 use super::Message;
+use crate::voice_mask::VoiceMask;
 
 // ─────────────────────────────────────────────────────────────────────────────
 //  SID constants
@@ -199,7 +200,7 @@ fn dim_color(c: Color, f: f32) -> Color {
 //  Register decoding helpers
 // ─────────────────────────────────────────────────────────────────────────────
 
-fn freq_to_hz(lo: u8, hi: u8, is_pal: bool) -> f64 {
+pub(crate) fn freq_to_hz(lo: u8, hi: u8, is_pal: bool) -> f64 {
     let word = ((hi as u32) << 8) | lo as u32;
     let clock = if is_pal { PAL_CLOCK } else { NTSC_CLOCK };
     word as f64 * clock / 16_777_216.0
@@ -207,7 +208,7 @@ fn freq_to_hz(lo: u8, hi: u8, is_pal: bool) -> f64 {
 
 /// Convert Hz → "C-4" style note name.  Uses a thread-local cache so we
 /// never allocate in the hot paint path.
-fn hz_to_note(hz: f64) -> &'static str {
+pub(crate) fn hz_to_note(hz: f64) -> &'static str {
     if hz < 16.0 {
         return "---";
     }
@@ -306,6 +307,8 @@ pub struct TrackerFrame {
     /// `voices[sid * 3 + voice_index]`
     pub voices: [TrackerVoice; 12],
     pub frame_idx: u64,
+    /// Raw register shadow for the active chips, kept for SIDdump export.
+    pub regs: Vec<u8>,
 }
 
 impl Default for TrackerFrame {
@@ -313,6 +316,7 @@ impl Default for TrackerFrame {
         Self {
             voices: std::array::from_fn(|_| TrackerVoice::default()),
             frame_idx: 0,
+            regs: Vec::new(),
         }
     }
 }
@@ -329,6 +333,9 @@ impl Default for TrackerFrame {
 pub struct TrackerHistory {
     pub frames: VecDeque<TrackerFrame>,
     pub frame_idx: u64,
+    /// Chip count and clock of the most recent push.
+    num_sids: usize,
+    is_pal: bool,
 }
 
 impl TrackerHistory {
@@ -336,6 +343,8 @@ impl TrackerHistory {
         Self {
             frames: VecDeque::with_capacity(TRACKER_HISTORY),
             frame_idx: 0,
+            num_sids: 1,
+            is_pal: true,
         }
     }
 
@@ -347,7 +356,12 @@ impl TrackerHistory {
         let mut frame = TrackerFrame {
             voices: std::array::from_fn(|_| TrackerVoice::default()),
             frame_idx: self.frame_idx,
+            regs: (0..n * SID_STRIDE)
+                .map(|i| sid_regs.get(i).copied().unwrap_or(0))
+                .collect(),
         };
+        self.num_sids = n;
+        self.is_pal = is_pal;
 
         for sid in 0..n {
             let base = sid * SID_STRIDE;
@@ -383,6 +397,16 @@ impl TrackerHistory {
         self.frames.clear();
         self.frame_idx = 0;
     }
+
+    /// Render the buffered frames as a SIDdump-style text dump. Rows are
+    /// GUI ticks rather than player frames, numbered by `frame_idx`.
+    pub fn siddump(&self) -> String {
+        let mut dump = crate::siddump::SidDump::new(self.num_sids, self.is_pal);
+        for f in &self.frames {
+            dump.push(f.frame_idx, &f.regs);
+        }
+        dump.finish()
+    }
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -429,11 +453,13 @@ impl TrackerView {
         &'a self,
         history: &'a TrackerHistory,
         num_sids: usize,
+        mask: VoiceMask,
         _height: f32,
     ) -> Element<'a, Message> {
         Canvas::new(TrackerCanvas {
             history,
             num_sids,
+            mask,
             cache: &self.cache,
         })
         .width(Length::Fill)
@@ -449,6 +475,7 @@ impl TrackerView {
 struct TrackerCanvas<'a> {
     history: &'a TrackerHistory,
     num_sids: usize,
+    mask: VoiceMask,
     cache: &'a Cache,
 }
 
@@ -465,10 +492,64 @@ impl<'a> canvas::Program<Message> for TrackerCanvas<'a> {
     ) -> Vec<Geometry> {
         let n = self.num_sids.clamp(1, 4);
         let geom = self.cache.draw(renderer, bounds.size(), |frame| {
-            paint_tracker_compact(frame, bounds, &self.history.frames, n);
+            paint_tracker_compact(frame, bounds, &self.history.frames, n, self.mask);
         });
         vec![geom]
     }
+
+    /// Clicking a voice column follows `voice_click`.
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: &canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<canvas::Action<Message>> {
+        let canvas::Event::Mouse(mouse::Event::ButtonReleased(btn)) = event else {
+            return None;
+        };
+        let pos = cursor.position_in(bounds)?;
+        let vi = tracker_voice_at(pos.x, bounds.width, self.num_sids.clamp(1, 4))?;
+        voice_click(*btn, vi).map(canvas::Action::publish)
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if cursor.is_over(bounds) {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
+
+/// The one mouse mapping for voices, shared by the tracker panel and the
+/// compact visualiser: right-click mutes voice `vi` (`sid * 3 + voice`),
+/// middle-click solos it. Left-click stays free for the visualiser's own
+/// mode cycling.
+pub(crate) fn voice_click(btn: mouse::Button, vi: usize) -> Option<Message> {
+    let (sid, voice) = (vi / 3, vi % 3);
+    match btn {
+        mouse::Button::Right => Some(Message::ToggleVoiceMute(sid, voice)),
+        mouse::Button::Middle => Some(Message::ToggleVoiceSolo(sid, voice)),
+        _ => None,
+    }
+}
+
+/// Voice column (`sid * 3 + voice`) under canvas-local `x`, using the same
+/// column layout as `paint_tracker_compact`. `None` over the row gutter.
+pub(crate) fn tracker_voice_at(x: f32, width: f32, num_sids: usize) -> Option<usize> {
+    let num_voices = num_sids * 3;
+    if x < GUTTER_W || num_voices == 0 {
+        return None;
+    }
+    let col_w = ((width - GUTTER_W) / num_voices as f32).max(40.0);
+    let vi = ((x - GUTTER_W) / col_w) as usize;
+    (vi < num_voices).then_some(vi)
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -480,6 +561,7 @@ pub fn paint_tracker_compact(
     bounds: Rectangle,
     history: &VecDeque<TrackerFrame>,
     num_sids: usize,
+    mask: VoiceMask,
 ) {
     let w = bounds.width;
     let h = bounds.height.max(180.0);
@@ -578,8 +660,20 @@ pub fn paint_tracker_compact(
         for voice in 0..3 {
             let vi = sid * 3 + voice;
             let cx = GUTTER_W + vi as f32 * col_w + col_w * 0.5;
-            let lbl = intern_str(format!("S{} V{}", sid + 1, voice + 1));
-            px_label(frame, lbl, cx, hdr_h / 2.0, bright, true);
+            let flag = if mask.is_muted(sid, voice) {
+                " M"
+            } else if mask.is_soloed(sid, voice) {
+                " S"
+            } else {
+                ""
+            };
+            let lbl = intern_str(format!("S{} V{}{}", sid + 1, voice + 1, flag));
+            let color = if mask.is_audible(sid, voice) {
+                bright
+            } else {
+                SID_DIM[sid]
+            };
+            px_label(frame, lbl, cx, hdr_h / 2.0, color, true);
         }
     }
 
@@ -722,6 +816,22 @@ pub fn paint_tracker_compact(
         }
     }
 
+    // ── Muted-voice shading ───────────────────────────────────────────────────
+    for vi in 0..num_voices {
+        if !mask.is_audible(vi / 3, vi % 3) {
+            frame.fill_rectangle(
+                Point::new(GUTTER_W + vi as f32 * col_w + 1.0, hdr_h),
+                Size::new(col_w - 1.0, h - hdr_h - 14.0),
+                Color {
+                    r: 0.0,
+                    g: 0.0,
+                    b: 0.0,
+                    a: 0.6,
+                },
+            );
+        }
+    }
+
     // ── Footer status bar ─────────────────────────────────────────────────────
     let foot_top = h - 14.0;
     frame.fill_rectangle(
@@ -805,6 +915,7 @@ pub fn sid_panel<'a>(
     sid_regs: &[u8],
     num_sids: usize,
     is_pal: bool,
+    mask: VoiceMask,
     tracker_height: f32,
 ) -> Element<'a, Message> {
     // Nothing playing — friendly placeholder.
@@ -827,7 +938,7 @@ pub fn sid_panel<'a>(
     let n = num_sids.clamp(1, 4);
 
     // ── Tracker canvas (top section) ──────────────────────────────────────────
-    let tracker_elem = tracker_view.view(tracker_history, n, tracker_height);
+    let tracker_elem = tracker_view.view(tracker_history, n, mask, tracker_height);
 
     // ── Register detail panels (bottom section) ───────────────────────────────
     // The chip panels must align with the tracker canvas columns.
@@ -837,7 +948,7 @@ pub fn sid_panel<'a>(
     // No rule::vertical separators — the tracker's own column lines are the dividers.
     let mut chips: Vec<Element<'a, Message>> = Vec::with_capacity(n);
     for sid in 0..n {
-        chips.push(sid_chip_panel(sid_regs, sid, is_pal));
+        chips.push(sid_chip_panel(sid_regs, sid, is_pal, mask));
     }
 
     let chip_row = iced::widget::Row::with_children({
@@ -881,13 +992,29 @@ pub fn sid_panel<'a>(
 //  Per-chip register panel
 // ─────────────────────────────────────────────────────────────────────────────
 
-fn sid_chip_panel<'a>(regs: &[u8], sid: usize, is_pal: bool) -> Element<'a, Message> {
+fn sid_chip_panel<'a>(
+    regs: &[u8],
+    sid: usize,
+    is_pal: bool,
+    mask: VoiceMask,
+) -> Element<'a, Message> {
     let base = sid * SID_STRIDE;
     let accent = SID_ACCENT.get(sid).copied().unwrap_or(Color::WHITE);
     let label = format!("SID {}", sid + 1);
+    let all_muted = (0..3).all(|v| mask.is_muted(sid, v));
+    let all_soloed = (0..3).all(|v| mask.is_soloed(sid, v));
 
     let mut col = Column::new().spacing(5);
-    col = col.push(text(label).size(font::sized(11.0)).color(accent));
+    col = col.push(
+        row![
+            text(label).size(font::sized(11.0)).color(accent),
+            Space::new().width(Length::Fill),
+            mask_button("M", all_muted, accent, Message::ToggleSidMute(sid)),
+            mask_button("S", all_soloed, accent, Message::ToggleSidSolo(sid)),
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center),
+    );
     col = col.push(rule::horizontal(1));
 
     for voice in 0..3 {
@@ -906,6 +1033,37 @@ fn sid_chip_panel<'a>(regs: &[u8], sid: usize, is_pal: bool) -> Element<'a, Mess
         .into()
 }
 
+/// Tiny per-chip mute/solo toggle shown next to the "SID n" label.
+fn mask_button<'a>(
+    label: &'a str,
+    active: bool,
+    accent: Color,
+    msg: Message,
+) -> Element<'a, Message> {
+    button(text(label).size(font::sized(10.0)))
+        .on_press(msg)
+        .padding(Padding::from([0, 5]))
+        .style(move |_theme: &Theme, st| button::Style {
+            background: Some(iced::Background::Color(match (active, st) {
+                (true, _) => dim_color(accent, 0.45),
+                (false, button::Status::Hovered) => Color::from_rgb(0.14, 0.18, 0.16),
+                (false, _) => Color::from_rgb(0.07, 0.09, 0.08),
+            })),
+            text_color: if active {
+                Color::WHITE
+            } else {
+                dim_color(accent, 0.6)
+            },
+            border: iced::Border {
+                radius: 2.0.into(),
+                width: 1.0,
+                color: dim_color(accent, 0.35),
+            },
+            ..Default::default()
+        })
+        .into()
+}
+
 fn voice_row<'a>(
     regs: &[u8],
     base: usize,
@@ -3,10 +3,12 @@
 //   Bar mode      — vertical bars per voice with peak-hold indicators,
 //                   coloured by SID chip (green / blue / orange / pink).
 //
-//   Scope mode    — oscilloscope lines drawn from a rolling history of
-//                   level samples.  Each voice gets its own lane; the
-//                   waveform scrolls left as new data arrives, giving a
-//                   natural attack / sustain / release envelope shape.
+//   Scope mode    — one oscilloscope lane per voice.  On reSID each lane
+//                   shows the voice's real waveform from the `voice_scope`
+//                   tap, trigger-aligned so it stands still.  On SIDLite
+//                   and hardware engines it falls back to a rolling history
+//                   of level samples, scrolling left as new data arrives,
+//                   giving a natural attack / sustain / release shape.
 //
 // Single-click anywhere on the widget to toggle between Bar and Scope modes.
 // Double-click to expand the visualiser to fill the whole window.
@@ -16,6 +18,7 @@ use iced::{mouse, Color, Element, Length, Point, Rectangle, Size, Theme};
 use std::time::Instant;
 
 use super::sid_panel::TrackerHistory;
+use crate::voice_mask::VoiceMask;
 
 /// References to the tracker state passed into the visualiser when mode == Tracker.
 pub struct TrackerRef<'a> {
@@ -44,6 +47,9 @@ const PEAK_DECAY: f32 = 0.985;
 /// show a faint slot rather than disappearing entirely.
 const MIN_BAR_HEIGHT: f32 = 0.02;
 
+/// Scope-mode: points per lane read from the per-voice waveform tap.
+const SCOPE_POINTS: usize = 320;
+
 /// Scope-mode: line stroke width in logical pixels.
 const SCOPE_LINE_WIDTH: f32 = 1.5;
 
@@ -239,6 +245,9 @@ pub struct Visualizer {
     scope_history: Vec<Vec<f32>>,
     /// Write cursor into each voice's ring buffer.
     scope_cursor: usize,
+    /// Trigger-aligned waveform per voice (−1.0–1.0) while a software
+    /// engine publishes one; replaces the level history when present.
+    scope_waves: Option<Vec<Vec<f32>>>,
 
     // ── Shared state ────────────────────────────────────────────────────────
     /// Number of SID chips in the current tune (1–4).
@@ -246,6 +255,8 @@ pub struct Visualizer {
     num_sids: usize,
     /// Current display mode (bar or scope).
     pub mode: VisMode,
+    /// Mute/solo state, used to dim silenced voices.
+    voice_mask: VoiceMask,
     /// iced canvas cache — cleared whenever data changes.
     cache: Cache,
     /// Separate cache for the full-screen expanded overlay.
@@ -262,8 +273,10 @@ impl Visualizer {
             peaks: vec![0.0; MAX_BARS],
             scope_history: vec![vec![0.0; SCOPE_HISTORY]; MAX_BARS],
             scope_cursor: 0,
+            scope_waves: None,
             num_sids: 1,
             mode: VisMode::Bars,
+            voice_mask: VoiceMask::default(),
             cache: Cache::new(),
             expanded_cache: Cache::new(),
         }
@@ -277,6 +290,13 @@ impl Visualizer {
         self.expanded_cache.clear();
     }
 
+    /// Update the mute/solo state shown on the bars and tracker columns.
+    pub fn set_voice_mask(&mut self, mask: VoiceMask) {
+        self.voice_mask = mask;
+        self.cache.clear();
+        self.expanded_cache.clear();
+    }
+
     /// Feed a new frame of voice levels from the player.
     /// `levels` is a flat slice: [SID1V1, SID1V2, SID1V3, SID2V1, …].
     /// Values are expected in the range 0.0–1.0.
@@ -301,7 +321,15 @@ impl Visualizer {
         }
         // Advance the shared write cursor (all voices share the same timeline).
         self.scope_cursor = (self.scope_cursor + 1) % SCOPE_HISTORY;
+        self.scope_waves = if self.mode == VisMode::Scope {
+            crate::voice_scope::read(SCOPE_POINTS)
+        } else {
+            None
+        };
         self.cache.clear();
+        if self.scope_waves.is_some() {
+            self.expanded_cache.clear();
+        }
     }
 
     /// Reset all state to silence (call on Stop or track change).
@@ -312,6 +340,7 @@ impl Visualizer {
             lane.fill(0.0);
         }
         self.scope_cursor = 0;
+        self.scope_waves = None;
         self.num_sids = 1;
         self.cache.clear();
         self.expanded_cache.clear();
@@ -334,8 +363,18 @@ impl Visualizer {
         self.num_sids * 3
     }
 
+    /// Bar index under canvas-local `x` in the compact bar layout.
+    fn bar_at(&self, x: f32, width: f32) -> Option<usize> {
+        let n = self.bar_count();
+        let (bar_w, gap) = bar_geometry(width, n);
+        let i = (x / (bar_w + gap)) as usize;
+        (i < n).then_some(i)
+    }
+
     /// Compact 60 px strip for the track-info bar.
     /// Single click cycles Bars → Scope → Tracker; double-click expands full window.
+    /// In Bars and Tracker mode voices take clicks per
+    /// `sid_panel::voice_click`.
     pub fn view<'a>(&'a self, tracker: Option<TrackerRef<'a>>) -> Element<'a, super::Message> {
         Canvas::new(VisProg {
             vis: self,
@@ -397,7 +436,13 @@ impl<'v, 'i> canvas::Program<super::Message> for VisProg<'v, 'i> {
                 match self.vis.mode {
                     VisMode::Tracker => {
                         if let Some(ref tr) = self.tracker {
-                            draw_tracker_expanded(tr, frame, bounds, self.info);
+                            draw_tracker_expanded(
+                                tr,
+                                frame,
+                                bounds,
+                                self.info,
+                                self.vis.voice_mask,
+                            );
                         } else {
                             draw_expanded(self.vis, frame, bounds, self.info);
                         }
@@ -421,6 +466,7 @@ impl<'v, 'i> canvas::Program<super::Message> for VisProg<'v, 'i> {
                                 bounds,
                                 &tr.history.frames,
                                 tr.num_sids,
+                                self.vis.voice_mask,
                             );
                         } else {
                             draw_bars(self.vis, frame, bounds);
@@ -439,6 +485,24 @@ impl<'v, 'i> canvas::Program<super::Message> for VisProg<'v, 'i> {
         bounds: Rectangle,
         cursor: mouse::Cursor,
     ) -> Option<canvas::Action<super::Message>> {
+        if let canvas::Event::Mouse(mouse::Event::ButtonReleased(
+            btn @ (mouse::Button::Right | mouse::Button::Middle),
+        )) = event
+        {
+            if self.expanded {
+                return None;
+            }
+            let pos = cursor.position_in(bounds)?;
+            let vi = match self.vis.mode {
+                VisMode::Tracker => {
+                    let tr = self.tracker.as_ref()?;
+                    super::sid_panel::tracker_voice_at(pos.x, bounds.width, tr.num_sids)?
+                }
+                VisMode::Bars | VisMode::Karaoke => self.vis.bar_at(pos.x, bounds.width)?,
+                VisMode::Scope => return None,
+            };
+            return super::sid_panel::voice_click(*btn, vi).map(canvas::Action::publish);
+        }
         if let canvas::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) = event {
             if cursor.is_over(bounds) {
                 let now = Instant::now();
@@ -460,6 +524,13 @@ impl<'v, 'i> canvas::Program<super::Message> for VisProg<'v, 'i> {
 //  Small bar — Bars
 // ─────────────────────────────────────────────────────────────────────────────
 
+/// Width of one compact bar and the gap between bars for `n` voices.
+fn bar_geometry(width: f32, n: usize) -> (f32, f32) {
+    let gap = 2.0_f32;
+    let bar_w = ((width - gap * (n as f32 - 1.0)) / n as f32).max(4.0);
+    (bar_w, gap)
+}
+
 /// Draw the vertical bar chart with peak-hold indicators.
 fn draw_bars(vis: &Visualizer, frame: &mut Frame, bounds: Rectangle) {
     let n = vis.bar_count();
@@ -468,8 +539,7 @@ fn draw_bars(vis: &Visualizer, frame: &mut Frame, bounds: Rectangle) {
     }
     let w = bounds.width;
     let h = bounds.height;
-    let gap = 2.0_f32;
-    let bar_w = ((w - gap * (n as f32 - 1.0)) / n as f32).max(4.0);
+    let (bar_w, gap) = bar_geometry(w, n);
 
     frame.fill_rectangle(
         Point::ORIGIN,
@@ -480,7 +550,11 @@ fn draw_bars(vis: &Visualizer, frame: &mut Frame, bounds: Rectangle) {
     for i in 0..n {
         let x = i as f32 * (bar_w + gap);
         let level = vis.bars[i].clamp(0.0, 1.0);
-        let color = voice_color(i);
+        let color = if vis.voice_mask.is_audible(i / 3, i % 3) {
+            voice_color(i)
+        } else {
+            Color::from_rgb(0.25, 0.25, 0.28)
+        };
         let min_h = MIN_BAR_HEIGHT * (h - 4.0);
         frame.fill_rectangle(
             Point::new(x, h - 2.0 - min_h),
@@ -561,16 +635,14 @@ fn draw_scope(vis: &Visualizer, frame: &mut Frame, bounds: Rectangle) {
             },
         );
 
+        let trace = scope_trace(vis, i);
         let path = Path::new(|b| {
-            for s in 0..SCOPE_HISTORY {
-                let level =
-                    vis.scope_history[i][(vis.scope_cursor + s) % SCOPE_HISTORY].clamp(0.0, 1.0);
-                let x = (s as f32 / (SCOPE_HISTORY - 1) as f32) * w;
-                let y = lane_mid - level * amplitude;
+            for (s, &(t, d)) in trace.iter().enumerate() {
+                let p = Point::new(t * w, lane_mid - d * amplitude);
                 if s == 0 {
-                    b.move_to(Point::new(x, y));
+                    b.move_to(p);
                 } else {
-                    b.line_to(Point::new(x, y));
+                    b.line_to(p);
                 }
             }
         });
@@ -591,6 +663,26 @@ fn draw_scope(vis: &Visualizer, frame: &mut Frame, bounds: Rectangle) {
     draw_mode_hint(frame, bounds);
 }
 
+/// Lane `i` as (x fraction 0–1, deflection −1–1) points: the voice's
+/// real waveform when the engine publishes one, else its level history.
+fn scope_trace(vis: &Visualizer, i: usize) -> Vec<(f32, f32)> {
+    if let Some(ref waves) = vis.scope_waves {
+        let wave = waves.get(i).map(Vec::as_slice).unwrap_or(&[]);
+        return (0..SCOPE_POINTS)
+            .map(|s| {
+                let t = s as f32 / (SCOPE_POINTS - 1) as f32;
+                (t, wave.get(s).copied().unwrap_or(0.0).clamp(-1.0, 1.0))
+            })
+            .collect();
+    }
+    (0..SCOPE_HISTORY)
+        .map(|s| {
+            let level = vis.scope_history[i][(vis.scope_cursor + s) % SCOPE_HISTORY];
+            (s as f32 / (SCOPE_HISTORY - 1) as f32, level.clamp(0.0, 1.0))
+        })
+        .collect()
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 //  Expanded full-window view
 // ─────────────────────────────────────────────────────────────────────────────
@@ -924,6 +1016,7 @@ fn draw_tracker_expanded(
     frame: &mut Frame,
     bounds: Rectangle,
     info: Option<&ExpandedInfo>,
+    mask: VoiceMask,
 ) {
     let w = bounds.width;
     let h = bounds.height;
@@ -992,7 +1085,13 @@ fn draw_tracker_expanded(
         height: (h - footer_h).max(40.0),
     };
 
-    super::sid_panel::paint_tracker_compact(frame, tracker_bounds, &tr.history.frames, tr.num_sids);
+    super::sid_panel::paint_tracker_compact(
+        frame,
+        tracker_bounds,
+        &tr.history.frames,
+        tr.num_sids,
+        mask,
+    );
 
     if let Some(info) = info {
         let foot_y = h - footer_h;
@@ -1358,6 +1457,7 @@ fn draw_scope_expanded(vis: &Visualizer, frame: &mut Frame, bounds: Rectangle) {
         );
 
         // 3-pass phosphor: wide+faint → medium → sharp+bright
+        let trace = scope_trace(vis, i);
         for pass in 0..3_u8 {
             let (lw, alpha) = match pass {
                 0 => (7.0_f32, 0.05_f32),
@@ -1365,15 +1465,12 @@ fn draw_scope_expanded(vis: &Visualizer, frame: &mut Frame, bounds: Rectangle) {
                 _ => (1.2, 0.92),
             };
             let path = Path::new(|b| {
-                for s in 0..SCOPE_HISTORY {
-                    let level = vis.scope_history[i][(vis.scope_cursor + s) % SCOPE_HISTORY]
-                        .clamp(0.0, 1.0);
-                    let x = x0 + (s as f32 / (SCOPE_HISTORY - 1) as f32) * w;
-                    let y = lane_mid - level * amplitude;
+                for (s, &(t, d)) in trace.iter().enumerate() {
+                    let p = Point::new(x0 + t * w, lane_mid - d * amplitude);
                     if s == 0 {
-                        b.move_to(Point::new(x, y));
+                        b.move_to(p);
                     } else {
-                        b.line_to(Point::new(x, y));
+                        b.line_to(p);
                     }
                 }
             });
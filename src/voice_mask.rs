@@ -0,0 +1,218 @@
+// Global per-voice mute/solo mask applied to every outgoing SID write.
+//
+// The player rewrites voice control registers ($D404/$D40B/$D412 per chip)
+// and the master-volume register ($D418) through `VoiceMask::filter` inside
+// `send_sid_writes`, so muting works identically for USB hardware, U64
+// network playback and both software emulators.  A muted voice keeps its
+// oscillator running (ring-mod/sync sources stay intact) but has its gate
+// and waveform bits cleared.  When all three voices of a chip are silenced
+// the volume nibble of $D418 is zeroed too, which also silences digis.
+//
+// Stored as a packed `u32` in an `AtomicU32` so the GUI and remote API can
+// flip bits without a round trip through the player command channel — the
+// same pattern as `audio_volume`.
+//
+// Does NOT affect the native U64 engine: there the C64 on the Ultimate runs
+// the tune itself and Phosphor never sees the SID writes.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Maximum number of voices covered by the mask (3 voices × 4 SIDs).
+pub const MAX_VOICES: usize = 12;
+
+const SID_REG_SIZE: u8 = 0x20;
+const SID_VOL_REG: u8 = 0x18;
+const VOICE_MASK_ALL: u16 = (1 << MAX_VOICES) - 1;
+
+/// Mute and solo bits, one per voice. Bit index is `sid * 3 + voice`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VoiceMask {
+    pub muted: u16,
+    pub soloed: u16,
+}
+
+impl VoiceMask {
+    fn bit(sid: usize, voice: usize) -> u16 {
+        if sid < 4 && voice < 3 {
+            1 << (sid * 3 + voice)
+        } else {
+            0
+        }
+    }
+
+    fn sid_bits(sid: usize) -> u16 {
+        if sid < 4 {
+            0b111 << (sid * 3)
+        } else {
+            0
+        }
+    }
+
+    /// True when nothing is muted or soloed.
+    pub fn is_clear(self) -> bool {
+        self.muted == 0 && self.soloed == 0
+    }
+
+    pub fn is_muted(self, sid: usize, voice: usize) -> bool {
+        self.muted & Self::bit(sid, voice) != 0
+    }
+
+    pub fn is_soloed(self, sid: usize, voice: usize) -> bool {
+        self.soloed & Self::bit(sid, voice) != 0
+    }
+
+    /// Effective state: a voice is heard unless it is muted, or another
+    /// voice is soloed and this one is not.
+    pub fn is_audible(self, sid: usize, voice: usize) -> bool {
+        let bit = Self::bit(sid, voice);
+        if bit == 0 {
+            return true;
+        }
+        if self.muted & bit != 0 {
+            return false;
+        }
+        self.soloed == 0 || self.soloed & bit != 0
+    }
+
+    pub fn toggle_mute(&mut self, sid: usize, voice: usize) {
+        self.muted ^= Self::bit(sid, voice);
+    }
+
+    pub fn toggle_solo(&mut self, sid: usize, voice: usize) {
+        self.soloed ^= Self::bit(sid, voice);
+    }
+
+    /// Mute or unmute all three voices of a chip. If any voice of the chip
+    /// is currently unmuted the whole chip is muted, otherwise it is unmuted.
+    pub fn toggle_sid_mute(&mut self, sid: usize) {
+        let bits = Self::sid_bits(sid);
+        if self.muted & bits == bits {
+            self.muted &= !bits;
+        } else {
+            self.muted |= bits;
+        }
+    }
+
+    /// Solo or un-solo all three voices of a chip (same rule as mute).
+    pub fn toggle_sid_solo(&mut self, sid: usize) {
+        let bits = Self::sid_bits(sid);
+        if self.soloed & bits == bits {
+            self.soloed &= !bits;
+        } else {
+            self.soloed |= bits;
+        }
+    }
+
+    /// Rewrite a single SID register write according to the mask.
+    /// `reg` is in the player's flat register space (chip n at n*0x20).
+    pub fn filter(self, reg: u8, val: u8) -> u8 {
+        if self.is_clear() {
+            return val;
+        }
+        let sid = (reg / SID_REG_SIZE) as usize;
+        let local = reg % SID_REG_SIZE;
+        match local {
+            4 | 11 | 18 => {
+                if self.is_audible(sid, (local / 7) as usize) {
+                    val
+                } else {
+                    // Keep sync/ring/test, drop gate and waveform.
+                    val & 0x0E
+                }
+            }
+            SID_VOL_REG => {
+                if (0..3).any(|v| self.is_audible(sid, v)) {
+                    val
+                } else {
+                    val & 0xF0
+                }
+            }
+            _ => val,
+        }
+    }
+
+    fn to_bits(self) -> u32 {
+        (self.muted as u32) | ((self.soloed as u32) << 16)
+    }
+
+    fn from_bits(bits: u32) -> Self {
+        Self {
+            muted: (bits as u16) & VOICE_MASK_ALL,
+            soloed: ((bits >> 16) as u16) & VOICE_MASK_ALL,
+        }
+    }
+}
+
+static MASK_BITS: AtomicU32 = AtomicU32::new(0);
+
+/// Publish a new mask. Takes effect on the player's next frame.
+pub fn set(mask: VoiceMask) {
+    MASK_BITS.store(mask.to_bits(), Ordering::Relaxed);
+}
+
+/// Current mask. Read once per frame by the player thread.
+#[inline]
+pub fn current() -> VoiceMask {
+    VoiceMask::from_bits(MASK_BITS.load(Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clear_mask_passes_everything() {
+        let m = VoiceMask::default();
+        assert_eq!(m.filter(0x04, 0x41), 0x41);
+        assert_eq!(m.filter(0x18, 0x1F), 0x1F);
+    }
+
+    #[test]
+    fn muted_voice_loses_gate_and_waveform() {
+        let mut m = VoiceMask::default();
+        m.toggle_mute(1, 2); // SID2 voice 3 → $D432
+        assert_eq!(m.filter(0x20 + 18, 0x47), 0x06);
+        assert_eq!(m.filter(0x20 + 11, 0x41), 0x41);
+        assert_eq!(m.filter(18, 0x41), 0x41);
+        // Frequency of the muted voice is untouched.
+        assert_eq!(m.filter(0x20 + 14, 0x99), 0x99);
+    }
+
+    #[test]
+    fn solo_silences_other_voices() {
+        let mut m = VoiceMask::default();
+        m.toggle_solo(0, 1);
+        assert!(m.is_audible(0, 1));
+        assert!(!m.is_audible(0, 0));
+        assert!(!m.is_audible(1, 1));
+        // Mute beats solo.
+        m.toggle_mute(0, 1);
+        assert!(!m.is_audible(0, 1));
+    }
+
+    #[test]
+    fn volume_zeroed_only_when_whole_chip_silent() {
+        let mut m = VoiceMask::default();
+        m.toggle_mute(0, 0);
+        m.toggle_mute(0, 1);
+        assert_eq!(m.filter(0x18, 0x3F), 0x3F);
+        m.toggle_mute(0, 2);
+        assert_eq!(m.filter(0x18, 0x3F), 0x30);
+        assert_eq!(m.filter(0x38, 0x3F), 0x3F);
+    }
+
+    #[test]
+    fn sid_toggles_and_bit_roundtrip() {
+        let mut m = VoiceMask::default();
+        m.toggle_mute(2, 0);
+        m.toggle_sid_mute(2);
+        assert!((0..3).all(|v| m.is_muted(2, v)));
+        m.toggle_sid_mute(2);
+        assert!((0..3).all(|v| !m.is_muted(2, v)));
+        m.toggle_sid_solo(3);
+        assert_eq!(VoiceMask::from_bits(m.to_bits()), m);
+        // Out-of-range indices are ignored.
+        m.toggle_mute(4, 0);
+        assert_eq!(m.muted, 0);
+    }
+}
@@ -0,0 +1,227 @@
+// Per-voice oscilloscope tap for the software engines.
+//
+// An engine that can report each voice's oscillator and envelope output
+// (reSID, via its OSC3 / ENV3 readout — see `sid_emulated`) publishes one
+// point per output sample and voice: OSC × ENV centred on zero, what the
+// voice DAC sees before the filter and the mixer. Engines that can't
+// expose their voices (SIDLite, hardware) don't publish, and the
+// visualiser keeps its level-based scope for them.
+//
+// The points go into a global lock-free ring, one lane per voice, which
+// the visualiser's Scope mode reads with `read()` and trigger-aligns on
+// a rising zero crossing so periodic waveforms stand still. Design notes:
+//   - Single producer. The first live `VoiceScope` to publish owns the
+//     tap until it is dropped; a second one (the other half of an A/B
+//     mirror, which sees the same writes) stays quiet.
+//   - `wanted()` is only true while the GUI has read in the last second,
+//     so engines can skip the per-voice work with the scope hidden.
+//   - No owner means nothing is publishing: `read()` returns `None`.
+
+use std::sync::atomic::{AtomicI16, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use crate::voice_mask::MAX_VOICES;
+
+/// Points kept per voice lane — ~85 ms at 96 kHz.
+const RING_LEN: usize = 8192;
+
+/// Time shown across one scope lane.
+const WINDOW_MS: usize = 25;
+
+/// Publishing stops this long after the GUI's last `read()`.
+const WANT_MS: u64 = 1000;
+
+/// One scope point from a voice's 8-bit OSC and ENV readout: the
+/// waveform centred on zero, scaled by the envelope.
+pub fn voice_point(osc: u8, env: u8) -> i16 {
+    ((osc as i32 - 0x80) * env as i32) as i16
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Shared tap
+// ─────────────────────────────────────────────────────────────────────────────
+
+struct Tap {
+    /// `MAX_VOICES` lanes of `RING_LEN` points, lane-major.
+    samples: Box<[AtomicI16]>,
+    /// Points written per lane since the tap was created.
+    written: AtomicUsize,
+    /// Lanes the owner fills (3 per active chip).
+    voices: AtomicUsize,
+    sample_rate: AtomicU32,
+    /// Id of the publishing `VoiceScope`, 0 when none.
+    owner: AtomicU32,
+    /// Publish until this time, in ms since `epoch`; pushed forward by
+    /// every `read()`.
+    wanted_until: AtomicU64,
+    epoch: Instant,
+}
+
+impl Tap {
+    fn now_ms(&self) -> u64 {
+        self.epoch.elapsed().as_millis() as u64
+    }
+}
+
+fn tap() -> &'static Tap {
+    static TAP: OnceLock<Tap> = OnceLock::new();
+    TAP.get_or_init(|| Tap {
+        samples: (0..MAX_VOICES * RING_LEN)
+            .map(|_| AtomicI16::new(0))
+            .collect(),
+        written: AtomicUsize::new(0),
+        voices: AtomicUsize::new(0),
+        sample_rate: AtomicU32::new(48_000),
+        owner: AtomicU32::new(0),
+        wanted_until: AtomicU64::new(0),
+        epoch: Instant::now(),
+    })
+}
+
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Producer
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Publishing handle, owned by a live software engine.
+pub struct VoiceScope {
+    id: u32,
+    sample_rate: u32,
+}
+
+impl VoiceScope {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            sample_rate,
+        }
+    }
+
+    /// Withdraw what was published, so a stopped player doesn't leave
+    /// the last waveform frozen on screen.
+    pub fn reset(&mut self) {
+        let tap = tap();
+        if tap.owner.load(Ordering::Relaxed) == self.id {
+            tap.written.store(0, Ordering::Release);
+        }
+    }
+
+    /// True while the scope is on screen and this handle owns the tap.
+    pub fn wanted(&mut self) -> bool {
+        let tap = tap();
+        tap.now_ms() < tap.wanted_until.load(Ordering::Relaxed) && self.claim(tap)
+    }
+
+    /// Publish one point per voice, SID1 voice 1 first. Call only after
+    /// `wanted()` returned true.
+    pub fn push(&mut self, points: &[i16]) {
+        let tap = tap();
+        let voices = points.len().min(MAX_VOICES);
+        let slot = tap.written.load(Ordering::Relaxed);
+        for (v, &p) in points[..voices].iter().enumerate() {
+            tap.samples[v * RING_LEN + slot % RING_LEN].store(p, Ordering::Relaxed);
+        }
+        tap.voices.store(voices, Ordering::Relaxed);
+        tap.written.store(slot + 1, Ordering::Release);
+    }
+
+    fn claim(&self, tap: &Tap) -> bool {
+        match tap.owner.load(Ordering::Relaxed) {
+            id if id == self.id => true,
+            0 => {
+                let won = tap
+                    .owner
+                    .compare_exchange(0, self.id, Ordering::AcqRel, Ordering::Relaxed)
+                    .is_ok();
+                if won {
+                    tap.sample_rate.store(self.sample_rate, Ordering::Relaxed);
+                }
+                won
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Drop for VoiceScope {
+    fn drop(&mut self) {
+        let _ = tap()
+            .owner
+            .compare_exchange(self.id, 0, Ordering::AcqRel, Ordering::Relaxed);
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+//  Consumer
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// The latest `WINDOW_MS` of every published voice, trigger-aligned and
+/// resampled to `points` values in −1.0–1.0. `None` while no software
+/// engine is publishing (hardware playback, or the scope just opened).
+pub fn read(points: usize) -> Option<Vec<Vec<f32>>> {
+    let tap = tap();
+    tap.wanted_until
+        .store(tap.now_ms() + WANT_MS, Ordering::Relaxed);
+    if tap.owner.load(Ordering::Relaxed) == 0 || points == 0 {
+        return None;
+    }
+    let written = tap.written.load(Ordering::Acquire);
+    let rate = tap.sample_rate.load(Ordering::Relaxed) as usize;
+    let len = (rate * WINDOW_MS / 1000).clamp(points, RING_LEN / 4);
+    if written < len * 2 {
+        return None;
+    }
+
+    let voices = tap.voices.load(Ordering::Relaxed).min(MAX_VOICES);
+    let mut lane = vec![0i16; len * 2];
+    let waves = (0..voices)
+        .map(|v| {
+            for (i, s) in lane.iter_mut().enumerate() {
+                let slot = (written - len * 2 + i) % RING_LEN;
+                *s = tap.samples[v * RING_LEN + slot].load(Ordering::Relaxed);
+            }
+            let start = trigger(&lane, len);
+            (0..points)
+                .map(|p| lane[start + p * len / points] as f32 / 32768.0)
+                .collect()
+        })
+        .collect();
+    Some(waves)
+}
+
+/// Start of a `len`-point window in `buf` (2 × `len` long): the latest
+/// rising zero crossing that leaves a full window, else the newest data.
+fn trigger(buf: &[i16], len: usize) -> usize {
+    (1..=len)
+        .rev()
+        .find(|&s| buf[s - 1] < 0 && buf[s] >= 0)
+        .unwrap_or(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn voice_point_centres_the_waveform_and_scales_by_envelope() {
+        assert_eq!(voice_point(0x80, 0xFF), 0);
+        assert_eq!(voice_point(0xFF, 0xFF), 127 * 255);
+        assert_eq!(voice_point(0x00, 0xFF), -128 * 255);
+        assert_eq!(voice_point(0xFF, 0), 0);
+    }
+
+    #[test]
+    fn trigger_locks_onto_the_latest_rising_crossing() {
+        // Sawtooth, 100 points per period.
+        let buf: Vec<i16> = (0..800)
+            .map(|i| voice_point((i % 100 * 255 / 99) as u8, 0xFF))
+            .collect();
+        let start = trigger(&buf, 400);
+        assert!(start <= 400);
+        assert!(buf[start - 1] < 0 && buf[start] >= 0);
+        // Flat input has no crossing: show the newest data.
+        assert_eq!(trigger(&[0; 20], 10), 10);
+    }
+}